@@ -0,0 +1,275 @@
+// src/bang.rs
+
+//! DuckDuckGo風のbang (`!g rust`、`rust !gh` など) でクエリから検索先を選ぶモジュール
+//!
+//! クエリの先頭または末尾の `!名前` をbangとして扱い、bangの表で検索先を決めます。
+//! 表の値はエンジン名 (`google` など、[`EngineType`] の名前) またはURLテンプレート
+//! (`https://...` で始まり、`{query}` をURLエンコードしたクエリに置き換えます) です。
+//!
+//! 組み込みのbangに加えて、設定ファイルの `[bangs]` で追加・上書きできます。
+//! 設定ファイルの読み込み時に [`validate`] で検査し、未知のエンジン名などはエラーになります。
+//! ```toml
+//! [bangs]
+//! gh = "github"
+//! crates = "crates"
+//! mdn = "https://developer.mozilla.org/ja/search?q={query}"
+//! ```
+//!
+//! ```
+//! use std::collections::BTreeMap;
+//! use www_search::EngineType;
+//! use www_search::bang::{Route, route_with};
+//!
+//! let custom = BTreeMap::from([("rs".to_string(), "docsrs".to_string())]);
+//! assert_eq!(
+//!     route_with("tokio spawn !rs", &custom),
+//!     Some(Route::Engine {
+//!         engine: EngineType::DocsRs,
+//!         query: "tokio spawn".to_string(),
+//!     })
+//! );
+//! ```
+
+use std::collections::BTreeMap;
+
+use crate::{EngineType, SearchData, config};
+
+/// URLテンプレートのクエリを置き換える文字列
+pub const QUERY_PLACEHOLDER: &str = "{query}";
+
+/// 組み込みのbang (名前, エンジン名またはURLテンプレート)
+const BUILTIN: &[(&str, &str)] = &[
+    ("g", "google"),
+    ("google", "google"),
+    ("ddg", "duckduckgo"),
+    ("d", "duckduckgo"),
+    ("brave", "brave"),
+    ("sx", "searxng"),
+    ("searx", "searxng"),
+    ("w", "wikipedia"),
+    ("wiki", "wikipedia"),
+    ("yj", "yahoo_japan"),
+    ("goo", "goo"),
+    ("gh", "github"),
+    ("github", "github"),
+    ("so", "stackexchange"),
+    ("se", "stackexchange"),
+    ("crates", "crates"),
+    ("docs", "docsrs"),
+    ("docsrs", "docsrs"),
+    ("arxiv", "arxiv"),
+    ("crossref", "crossref"),
+    ("local", "local"),
+    ("yt", "https://www.youtube.com/results?search_query={query}"),
+    (
+        "mdn",
+        "https://developer.mozilla.org/en-US/search?q={query}",
+    ),
+    ("rust", "https://doc.rust-lang.org/std/?search={query}"),
+    ("npm", "https://www.npmjs.com/search?q={query}"),
+    ("pypi", "https://pypi.org/search/?q={query}"),
+];
+
+/// bangの解決結果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Route {
+    /// 指定したエンジンで、bangを除いたクエリを検索する
+    Engine { engine: EngineType, query: String },
+    /// URLテンプレートを展開したページを開く
+    Url {
+        bang: String,
+        query: String,
+        url: String,
+    },
+}
+
+impl Route {
+    /// URLテンプレートのbangを、そのページを指す1件の検索結果に変換します (エンジンのbangは `None`)。
+    ///
+    /// `metadata` の `bang` にbangの名前を格納します。
+    pub fn to_result(&self) -> Option<SearchData> {
+        let Route::Url { bang, query, url } = self else {
+            return None;
+        };
+        Some(SearchData {
+            title: format!("!{} {}", bang, query).trim_end().to_string(),
+            url: url.clone(),
+            description: String::new(),
+            metadata: BTreeMap::from([("bang".to_string(), bang.clone())]),
+        })
+    }
+}
+
+/// 現在の設定 (組み込みのbangと `[bangs]`) でクエリのbangを解決します。
+///
+/// bangを含まない、または未知のbangの場合は `None` を返します (クエリはそのまま検索します)。
+pub fn route(query: &str) -> Option<Route> {
+    route_with(query, &config::current().bangs)
+}
+
+/// 追加のbangの表 (`name` → エンジン名またはURLテンプレート) を指定してクエリのbangを解決します。
+///
+/// `custom` の値は組み込みのbangより優先します。
+pub fn route_with(query: &str, custom: &BTreeMap<String, String>) -> Option<Route> {
+    let (bang, rest) = split(query)?;
+    let target = custom
+        .iter()
+        .find(|(name, _)| name.trim_start_matches('!').eq_ignore_ascii_case(&bang))
+        .map(|(_, target)| target.as_str())
+        .or_else(|| {
+            BUILTIN
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(&bang))
+                .map(|(_, target)| *target)
+        })?;
+    if target.starts_with("http://") || target.starts_with("https://") {
+        let url = target.replace(QUERY_PLACEHOLDER, &urlencoding::encode(&rest));
+        return Some(Route::Url {
+            bang,
+            query: rest,
+            url,
+        });
+    }
+    let engine = target.parse().ok()?;
+    Some(Route::Engine {
+        engine,
+        query: rest,
+    })
+}
+
+/// `[bangs]` の表を検査し、名前に使えない文字を含むbangや、エンジン名でもURLテンプレートでもない値を報告します。
+///
+/// すべての誤りを `; ` で区切った1つのエラーメッセージにまとめて返します。
+pub fn validate(custom: &BTreeMap<String, String>) -> Result<(), String> {
+    let errors: Vec<String> = custom
+        .iter()
+        .filter_map(|(name, target)| {
+            let bang = name.trim_start_matches('!');
+            if !is_name(bang) {
+                return Some(format!("invalid bang name `{}`", name));
+            }
+            if target.starts_with("http://") || target.starts_with("https://") {
+                return None;
+            }
+            target
+                .parse::<EngineType>()
+                .err()
+                .map(|e| format!("bang `!{}`: {}", bang, e))
+        })
+        .collect();
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors.join("; "))
+    }
+}
+
+/// 利用できるbangの一覧 (名前, エンジン名またはURLテンプレート) を返します。
+pub fn list() -> Vec<(String, String)> {
+    let mut bangs: BTreeMap<String, String> = BUILTIN
+        .iter()
+        .map(|(name, target)| (name.to_string(), target.to_string()))
+        .collect();
+    for (name, target) in &config::current().bangs {
+        bangs.insert(name.trim_start_matches('!').to_lowercase(), target.clone());
+    }
+    bangs.into_iter().collect()
+}
+
+/// クエリの先頭または末尾の `!名前` を取り出し、(名前, 残りのクエリ) を返す
+fn split(query: &str) -> Option<(String, String)> {
+    let words: Vec<&str> = query.split_whitespace().collect();
+    let is_bang = |word: &str| word.strip_prefix('!').is_some_and(is_name);
+    let index = if words.first().is_some_and(|w| is_bang(w)) {
+        0
+    } else if words.last().is_some_and(|w| is_bang(w)) {
+        words.len() - 1
+    } else {
+        return None;
+    };
+    let bang = words[index][1..].to_lowercase();
+    let rest = words
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| *i != index)
+        .map(|(_, w)| *w)
+        .collect::<Vec<_>>()
+        .join(" ");
+    Some((bang, rest))
+}
+
+/// bangの名前に使える文字列か (英数字と `_`, `-`, `.`)
+fn is_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_leading_and_trailing_bangs() {
+        assert_eq!(
+            split("!gh tokio runtime"),
+            Some(("gh".to_string(), "tokio runtime".to_string()))
+        );
+        assert_eq!(
+            split("tokio runtime !GH"),
+            Some(("gh".to_string(), "tokio runtime".to_string()))
+        );
+        assert_eq!(split("!w"), Some(("w".to_string(), String::new())));
+    }
+
+    #[test]
+    fn ignores_non_bang_words() {
+        assert_eq!(split("rust is great!"), None);
+        assert_eq!(split("rust ! tokio"), None);
+        assert_eq!(split("a !b c"), None);
+        assert_eq!(split("!what?! now"), None);
+        assert_eq!(split(""), None);
+    }
+
+    #[test]
+    fn routes_url_templates() {
+        assert_eq!(
+            route_with("!npm left pad", &BTreeMap::new()),
+            Some(Route::Url {
+                bang: "npm".to_string(),
+                query: "left pad".to_string(),
+                url: "https://www.npmjs.com/search?q=left%20pad".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn custom_bangs_override_builtin() {
+        let custom = BTreeMap::from([("!g".to_string(), "duckduckgo".to_string())]);
+        assert_eq!(
+            route_with("!g rust", &custom),
+            Some(Route::Engine {
+                engine: EngineType::DuckDuckGo,
+                query: "rust".to_string(),
+            })
+        );
+        assert_eq!(route_with("!unknown rust", &custom), None);
+    }
+
+    #[test]
+    fn validates_custom_bangs() {
+        let valid = BTreeMap::from([
+            ("!gh".to_string(), "github".to_string()),
+            (
+                "mdn".to_string(),
+                "https://developer.mozilla.org/?q={query}".to_string(),
+            ),
+        ]);
+        assert_eq!(validate(&valid), Ok(()));
+        let typo = BTreeMap::from([("gh".to_string(), "githb".to_string())]);
+        assert!(validate(&typo).is_err_and(|e| e.contains("!gh") && e.contains("githb")));
+        let bad_name = BTreeMap::from([("g h".to_string(), "google".to_string())]);
+        assert!(validate(&bad_name).is_err());
+    }
+}
@@ -0,0 +1,146 @@
+// src/bibtex.rs
+
+//! 学術系エンジン (arXiv, Crossref) の検索結果をBibTeX形式に変換するモジュール
+//!
+//! `SearchData::metadata` の `authors` (`; ` 区切り), `year`, `doi`, `venue`, `entry_type`,
+//! `arxiv_id` などからエントリを組み立てます。これらの値を持たない結果 (一般のウェブ検索結果) は
+//! `@misc` エントリとして、タイトルとURLのみを出力します。
+
+use std::collections::HashSet;
+
+use crate::SearchData;
+
+/// 検索結果のリストをBibTeX形式の文字列に変換します。
+///
+/// 引用キーが重複する場合は末尾に `a`, `b`, ..., `z`, `aa`, `ab`, ... を付けて区別します。
+pub fn to_bibtex(results: &[SearchData]) -> String {
+    let mut used = HashSet::new();
+    results
+        .iter()
+        .map(|data| {
+            let base = citation_key(data);
+            let mut key = base.clone();
+            let mut n = 0;
+            while !used.insert(key.clone()) {
+                key = format!("{}{}", base, suffix(n));
+                n += 1;
+            }
+            entry(data, &key)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// 0始まりの番号を `a`, ..., `z`, `aa`, `ab`, ... の接尾辞に変換する
+fn suffix(mut n: usize) -> String {
+    let mut letters = Vec::new();
+    loop {
+        letters.push(b'a' + (n % 26) as u8);
+        if n < 26 {
+            break;
+        }
+        n = n / 26 - 1;
+    }
+    letters.iter().rev().map(|&b| b as char).collect()
+}
+
+/// 1件の検索結果を、指定した引用キーのBibTeXエントリに変換します。
+pub fn entry(data: &SearchData, key: &str) -> String {
+    let metadata = &data.metadata;
+    let entry_type = metadata
+        .get("entry_type")
+        .map(String::as_str)
+        .unwrap_or("misc");
+    let mut fields: Vec<(&str, String)> = vec![("title", format!("{{{}}}", escape(&data.title)))];
+    if let Some(authors) = metadata.get("authors") {
+        let authors: Vec<String> = authors.split("; ").map(escape).collect();
+        fields.push(("author", authors.join(" and ")));
+    }
+    if let Some(year) = metadata.get("year") {
+        fields.push(("year", year.clone()));
+    }
+    if let Some(venue) = metadata.get("venue").filter(|v| v.as_str() != "arXiv") {
+        let field = match entry_type {
+            "article" => "journal",
+            "inproceedings" | "incollection" => "booktitle",
+            _ => "howpublished",
+        };
+        fields.push((field, escape(venue)));
+    }
+    for (field, key) in [
+        ("publisher", "publisher"),
+        ("volume", "volume"),
+        ("number", "issue"),
+        ("pages", "pages"),
+    ] {
+        if let Some(value) = metadata.get(key) {
+            // ページ範囲はBibTeXの慣習に従い `--` でつなぐ
+            let value = if field == "pages" {
+                value.replace('-', "--")
+            } else {
+                value.clone()
+            };
+            fields.push((field, escape(&value)));
+        }
+    }
+    if let Some(doi) = metadata.get("doi") {
+        fields.push(("doi", escape(doi)));
+    }
+    if let Some(arxiv_id) = metadata.get("arxiv_id") {
+        fields.push(("eprint", escape(arxiv_id)));
+        fields.push(("archivePrefix", "arXiv".to_string()));
+        if let Some(category) = metadata.get("primary_category") {
+            fields.push(("primaryClass", escape(category)));
+        }
+    }
+    fields.push(("url", data.url.clone()));
+
+    let body: Vec<String> = fields
+        .into_iter()
+        .map(|(name, value)| format!("  {} = {{{}}}", name, value))
+        .collect();
+    format!("@{}{{{},\n{}\n}}\n", entry_type, key, body.join(",\n"))
+}
+
+/// 引用キー (`<第一著者の姓><年><タイトルの最初の単語>`、例: `vaswani2017attention`) を生成します。
+fn citation_key(data: &SearchData) -> String {
+    let author = data
+        .metadata
+        .get("authors")
+        .and_then(|a| a.split("; ").next())
+        .and_then(|a| a.split_whitespace().last())
+        .unwrap_or("");
+    let year = data.metadata.get("year").map(String::as_str).unwrap_or("");
+    // 冠詞などの短い単語を飛ばして、タイトルの最初の意味のある単語を使う
+    let word = data
+        .title
+        .split(|c: char| !c.is_alphanumeric())
+        .find(|w| w.len() > 3)
+        .unwrap_or("");
+    let key: String = format!("{}{}{}", author, year, word)
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_lowercase();
+    if key.is_empty() {
+        "ref".to_string()
+    } else {
+        key
+    }
+}
+
+/// BibTeXで特別な意味を持つ文字をエスケープする
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' | '%' | '$' | '#' | '_' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '{' | '}' => {}
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
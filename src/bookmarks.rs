@@ -0,0 +1,407 @@
+// src/bookmarks.rs
+
+//! 検索結果のブックマークと「あとで読む」リストを管理するモジュール
+//!
+//! ブックマークは `[bookmarks] path` (未指定なら `$XDG_DATA_HOME/www-search/bookmarks.json`)
+//! にJSONの配列として保存します。「あとで読む」に追加したページは閲覧したMarkdownを
+//! `[bookmarks] reading_dir` (未指定なら `$XDG_DATA_HOME/www-search/reading`) に
+//! `<番号>.md` として保存し、オフラインでも読めるようにします。
+//!
+//! ブックマークにはタグを付けられ、一覧・検索のほか、Netscape形式のHTML (ブラウザで
+//! インポート可能)、Markdown、JSONで書き出せます。
+//!
+//! ```ignore
+//! use www_search::bookmarks::{self, ExportFormat};
+//!
+//! let bookmark = bookmarks::add(&result, &bookmarks::parse_tags("rust,async"), "tokio")?;
+//! let html = bookmarks::export(&bookmarks::load()?, ExportFormat::Html);
+//! ```
+
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{SearchData, config, search};
+
+/// ブックマーク
+///
+/// - `id`: ブックマークの番号 (1始まり。削除しても他の番号は変わりません)
+/// - `added`: 追加した日時 (UTCのISO 8601形式)
+/// - `url`, `title`, `description`: 検索結果のURL・タイトル・説明
+/// - `tags`: タグ
+/// - `query`: ブックマークした検索のクエリ
+/// - `saved`: 「あとで読む」に保存したMarkdownのファイル名 (`reading_dir` からの相対パス)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Bookmark {
+    pub id: u64,
+    pub added: String,
+    pub url: String,
+    pub title: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub query: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub saved: Option<String>,
+}
+
+impl Bookmark {
+    /// 「あとで読む」リストに含まれるか (閲覧したMarkdownを保存しているか)
+    pub fn is_saved(&self) -> bool {
+        self.saved.is_some()
+    }
+
+    /// タグを持つか (大文字・小文字を区別しない)
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag))
+    }
+
+    /// タイトル・URL・説明・タグ・クエリが文字列を含むか (大文字・小文字を区別しない)
+    pub fn matches(&self, term: &str) -> bool {
+        let term = term.to_lowercase();
+        let contains = |s: &str| s.to_lowercase().contains(&term);
+        contains(&self.title)
+            || contains(&self.url)
+            || contains(&self.description)
+            || contains(&self.query)
+            || self.tags.iter().any(|t| contains(t))
+    }
+}
+
+/// ブックマークの書き出し形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// Netscape形式のブックマークHTML (各ブラウザでインポートできます)
+    Html,
+    /// Markdownのリスト
+    Markdown,
+    /// JSONの配列
+    Json,
+}
+
+impl FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "html" | "netscape" => Ok(ExportFormat::Html),
+            "markdown" | "md" => Ok(ExportFormat::Markdown),
+            "json" => Ok(ExportFormat::Json),
+            _ => Err(format!(
+                "Unknown bookmark export format: {} (html, markdown, json)",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for ExportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            ExportFormat::Html => "html",
+            ExportFormat::Markdown => "markdown",
+            ExportFormat::Json => "json",
+        })
+    }
+}
+
+/// ブックマークファイルのパス。設定がなく、ホームディレクトリも分からない場合は `None` を返します。
+pub fn path() -> Option<PathBuf> {
+    if let Some(path) = config::current().bookmarks.path.clone() {
+        return Some(path);
+    }
+    Some(config::data_dir()?.join("bookmarks.json"))
+}
+
+/// 「あとで読む」に保存したMarkdownを置くディレクトリ
+pub fn reading_dir() -> Option<PathBuf> {
+    if let Some(dir) = config::current().bookmarks.reading_dir.clone() {
+        return Some(dir);
+    }
+    Some(config::data_dir()?.join("reading"))
+}
+
+/// 保存されているブックマークを追加した順に返します。ファイルがなければ空のリストを返します。
+pub fn load() -> Result<Vec<Bookmark>, String> {
+    let Some(path) = path() else {
+        return Ok(Vec::new());
+    };
+    let text = match std::fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+    };
+    serde_json::from_str(&text).map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+}
+
+/// 検索結果をブックマークします。
+///
+/// 同じURLのブックマークがすでにある場合は、タグを追加して既存のブックマークを返します。
+pub fn add(result: &SearchData, tags: &[String], query: &str) -> Result<Bookmark, String> {
+    let mut bookmarks = load()?;
+    let bookmark = upsert(&mut bookmarks, result, tags, query).clone();
+    store(&bookmarks)?;
+    Ok(bookmark)
+}
+
+/// 検索結果をブックマークし、閲覧したページのMarkdownを「あとで読む」リストに保存します。
+pub fn save_for_reading(
+    result: &SearchData,
+    markdown: &str,
+    tags: &[String],
+    query: &str,
+) -> Result<Bookmark, String> {
+    let dir = reading_dir().ok_or("Could not determine the reading list directory")?;
+    let mut bookmarks = load()?;
+    let bookmark = upsert(&mut bookmarks, result, tags, query);
+    let file = format!("{}.md", bookmark.id);
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let content = format!(
+        "# {}\n\n<{}>\n\n{}\n",
+        bookmark.title,
+        bookmark.url,
+        markdown.trim_end()
+    );
+    std::fs::write(dir.join(&file), content)
+        .map_err(|e| format!("Failed to write {}: {}", dir.join(&file).display(), e))?;
+    bookmark.saved = Some(file);
+    let bookmark = bookmark.clone();
+    store(&bookmarks)?;
+    Ok(bookmark)
+}
+
+/// 「あとで読む」に保存したMarkdownを読み込みます。保存していなければ `None` を返します。
+pub fn read(bookmark: &Bookmark) -> Result<Option<String>, String> {
+    let (Some(file), Some(dir)) = (&bookmark.saved, reading_dir()) else {
+        return Ok(None);
+    };
+    let path = dir.join(file);
+    std::fs::read_to_string(&path)
+        .map(Some)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+}
+
+/// 番号のブックマークを返します。
+pub fn get(id: u64) -> Result<Bookmark, String> {
+    load()?
+        .into_iter()
+        .find(|b| b.id == id)
+        .ok_or_else(|| format!("No bookmark #{}", id))
+}
+
+/// タイトル・URL・説明・タグ・クエリ、または「あとで読む」に保存した本文に文字列を含む
+/// ブックマークを返します。
+pub fn search(term: &str) -> Result<Vec<Bookmark>, String> {
+    let lowered = term.to_lowercase();
+    let mut found = Vec::new();
+    for bookmark in load()? {
+        let in_body = || {
+            read(&bookmark)
+                .ok()
+                .flatten()
+                .is_some_and(|body| body.to_lowercase().contains(&lowered))
+        };
+        if bookmark.matches(term) || in_body() {
+            found.push(bookmark);
+        }
+    }
+    Ok(found)
+}
+
+/// ブックマークのタグを変更します。
+///
+/// `-` で始まるタグは削除し、それ以外のタグは追加します。
+pub fn tag(id: u64, tags: &[String]) -> Result<Bookmark, String> {
+    let mut bookmarks = load()?;
+    let bookmark = bookmarks
+        .iter_mut()
+        .find(|b| b.id == id)
+        .ok_or_else(|| format!("No bookmark #{}", id))?;
+    for tag in tags {
+        match tag.strip_prefix('-') {
+            Some(removed) => bookmark.tags.retain(|t| !t.eq_ignore_ascii_case(removed)),
+            None => add_tag(&mut bookmark.tags, tag),
+        }
+    }
+    let bookmark = bookmark.clone();
+    store(&bookmarks)?;
+    Ok(bookmark)
+}
+
+/// ブックマークを削除します。「あとで読む」に保存したMarkdownも削除します。
+pub fn remove(id: u64) -> Result<Bookmark, String> {
+    let mut bookmarks = load()?;
+    let index = bookmarks
+        .iter()
+        .position(|b| b.id == id)
+        .ok_or_else(|| format!("No bookmark #{}", id))?;
+    let bookmark = bookmarks.remove(index);
+    if let (Some(file), Some(dir)) = (&bookmark.saved, reading_dir()) {
+        match std::fs::remove_file(dir.join(file)) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => {
+                return Err(format!(
+                    "Failed to remove {}: {}",
+                    dir.join(file).display(),
+                    e
+                ));
+            }
+        }
+    }
+    store(&bookmarks)?;
+    Ok(bookmark)
+}
+
+/// `rust,async` や `#rust #async` 形式のタグ指定をタグのリストに変換します。
+pub fn parse_tags(input: &str) -> Vec<String> {
+    let mut tags = Vec::new();
+    for tag in input.split([',', ' ', '\t']) {
+        add_tag(&mut tags, tag);
+    }
+    tags
+}
+
+/// ブックマークを指定の形式で書き出します。
+pub fn export(bookmarks: &[Bookmark], format: ExportFormat) -> String {
+    match format {
+        ExportFormat::Html => to_netscape_html(bookmarks),
+        ExportFormat::Markdown => to_markdown(bookmarks),
+        ExportFormat::Json => serde_json::to_string_pretty(bookmarks).unwrap_or_default(),
+    }
+}
+
+/// 同じURLのブックマークがあればタグを追加し、なければ新しいブックマークを追加する
+fn upsert<'a>(
+    bookmarks: &'a mut Vec<Bookmark>,
+    result: &SearchData,
+    tags: &[String],
+    query: &str,
+) -> &'a mut Bookmark {
+    let index = match bookmarks.iter().position(|b| b.url == result.url) {
+        Some(index) => index,
+        None => {
+            let id = bookmarks.iter().map(|b| b.id).max().unwrap_or(0) + 1;
+            bookmarks.push(Bookmark {
+                id,
+                added: search::now_iso(),
+                url: result.url.clone(),
+                title: result.title.clone(),
+                description: result.description.clone(),
+                tags: Vec::new(),
+                query: query.to_string(),
+                saved: None,
+            });
+            bookmarks.len() - 1
+        }
+    };
+    let bookmark = &mut bookmarks[index];
+    for tag in tags {
+        add_tag(&mut bookmark.tags, tag);
+    }
+    bookmark
+}
+
+/// 先頭の `#` と前後の空白を除いてタグを追加する (空のタグと重複は追加しない)
+fn add_tag(tags: &mut Vec<String>, tag: &str) {
+    let tag = tag.trim().trim_start_matches('#');
+    if !tag.is_empty() && !tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+        tags.push(tag.to_string());
+    }
+}
+
+/// ブックマークをファイルに書き込む
+fn store(bookmarks: &[Bookmark]) -> Result<(), String> {
+    let path = path().ok_or("Could not determine the bookmarks file path")?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    let json = serde_json::to_string_pretty(bookmarks)
+        .map_err(|e| format!("Failed to serialize bookmarks: {}", e))?;
+    config::write_atomic(&path, &(json + "\n"))
+}
+
+/// Netscape形式のブックマークHTML
+fn to_netscape_html(bookmarks: &[Bookmark]) -> String {
+    let mut html = String::from(
+        "<!DOCTYPE NETSCAPE-Bookmark-file-1>\n\
+         <!-- This is an automatically generated file.\n     \
+         It will be read and overwritten.\n     \
+         DO NOT EDIT! -->\n\
+         <META HTTP-EQUIV=\"Content-Type\" CONTENT=\"text/html; charset=UTF-8\">\n\
+         <TITLE>Bookmarks</TITLE>\n\
+         <H1>Bookmarks</H1>\n\
+         <DL><p>\n",
+    );
+    for bookmark in bookmarks {
+        let added = search::parse_unix_time(&bookmark.added).unwrap_or_default();
+        html.push_str(&format!(
+            "    <DT><A HREF=\"{}\" ADD_DATE=\"{}\"",
+            escape_html(&bookmark.url),
+            added
+        ));
+        if !bookmark.tags.is_empty() {
+            html.push_str(&format!(
+                " TAGS=\"{}\"",
+                escape_html(&bookmark.tags.join(","))
+            ));
+        }
+        html.push_str(&format!(">{}</A>\n", escape_html(&bookmark.title)));
+        if !bookmark.description.is_empty() {
+            html.push_str(&format!("    <DD>{}\n", escape_html(&bookmark.description)));
+        }
+    }
+    html.push_str("</DL><p>\n");
+    html
+}
+
+/// Markdownのリスト。タグは `#タグ`、「あとで読む」に保存したものは保存先のファイル名を付ける
+fn to_markdown(bookmarks: &[Bookmark]) -> String {
+    let mut markdown = String::from("# Bookmarks\n\n");
+    for bookmark in bookmarks {
+        let title = if bookmark.title.is_empty() {
+            &bookmark.url
+        } else {
+            &bookmark.title
+        };
+        markdown.push_str(&format!(
+            "- [{}](<{}>)",
+            single_line(title).replace('[', "\\[").replace(']', "\\]"),
+            bookmark
+                .url
+                .replace('<', "%3C")
+                .replace('>', "%3E")
+                .replace(' ', "%20")
+        ));
+        for tag in &bookmark.tags {
+            markdown.push_str(&format!(" `#{}`", tag));
+        }
+        if let Some(file) = &bookmark.saved {
+            markdown.push_str(&format!(" (saved: {})", file));
+        }
+        markdown.push('\n');
+        if !bookmark.description.is_empty() {
+            markdown.push_str(&format!("  > {}\n", single_line(&bookmark.description)));
+        }
+    }
+    markdown
+}
+
+/// 改行を含む連続する空白を1つにまとめる
+fn single_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// HTMLで特別な意味を持つ文字をエスケープする
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
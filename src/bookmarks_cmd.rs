@@ -0,0 +1,129 @@
+// src/bookmarks_cmd.rs
+
+//! `www-search bookmarks` サブコマンド
+//!
+//! - `bookmarks list [タグ]`: ブックマークを表示する (タグを指定するとそのタグのものだけ)
+//! - `bookmarks reading`: 「あとで読む」リストを表示する
+//! - `bookmarks search <文字列>`: タイトル・URL・タグ・保存した本文などに文字列を含むものを表示する
+//! - `bookmarks read <番号>`: 「あとで読む」に保存したMarkdownを表示する
+//! - `bookmarks tag <番号> <タグ>...`: タグを追加する (`-タグ` で削除)
+//! - `bookmarks remove <番号>`: ブックマークを削除する
+//! - `bookmarks export <html|markdown|json> [ファイル]`: 書き出す (ファイルを省略すると標準出力)
+
+use www_search::bookmarks::{self, Bookmark, ExportFormat};
+use www_search::config::OutputFormat;
+
+/// サブコマンドを実行します。
+pub fn run(
+    command: &str,
+    argument: &str,
+    format: OutputFormat,
+    limit: Option<usize>,
+) -> Result<(), String> {
+    match command {
+        "" | "list" => {
+            let bookmarks = bookmarks::load()?
+                .into_iter()
+                .filter(|b| argument.is_empty() || b.has_tag(argument.trim_start_matches('#')))
+                .collect();
+            print_bookmarks(bookmarks, format, limit);
+        }
+        "reading" => {
+            let bookmarks = bookmarks::load()?
+                .into_iter()
+                .filter(Bookmark::is_saved)
+                .collect();
+            print_bookmarks(bookmarks, format, limit);
+        }
+        "search" => {
+            if argument.is_empty() {
+                return Err("Usage: bookmarks search <text>".to_string());
+            }
+            print_bookmarks(bookmarks::search(argument)?, format, limit);
+        }
+        "read" => {
+            let bookmark = bookmarks::get(parse_id(argument, "read <number>")?)?;
+            match bookmarks::read(&bookmark)? {
+                Some(markdown) => println!("{}", markdown),
+                None => {
+                    return Err(format!(
+                        "Bookmark #{} is not in the reading list",
+                        bookmark.id
+                    ));
+                }
+            }
+        }
+        "tag" => {
+            let (id, tags) = argument.split_once(' ').unwrap_or((argument, ""));
+            let tags = bookmarks::parse_tags(tags);
+            if tags.is_empty() {
+                return Err("Usage: bookmarks tag <number> <tag>...".to_string());
+            }
+            let bookmark = bookmarks::tag(parse_id(id, "tag <number> <tag>...")?, &tags)?;
+            println!("[{}] tags: {}", bookmark.id, bookmark.tags.join(", "));
+        }
+        "remove" => {
+            let bookmark = bookmarks::remove(parse_id(argument, "remove <number>")?)?;
+            println!("Removed [{}] {}", bookmark.id, bookmark.title);
+        }
+        "export" => {
+            let (export_format, file) = argument.split_once(' ').unwrap_or((argument, ""));
+            if export_format.is_empty() {
+                return Err("Usage: bookmarks export <html|markdown|json> [file]".to_string());
+            }
+            let export_format: ExportFormat = export_format.parse()?;
+            let text = bookmarks::export(&bookmarks::load()?, export_format);
+            let file = file.trim();
+            if file.is_empty() {
+                print!("{}", text);
+            } else {
+                std::fs::write(file, text)
+                    .map_err(|e| format!("Failed to write {}: {}", file, e))?;
+                println!("Exported bookmarks to {}", file);
+            }
+        }
+        _ => {
+            return Err(format!(
+                "Unknown bookmarks command: {} (list, reading, search, read, tag, remove, export)",
+                command
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn parse_id(argument: &str, usage: &str) -> Result<u64, String> {
+    argument
+        .trim()
+        .parse()
+        .map_err(|_| format!("Usage: bookmarks {}", usage))
+}
+
+fn print_bookmarks(bookmarks: Vec<Bookmark>, format: OutputFormat, limit: Option<usize>) {
+    let bookmarks: Vec<Bookmark> = bookmarks
+        .into_iter()
+        .take(limit.unwrap_or(usize::MAX))
+        .collect();
+    if format == OutputFormat::Json {
+        println!("{}", bookmarks::export(&bookmarks, ExportFormat::Json));
+        return;
+    }
+    if bookmarks.is_empty() {
+        println!("No bookmarks.");
+        return;
+    }
+    for bookmark in bookmarks {
+        println!(
+            "[{}] {}{}\n    {}{}",
+            bookmark.id,
+            bookmark.title,
+            if bookmark.is_saved() { "  (saved)" } else { "" },
+            bookmark.url,
+            bookmark
+                .tags
+                .iter()
+                .map(|t| format!("  #{}", t))
+                .collect::<String>()
+        );
+    }
+}
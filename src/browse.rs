@@ -1,100 +1,247 @@
-use scraper::{Html, Selector};
+use scraper::{ElementRef, Html, Node, Selector};
+
+/// 取得したページのタイトルと本文
+///
+/// - `title`: HTMLの `<title>`、なければMarkdownの最初の見出し (どちらもなければ空)
+/// - `markdown`: 本文のMarkdown
+#[derive(Debug, Clone, Default)]
+pub struct Page {
+    pub title: String,
+    pub markdown: String,
+}
 
 /// 指定したURLのWebページ本文を取得し、Markdown形式で返す関数
+///
+/// Wikipedia (および設定したMediaWiki) の記事URLの場合は、parse APIで取得した本文を変換します。
 pub async fn fetch_and_markdown(url: &str) -> Result<String, String> {
-    let html = match reqwest::get(url).await {
-        Ok(resp) => match resp.text().await {
-            Ok(t) => t,
-            Err(e) => return Err(format!("Failed to get text: {}", e)),
-        },
+    fetch_page(url).await.map(|page| page.markdown)
+}
+
+#[allow(dead_code)]
+/// 指定したURLのWebページ本文を取得し、Markdown形式で返す関数
+pub fn fetch_and_markdown_sync(url: &str) -> Result<String, String> {
+    fetch_page_sync(url).map(|page| page.markdown)
+}
+
+/// 指定したURLのWebページを取得し、タイトルと本文のMarkdownを返します。
+pub async fn fetch_page(url: &str) -> Result<Page, String> {
+    if let Some(article) = crate::search::wikipedia::fetch_article(url).await {
+        return article.map(|markdown| page(None, markdown));
+    }
+    let client = crate::search::client(None, None)?;
+    // 相対リンクは、リダイレクト後の最終的なURLを基準に解決する
+    let (base, html) = match client.get(url).send().await {
+        Ok(resp) => {
+            let base = resp.url().clone();
+            match resp.text().await {
+                Ok(t) => (base, t),
+                Err(e) => return Err(format!("Failed to get text: {}", e)),
+            }
+        }
         Err(e) => return Err(format!("Failed to fetch URL: {}", e)),
     };
-    let document = Html::parse_document(&html);
-    // 本文抽出: <main> > <article> > <p> などを優先し、なければbody内の<p>を抽出
-    let mut markdown = String::new();
-    let selectors = [
-        "main article p", // 一般的な構造
-        "main p",
-        "article p",
-        "body p",
+    let markdown = html_to_markdown(&html, base.as_str())?;
+    Ok(page(Some(&html), markdown))
+}
+
+/// 指定したURLのWebページを同期で取得し、タイトルと本文のMarkdownを返します。
+pub fn fetch_page_sync(url: &str) -> Result<Page, String> {
+    if let Some(article) = crate::search::wikipedia::fetch_article_sync(url) {
+        return article.map(|markdown| page(None, markdown));
+    }
+    let client = crate::search::blocking_client(None, None)?;
+    let (base, html) = match client.get(url).send() {
+        Ok(resp) => {
+            let base = resp.url().clone();
+            match resp.text() {
+                Ok(t) => (base, t),
+                Err(e) => return Err(format!("Failed to get text: {}", e)),
+            }
+        }
+        Err(e) => return Err(format!("Failed to fetch URL: {}", e)),
+    };
+    let markdown = html_to_markdown(&html, base.as_str())?;
+    Ok(page(Some(&html), markdown))
+}
+
+/// HTMLの `<title>`、なければMarkdownの最初の見出しをタイトルにした `Page` を作る
+fn page(html: Option<&str>, markdown: String) -> Page {
+    let title = html
+        .and_then(|html| {
+            let document = Html::parse_document(html);
+            let selector = Selector::parse("title").unwrap();
+            document
+                .select(&selector)
+                .next()
+                .map(crate::search::element_text)
+        })
+        .filter(|title| !title.is_empty())
+        .or_else(|| {
+            markdown
+                .lines()
+                .find_map(|line| line.strip_prefix("# "))
+                .map(|title| title.trim().to_string())
+        })
+        .unwrap_or_default();
+    Page { title, markdown }
+}
+
+/// HTML文字列から本文を抽出し、Markdown形式に変換する関数
+///
+/// 見出し (`h1`〜`h6`) は `#` 形式、リスト項目は `- ` 形式、`<pre>` はコードブロックに変換します。
+/// 本文中のリンクは `base_url` を基準に絶対URLへ解決し、`[テキスト](<URL>)` 形式で出力します。
+/// `<br>` は行末の2つの空白による改行になります。
+pub fn html_to_markdown(html: &str, base_url: &str) -> Result<String, String> {
+    let document = Html::parse_document(html);
+    let base = url::Url::parse(base_url).ok();
+    // 本文抽出: <main> > <article> などを優先し、なければbody内のブロック要素を抽出
+    let containers = [
+        "main article", // 一般的な構造
+        "main",
+        "article",
+        "body",
     ];
-    for sel in selectors.iter() {
-        if let Ok(selector) = Selector::parse(sel) {
-            let mut found = false;
-            for p in document.select(&selector) {
-                let text = p.text().collect::<Vec<_>>().join("").trim().to_string();
-                if !text.is_empty() {
-                    markdown.push_str(&format!(
-                        "\n{}
-",
-                        text
-                    ));
-                    found = true;
+    let block_selector = Selector::parse("h1, h2, h3, h4, h5, h6, p, li, pre").unwrap();
+    for sel in containers.iter() {
+        let Ok(selector) = Selector::parse(sel) else {
+            continue;
+        };
+        let mut markdown = String::new();
+        let mut has_paragraph = false;
+        for container in document.select(&selector) {
+            for block in container.select(&block_selector) {
+                // 他のブロック要素の内側にある要素は、外側の要素でまとめて出力済み
+                if has_block_ancestor(block) {
+                    continue;
                 }
+                let name = block.value().name();
+                let line = match name {
+                    "pre" => {
+                        let code = block.text().collect::<String>();
+                        let code = code.trim_end();
+                        if code.trim().is_empty() {
+                            continue;
+                        }
+                        format!("```\n{}\n```", code)
+                    }
+                    _ => {
+                        let text = inline_markdown(block, base.as_ref());
+                        if text.is_empty() {
+                            continue;
+                        }
+                        match name {
+                            "li" => format!("- {}", text),
+                            "p" => {
+                                has_paragraph = true;
+                                text
+                            }
+                            h => {
+                                let level = h[1..].parse::<usize>().unwrap_or(1);
+                                format!("{} {}", "#".repeat(level), text)
+                            }
+                        }
+                    }
+                };
+                markdown.push_str(&format!("\n{}\n", line));
             }
-            if found {
-                return Ok(markdown.trim().to_string());
-            }
+        }
+        if has_paragraph {
+            return Ok(markdown.trim().to_string());
         }
     }
     // fallback: body全体のテキスト
-    if let Ok(selector) = Selector::parse("body") {
-        if let Some(body) = document.select(&selector).next() {
-            let text = body.text().collect::<Vec<_>>().join("").trim().to_string();
-            if !text.is_empty() {
-                return Ok(text);
-            }
+    if let Ok(selector) = Selector::parse("body")
+        && let Some(body) = document.select(&selector).next()
+    {
+        let text = body.text().collect::<Vec<_>>().join("").trim().to_string();
+        if !text.is_empty() {
+            return Ok(text);
         }
     }
     Err("No readable content found".to_string())
 }
 
-#[allow(dead_code)]
-/// 指定したURLのWebページ本文を取得し、Markdown形式で返す関数
-pub fn fetch_and_markdown_sync(url: &str) -> Result<String, String> {
-    let html = match reqwest::blocking::get(url) {
-        Ok(resp) => match resp.text() {
-            Ok(t) => t,
-            Err(e) => return Err(format!("Failed to get text: {}", e)),
-        },
-        Err(e) => return Err(format!("Failed to fetch URL: {}", e)),
-    };
-    let document = Html::parse_document(&html);
-    // 本文抽出: <main> > <article> > <p> などを優先し、なければbody内の<p>を抽出
-    let mut markdown = String::new();
-    let selectors = [
-        "main article p", // 一般的な構造
-        "main p",
-        "article p",
-        "body p",
-    ];
-    for sel in selectors.iter() {
-        if let Ok(selector) = Selector::parse(sel) {
-            let mut found = false;
-            for p in document.select(&selector) {
-                let text = p.text().collect::<Vec<_>>().join("").trim().to_string();
-                if !text.is_empty() {
-                    markdown.push_str(&format!(
-                        "\n{}
-",
-                        text
-                    ));
-                    found = true;
+/// 祖先にブロック要素 (見出し・段落・リスト項目・pre) があるかどうかを判定する
+fn has_block_ancestor(element: ElementRef) -> bool {
+    element.ancestors().filter_map(ElementRef::wrap).any(|e| {
+        matches!(
+            e.value().name(),
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "p" | "li" | "pre"
+        )
+    })
+}
+
+/// `<br>` の位置を表す文字 (HTMLの解析でNULは置き換えられるため、本文には現れない)
+const LINE_BREAK: char = '\0';
+
+/// ブロック要素内のインライン要素をMarkdownに変換し、空白を正規化して返す
+fn inline_markdown(element: ElementRef, base: Option<&url::Url>) -> String {
+    let mut out = String::new();
+    push_inline(element, base, &mut out);
+    out.split(LINE_BREAK)
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("  \n")
+}
+
+fn push_inline(element: ElementRef, base: Option<&url::Url>, out: &mut String) {
+    for child in element.children() {
+        match child.value() {
+            Node::Text(text) => out.push_str(text),
+            Node::Element(_) => {
+                let Some(child) = ElementRef::wrap(child) else {
+                    continue;
+                };
+                match child.value().name() {
+                    "script" | "style" | "noscript" => {}
+                    "br" => out.push(LINE_BREAK),
+                    "a" => {
+                        let mut text = String::new();
+                        push_inline(child, base, &mut text);
+                        let text = text
+                            .replace(LINE_BREAK, " ")
+                            .split_whitespace()
+                            .collect::<Vec<_>>()
+                            .join(" ");
+                        let href = child.value().attr("href").and_then(|href| match base {
+                            Some(base) => base.join(href).ok().map(|u| u.to_string()),
+                            None => Some(href.to_string()),
+                        });
+                        match href {
+                            Some(href)
+                                if !text.is_empty()
+                                    && (href.starts_with("http://")
+                                        || href.starts_with("https://")) =>
+                            {
+                                let href = href
+                                    .replace('<', "%3C")
+                                    .replace('>', "%3E")
+                                    .replace(' ', "%20");
+                                out.push_str(&format!("[{}](<{}>)", text, href));
+                            }
+                            _ => out.push_str(&text),
+                        }
+                    }
+                    "strong" | "b" => wrap_inline(child, base, out, "**"),
+                    "em" | "i" => wrap_inline(child, base, out, "*"),
+                    "code" => wrap_inline(child, base, out, "`"),
+                    _ => push_inline(child, base, out),
                 }
             }
-            if found {
-                return Ok(markdown.trim().to_string());
-            }
+            _ => {}
         }
     }
-    // fallback: body全体のテキスト
-    if let Ok(selector) = Selector::parse("body") {
-        if let Some(body) = document.select(&selector).next() {
-            let text = body.text().collect::<Vec<_>>().join("").trim().to_string();
-            if !text.is_empty() {
-                return Ok(text);
-            }
-        }
+}
+
+fn wrap_inline(element: ElementRef, base: Option<&url::Url>, out: &mut String, mark: &str) {
+    let mut text = String::new();
+    push_inline(element, base, &mut text);
+    let text = text.trim();
+    if text.is_empty() {
+        return;
     }
-    Err("No readable content found".to_string())
+    out.push_str(mark);
+    out.push_str(text);
+    out.push_str(mark);
 }
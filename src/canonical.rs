@@ -0,0 +1,484 @@
+// src/canonical.rs
+
+//! 検索結果のURLを正規化するモジュール
+//!
+//! 各エンジンの結果は [`canonicalize`] で次の処理をしてから返します。同じページを指すURLが
+//! 同じ文字列になります。複数エンジン・複数ページの結果の重複は、さらにパスの末尾の `/` を除いた
+//! [`dedup_key`] で判定します (リンク先の相対URLが変わらないように、結果のURL自体の `/` は残します)。
+//! 1. 既知のリダイレクト (Googleの `/url?q=`、DuckDuckGoの `/l/?uddg=`、Yahoo!の `RU=`、
+//!    gooの `url=`、Bingの `ck/a?u=`、Facebookの `l.php?u=` など) から本来のURLを取り出す
+//! 2. AMPのURL (AMPキャッシュ、Googleの `/amp/s/`、既知のサイトの末尾の `/amp`・`amp=1` など) を元のページのURLにする
+//! 3. トラッキング用のクエリパラメータ (`utm_*`, `fbclid`, `gclid` など) を除く
+//! 4. テキストフラグメント (`#:~:text=...`) を除く
+//! 5. スキーム・ホストを小文字にし、既定のポートを除く
+//!
+//! ```
+//! use www_search::canonical::canonicalize;
+//!
+//! assert_eq!(
+//!     canonicalize("https://www.google.com/url?q=https://Example.com/docs/%3Futm_source%3Dx&sa=U"),
+//!     "https://example.com/docs/"
+//! );
+//! ```
+
+use url::Url;
+
+use crate::SearchData;
+
+/// 除去するトラッキング用のクエリパラメータ (`utm_` で始まるものも除去します)
+const TRACKING_PARAMS: &[&str] = &[
+    "fbclid",
+    "gclid",
+    "gclsrc",
+    "dclid",
+    "gbraid",
+    "wbraid",
+    "msclkid",
+    "yclid",
+    "twclid",
+    "igshid",
+    "mc_cid",
+    "mc_eid",
+    "_ga",
+    "_gl",
+    "_hsenc",
+    "_hsmi",
+    "mkt_tok",
+    "oly_anon_id",
+    "oly_enc_id",
+    "vero_id",
+    "ref_src",
+    "srsltid",
+];
+
+/// 末尾の `/amp`・`.amp`・`.amp.html` のパスや `amp=1` などのパラメータでAMP版を配信している既知のサイト
+///
+/// その他のサイトの `/amp` (`npmjs.com/package/amp` など) はAMPと関係ない場合があるため変更しません。
+/// `amp.` で始まるホスト (`amp.example.com`) もAMP版として扱います。
+const AMP_SITES: &[&str] = &[
+    "bbc.co.uk",
+    "bbc.com",
+    "cnbc.com",
+    "cnn.com",
+    "independent.co.uk",
+    "nytimes.com",
+    "theguardian.com",
+    "washingtonpost.com",
+];
+
+/// AMPキャッシュ・ビューアが付け加えるクエリパラメータ
+const AMP_VIEWER_PARAMS: &[&str] = &["amp", "usqp", "amp_js_v", "amp_gsa"];
+
+/// リダイレクトを解除するときの最大の入れ子の深さ
+const MAX_REDIRECTS: usize = 4;
+
+/// URLを正規化します。
+///
+/// `http`/`https` 以外のURLや、URLとして解釈できない文字列はそのまま返します。
+pub fn canonicalize(url: &str) -> String {
+    let Ok(mut parsed) = Url::parse(url.trim()) else {
+        return url.to_string();
+    };
+    for _ in 0..MAX_REDIRECTS {
+        match unwrap_redirect(&parsed).and_then(|u| Url::parse(&u).ok()) {
+            Some(target) => parsed = target,
+            None => break,
+        }
+    }
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return url.to_string();
+    }
+    if let Some(original) = de_amp(&parsed) {
+        parsed = original;
+    }
+    strip_tracking_params(&mut parsed);
+    strip_text_fragment(&mut parsed);
+    if let Some(host) = parsed
+        .host_str()
+        .map(|h| h.trim_end_matches('.').to_string())
+        && parsed.set_host(Some(&host)).is_err()
+    {
+        return url.to_string();
+    }
+    parsed.to_string()
+}
+
+/// 同じページかどうかを判定するためのキーを返します。
+///
+/// [`canonicalize`] したURLから、ルート以外のパスの末尾の `/` を除いたものです
+/// (`https://example.com/docs/` と `https://example.com/docs` は同じキーになります)。
+pub fn dedup_key(url: &str) -> String {
+    let canonical = canonicalize(url);
+    let Ok(mut parsed) = Url::parse(&canonical) else {
+        return canonical;
+    };
+    let path = parsed.path().to_string();
+    if path.len() > 1 && path.ends_with('/') {
+        parsed.set_path(path.trim_end_matches('/'));
+    }
+    parsed.to_string()
+}
+
+/// 検索結果の `url` と、画像検索の掲載ページのURL (`metadata` の `source_page`) を正規化します。
+pub fn canonicalize_result(mut result: SearchData) -> SearchData {
+    result.url = canonicalize(&result.url);
+    if let Some(page) = result.metadata.get_mut("source_page") {
+        *page = canonicalize(page);
+    }
+    result
+}
+
+/// 検索結果ページ内のリンク (`href`) を `base` のURLで解決し、正規化します。
+///
+/// 相対URL (`/url?q=...` など) のリダイレクトも解除します。解決できない場合は `None` を返します。
+pub fn resolve(base: &str, href: &str) -> Option<String> {
+    let url = Url::parse(base).and_then(|b| b.join(href)).ok()?;
+    Some(canonicalize(url.as_str()))
+}
+
+/// 既知のリダイレクトのURLであれば、リダイレクト先のURLを返します。
+pub fn unwrap_redirect(url: &Url) -> Option<String> {
+    let host = url.host_str()?.to_lowercase();
+    let path = url.path();
+    let param = |names: &[&str]| {
+        url.query_pairs()
+            .find(|(k, v)| names.contains(&k.as_ref()) && is_http(v))
+            .map(|(_, v)| v.into_owned())
+    };
+    let domain = |name: &str| is_domain(&host, name);
+    if is_google(&host) && path == "/url" {
+        return param(&["q", "url"]);
+    }
+    if domain("duckduckgo.com") && path == "/l/" {
+        return param(&["uddg"]);
+    }
+    if domain("goo.ne.jp") {
+        return param(&["url", "u"]);
+    }
+    if domain("yahoo.co.jp") || domain("yahoo.com") {
+        return yahoo_redirect(path);
+    }
+    if domain("bing.com") && path == "/ck/a" {
+        // `u=a1<Base64>` 形式
+        let encoded = url
+            .query_pairs()
+            .find(|(k, _)| k == "u")
+            .map(|(_, v)| v.into_owned())?;
+        return decode_base64(encoded.strip_prefix("a1").unwrap_or(&encoded))
+            .and_then(|bytes| String::from_utf8(bytes).ok())
+            .filter(|u| is_http(u));
+    }
+    if (domain("facebook.com") && path == "/l.php")
+        || (domain("youtube.com") && path == "/redirect")
+    {
+        return param(&["u", "q"]);
+    }
+    None
+}
+
+/// Yahoo!のクリック計測用URLのパス (`.../RU=<URL>/RS=...`) から本来のURLを取り出す
+///
+/// `RU=` のパス要素はBase64 (パディングは `-`) またはパーセントエンコードされたURLです。
+fn yahoo_redirect(path: &str) -> Option<String> {
+    let (_, rest) = path.split_once("/RU=")?;
+    let encoded = rest.split('/').next().unwrap_or("");
+    let decoded = urlencoding::decode(encoded)
+        .map(|d| d.into_owned())
+        .unwrap_or_default();
+    if is_http(&decoded) {
+        return Some(decoded);
+    }
+    decode_base64(encoded)
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+        .filter(|url| is_http(url))
+}
+
+/// AMPのURLであれば、元のページのURLを返す
+fn de_amp(url: &Url) -> Option<Url> {
+    let host = url.host_str()?.to_lowercase();
+    let path = url.path();
+    // AMPキャッシュ (`https://example-com.cdn.ampproject.org/c/s/example.com/path`) と
+    // Googleのビューア (`https://www.google.com/amp/s/example.com/path`)
+    let cached = if host.ends_with(".cdn.ampproject.org") {
+        ["/c/s/", "/v/s/", "/i/s/", "/c/", "/v/", "/i/"]
+            .iter()
+            .find_map(|prefix| Some((*prefix, path.strip_prefix(prefix)?)))
+    } else if is_google(&host) {
+        ["/amp/s/", "/amp/"]
+            .iter()
+            .find_map(|prefix| Some((*prefix, path.strip_prefix(prefix)?)))
+    } else {
+        None
+    };
+    if let Some((prefix, rest)) = cached {
+        let scheme = if prefix.ends_with("/s/") {
+            "https"
+        } else {
+            "http"
+        };
+        // キャッシュ・ビューアで配信されたページはAMP版なので、サイトによらずAMPのパスなどを除く
+        let mut original = Url::parse(&format!("{}://{}", scheme, rest)).ok()?;
+        original.set_query(url.query());
+        filter_query(&mut original, |k, _| !AMP_VIEWER_PARAMS.contains(&k));
+        return strip_amp(&original).or(Some(original));
+    }
+    if host.starts_with("amp.") || AMP_SITES.iter().any(|site| is_domain(&host, site)) {
+        return strip_amp(url);
+    }
+    None
+}
+
+/// AMP版のパス (末尾の `/amp`, `.amp`, `.amp.html`) とパラメータ (`amp=1`, `outputType=amp` など) を除く。
+/// 該当するものがなければ `None` を返す
+fn strip_amp(url: &Url) -> Option<Url> {
+    let mut original = url.clone();
+    let mut changed = false;
+    let path = url.path().trim_end_matches('/');
+    if let Some(stripped) = path
+        .strip_suffix("/amp")
+        .or_else(|| path.strip_suffix(".amp"))
+    {
+        original.set_path(if stripped.is_empty() { "/" } else { stripped });
+        changed = true;
+    } else if let Some(stripped) = path.strip_suffix(".amp.html") {
+        original.set_path(&format!("{}.html", stripped));
+        changed = true;
+    }
+    changed |= filter_query(&mut original, |k, v| {
+        !(AMP_VIEWER_PARAMS.contains(&k) || (matches!(k, "outputType" | "output") && v == "amp"))
+    });
+    changed.then_some(original)
+}
+
+/// トラッキング用のクエリパラメータを除く。パラメータがなくなった場合は `?` も除く
+fn strip_tracking_params(url: &mut Url) {
+    if url.query() == Some("") {
+        url.set_query(None);
+        return;
+    }
+    filter_query(url, |k, _| {
+        let k = k.to_lowercase();
+        !k.starts_with("utm_") && !TRACKING_PARAMS.contains(&k.as_str())
+    });
+}
+
+/// `keep` が `false` を返すクエリパラメータ (デコードした名前と値で判定) を除き、変更したかを返す
+///
+/// 残したパラメータは元の表記 (`%20`、値のない `?flag` など) のまま保ちます。
+/// パラメータがなくなった場合は `?` も除きます。
+fn filter_query(url: &mut Url, keep: impl Fn(&str, &str) -> bool) -> bool {
+    let Some(query) = url.query() else {
+        return false;
+    };
+    let pairs: Vec<&str> = query.split('&').collect();
+    let kept: Vec<&str> = pairs
+        .iter()
+        .copied()
+        .filter(
+            |pair| match url::form_urlencoded::parse(pair.as_bytes()).next() {
+                Some((k, v)) => keep(&k, &v),
+                None => true,
+            },
+        )
+        .collect();
+    if kept.len() == pairs.len() {
+        return false;
+    }
+    let query = kept.join("&");
+    url.set_query((!query.is_empty()).then_some(query.as_str()));
+    true
+}
+
+/// テキストフラグメント (`#:~:text=...`) を除く。通常のフラグメント (`#section:~:text=...` の `section`) は残す
+fn strip_text_fragment(url: &mut Url) {
+    let Some(fragment) = url.fragment() else {
+        return;
+    };
+    match fragment.find(":~:") {
+        Some(0) => url.set_fragment(None),
+        Some(i) => {
+            let kept = fragment[..i].to_string();
+            url.set_fragment(Some(&kept));
+        }
+        None if fragment.is_empty() => url.set_fragment(None),
+        None => {}
+    }
+}
+
+/// Googleのホスト (`www.google.com`, `google.co.jp` など) かどうか
+fn is_google(host: &str) -> bool {
+    let host = host.strip_prefix("www.").unwrap_or(host);
+    host.strip_prefix("google.").is_some_and(|tld| {
+        tld.split('.')
+            .all(|label| !label.is_empty() && label.len() <= 3)
+    })
+}
+
+/// `host` が `domain` またはそのサブドメインかどうか
+fn is_domain(host: &str, domain: &str) -> bool {
+    host == domain || host.ends_with(&format!(".{}", domain))
+}
+
+fn is_http(url: &str) -> bool {
+    url.starts_with("http://") || url.starts_with("https://")
+}
+
+/// 標準・URLセーフ両方のアルファベットを受け付けるBase64デコーダ
+///
+/// 長さが4の倍数のときは末尾の `-` と `=` をパディングとして扱います。
+fn decode_base64(input: &str) -> Option<Vec<u8>> {
+    let trimmed = if input.len().is_multiple_of(4) {
+        input.trim_end_matches(['=', '-'])
+    } else {
+        input.trim_end_matches('=')
+    };
+    let mut bytes = Vec::with_capacity(trimmed.len() * 3 / 4);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for c in trimmed.bytes() {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' | b'-' => 62,
+            b'/' | b'_' => 63,
+            _ => return None,
+        };
+        buffer = (buffer << 6) | value as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    Some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unwraps_google_redirect() {
+        assert_eq!(
+            canonicalize("https://www.google.co.jp/url?q=https://example.com/a%3Fx%3D1&sa=U&ved=0"),
+            "https://example.com/a?x=1"
+        );
+        // `/url` 以外のGoogleのURLはそのまま
+        assert_eq!(
+            canonicalize("https://www.google.com/search?q=rust"),
+            "https://www.google.com/search?q=rust"
+        );
+    }
+
+    #[test]
+    fn unwraps_duckduckgo_uddg() {
+        assert_eq!(
+            canonicalize(
+                "https://duckduckgo.com/l/?uddg=https%3A%2F%2Fexample.com%2Fpage%3Futm_medium%3Dx&rut=abc"
+            ),
+            "https://example.com/page"
+        );
+        assert_eq!(
+            resolve(
+                "https://html.duckduckgo.com/html/",
+                "//duckduckgo.com/l/?uddg=https%3A%2F%2Fexample.com%2F"
+            ),
+            Some("https://example.com/".to_string())
+        );
+    }
+
+    #[test]
+    fn unwraps_yahoo_ru() {
+        assert_eq!(
+            canonicalize(
+                "https://search.yahoo.co.jp/clear.gif/_ylt=A2R/RV=2/RE=1/RO=10/RU=https%3a%2f%2fexample.com%2fpage/RK=2/RS=abc-"
+            ),
+            "https://example.com/page"
+        );
+        assert_eq!(
+            canonicalize(
+                "https://ord.yahoo.co.jp/o/search/_ylt=A2R/RV=2/RE=1/RU=aHR0cHM6Ly9leGFtcGxlLmNvbS9hP2I9MQ--/RK=2/RS=abc"
+            ),
+            "https://example.com/a?b=1"
+        );
+    }
+
+    #[test]
+    fn replaces_amp_urls() {
+        assert_eq!(
+            canonicalize("https://example-com.cdn.ampproject.org/c/s/example.com/news/1.amp.html"),
+            "https://example.com/news/1.html"
+        );
+        assert_eq!(
+            canonicalize("https://www.google.com/amp/s/example.com/story/amp?usqp=mq331AQ"),
+            "https://example.com/story"
+        );
+        assert_eq!(
+            canonicalize("https://www.bbc.co.uk/news/world-123.amp"),
+            "https://www.bbc.co.uk/news/world-123"
+        );
+        // AMPと無関係なサイトの `/amp` や `amp` パラメータは変えない
+        assert_eq!(
+            canonicalize("https://www.npmjs.com/package/amp"),
+            "https://www.npmjs.com/package/amp"
+        );
+        assert_eq!(
+            canonicalize("https://example.com/page?amp=1"),
+            "https://example.com/page?amp=1"
+        );
+    }
+
+    #[test]
+    fn keeps_surviving_query_params_verbatim() {
+        assert_eq!(
+            canonicalize("https://example.com/a?q=a%20b&flag&utm_source=x&fbclid=y"),
+            "https://example.com/a?q=a%20b&flag"
+        );
+        assert_eq!(
+            canonicalize("https://example.com/a?utm_campaign=x"),
+            "https://example.com/a"
+        );
+    }
+
+    #[test]
+    fn strips_text_fragments() {
+        assert_eq!(
+            canonicalize("https://example.com/page#:~:text=hello"),
+            "https://example.com/page"
+        );
+        assert_eq!(
+            canonicalize("https://example.com/page#section:~:text=hello"),
+            "https://example.com/page#section"
+        );
+        assert_eq!(
+            canonicalize("https://example.com/page#section"),
+            "https://example.com/page#section"
+        );
+    }
+
+    #[test]
+    fn normalizes_host_and_trailing_slash() {
+        assert_eq!(
+            canonicalize("HTTPS://Example.COM.:443/Docs/"),
+            "https://example.com/Docs/"
+        );
+        assert_eq!(canonicalize("https://example.com/"), "https://example.com/");
+        assert_eq!(canonicalize("mailto:a@example.com"), "mailto:a@example.com");
+        assert_eq!(canonicalize("not a url"), "not a url");
+    }
+
+    #[test]
+    fn dedup_key_ignores_trailing_slash() {
+        assert_eq!(
+            dedup_key("https://example.com/docs/?utm_source=x"),
+            "https://example.com/docs"
+        );
+        assert_eq!(
+            dedup_key("https://example.com/docs/"),
+            dedup_key("https://example.com/docs")
+        );
+        assert_eq!(dedup_key("https://example.com/"), "https://example.com/");
+    }
+}
@@ -0,0 +1,730 @@
+// src/config.rs
+
+//! ユーザー設定ファイルの読み込みと、プロセス全体で共有する設定を管理するモジュール
+//!
+//! 設定は以下の優先順位で決定されます (後のものほど優先)。
+//! 1. 組み込みのデフォルト値
+//! 2. 設定ファイル (`$WWW_SEARCH_CONFIG`、なければ `$XDG_CONFIG_HOME/www-search/config.toml`
+//!    または `~/.config/www-search/config.toml`)
+//! 3. 環境変数 (`WWW_SEARCH_ENGINE` など)
+//! 4. CLIのフラグ (CLI側で上書きしてから [`set`] で登録します)
+//!
+//! ライブラリは設定ファイルを自動では読み込みません。[`set`] (または [`load`]) で登録するまでは
+//! 組み込みのデフォルト値を使用します。
+//!
+//! # 設定ファイルの例
+//! ```toml
+//! [search]
+//! engine = "duckduckgo"
+//! max_results = 10
+//!
+//! [engines.google]
+//! user_agent = "w3m/0.5.3"
+//!
+//! [engines.searxng]
+//! base_url = "https://searx.example.org"
+//!
+//! [engines.wikipedia]
+//! base_url = "https://ja.wikipedia.org/w"
+//!
+//! [network]
+//! timeout_secs = 20
+//! proxy = "socks5://127.0.0.1:9050"
+//!
+//! [output]
+//! format = "json"
+//!
+//! [domains]
+//! block = ["example-spam.com", "*.contentfarm.*"]
+//! pin = ["docs.rs"]
+//! boost = ["wikipedia.org"]
+//! lower = ["pinterest.*"]
+//!
+//! [domains.rewrite]
+//! "reddit.com" = "old.reddit.com"
+//!
+//! [history]
+//! retention_days = 90
+//!
+//! [index]
+//! enabled = true
+//!
+//! [bangs]
+//! rs = "docsrs"
+//! mdn = "https://developer.mozilla.org/ja/search?q={query}"
+//!
+//! [server]
+//! listen = "127.0.0.1:8888"
+//! api_keys = ["secret"]
+//! ```
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+
+use serde::Deserialize;
+
+use crate::{EngineType, SearchData};
+
+/// 設定ファイル全体
+///
+/// `bangs` はbang (`!名前`) の追加・上書き (`名前 = "エンジン名またはURLテンプレート"`) です
+/// ([`crate::bang`] を参照)。
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub search: SearchConfig,
+    pub engines: EnginesConfig,
+    pub network: NetworkConfig,
+    pub output: OutputConfig,
+    pub domains: DomainsConfig,
+    pub server: ServerConfig,
+    pub history: HistoryConfig,
+    pub bookmarks: BookmarksConfig,
+    pub index: IndexConfig,
+    pub bangs: BTreeMap<String, String>,
+}
+
+/// 検索全般の設定 (`[search]`)
+///
+/// - `engine`: デフォルトの検索エンジン
+/// - `max_results`: 返却する検索結果の最大件数 (未指定なら無制限)
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SearchConfig {
+    pub engine: EngineType,
+    pub max_results: Option<usize>,
+}
+
+/// エンジンごとの設定 (`[engines.<name>]`)
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EnginesConfig {
+    pub google: EngineConfig,
+    pub duckduckgo: EngineConfig,
+    pub brave: EngineConfig,
+    pub searxng: EngineConfig,
+    pub wikipedia: EngineConfig,
+    pub yahoo_japan: EngineConfig,
+    pub goo: EngineConfig,
+    pub github: EngineConfig,
+    pub stackexchange: EngineConfig,
+    pub crates: EngineConfig,
+    pub docsrs: EngineConfig,
+    pub arxiv: EngineConfig,
+    pub crossref: EngineConfig,
+    pub local: EngineConfig,
+}
+
+/// 個々のエンジンの設定。未指定の項目は `[network]` の値を使用します。
+///
+/// - `api_key`: 公式APIのキー。設定されている場合、対応するエンジン (Brave) はHTMLではなくAPIを使用します。
+///   GitHubではトークン、Stack Exchangeではアプリケーションキー、Crossrefでは連絡先のメールアドレスとして使用します。
+/// - `base_url`: 接続先のインスタンスURL (SearXNGでは必須)、MediaWikiのスクリプトパス (Wikipedia)
+/// - `categories`: デフォルトの検索カテゴリ (SearXNG)、検索するサイト (Stack Exchange)
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EngineConfig {
+    pub user_agent: Option<String>,
+    pub timeout_secs: Option<u64>,
+    pub api_key: Option<String>,
+    pub base_url: Option<String>,
+    pub categories: Vec<String>,
+}
+
+/// ネットワークの設定 (`[network]`)
+///
+/// - `user_agent`: User-Agent (未指定ならエンジンごとの既定値)
+/// - `timeout_secs`: リクエストのタイムアウト秒数
+/// - `proxy`: プロキシURL (`http://`, `https://`, `socks5://`)
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NetworkConfig {
+    pub user_agent: Option<String>,
+    pub timeout_secs: Option<u64>,
+    pub proxy: Option<String>,
+}
+
+/// 出力の設定 (`[output]`)
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OutputConfig {
+    pub format: OutputFormat,
+}
+
+/// CLIの出力形式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            _ => Err(format!("Unknown output format: {}", s)),
+        }
+    }
+}
+
+/// ドメインごとの検索結果の扱いの設定 (`[domains]`)
+///
+/// ドメインはサブドメインも含めて一致します (`example.com` は `www.example.com` にも一致)。
+/// `*` は任意の文字列に一致するワイルドカードで、ホスト名の末尾部分に対して照合します
+/// (`*.blogspot.*` は `foo.blogspot.com` に、`seo-*.net` は `www.seo-tips.net` に一致)。
+/// - `block`: 検索結果から除外するドメイン
+/// - `allow`: 空でなければ、一致するドメインの結果だけを残す (許可リスト)
+/// - `pin`: 検索結果の先頭に固定するドメイン
+/// - `boost`: 検索結果の先頭 (`pin` の結果の次) へ移動するドメイン
+/// - `lower`: 検索結果の末尾へ移動するドメイン
+/// - `rewrite`: URLのホストの置き換え (`"reddit.com" = "old.reddit.com"`)。キーはドメイン
+///   (`www.` 付きのホストにも一致) またはワイルドカードのパターン、値はホスト名またはスキームを含むURL
+///   (`https://nitter.example.net`)
+///
+/// 画像検索の結果は掲載ページのURL (`metadata` の `source_page`) で判定します。
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DomainsConfig {
+    pub block: Vec<String>,
+    pub allow: Vec<String>,
+    pub pin: Vec<String>,
+    pub boost: Vec<String>,
+    pub lower: Vec<String>,
+    pub rewrite: BTreeMap<String, String>,
+}
+
+impl DomainsConfig {
+    /// ブロック・許可リストで検索結果を絞り込み、固定・ブースト・下位への移動で並べ替えてから、
+    /// ホストの置き換えを適用します。
+    ///
+    /// 並べ替えでは、同じ扱いの結果同士の順序は変えません。
+    pub fn apply(&self, results: Vec<SearchData>) -> Vec<SearchData> {
+        let mut results: Vec<(u8, SearchData)> = results
+            .into_iter()
+            .filter_map(|r| {
+                let host = host(page_url(&r)).unwrap_or_default();
+                if matches_any(&host, &self.block)
+                    || (!self.allow.is_empty() && !matches_any(&host, &self.allow))
+                {
+                    return None;
+                }
+                let rank = if matches_any(&host, &self.pin) {
+                    0
+                } else if matches_any(&host, &self.boost) {
+                    1
+                } else if matches_any(&host, &self.lower) {
+                    3
+                } else {
+                    2
+                };
+                Some((rank, r))
+            })
+            .collect();
+        results.sort_by_key(|(rank, _)| *rank);
+        results
+            .into_iter()
+            .map(|(_, mut r)| {
+                if !self.rewrite.is_empty() {
+                    match r.metadata.get_mut("source_page") {
+                        Some(page) => *page = self.rewrite_url(page),
+                        None => r.url = self.rewrite_url(&r.url),
+                    }
+                }
+                r
+            })
+            .collect()
+    }
+
+    /// `rewrite` の設定でURLのホストを置き換えます。一致する設定がなければそのまま返します。
+    ///
+    /// 置き換えたURLには元のURLのユーザー情報 (`user:password@`) を残しません。
+    pub fn rewrite_url(&self, url: &str) -> String {
+        let Ok(mut parsed) = url::Url::parse(url) else {
+            return url.to_string();
+        };
+        let Some(host) = parsed.host_str().map(str::to_lowercase) else {
+            return url.to_string();
+        };
+        let target = self.rewrite.iter().find_map(|(pattern, target)| {
+            let pattern = pattern.to_lowercase();
+            let matched = if pattern.contains('*') {
+                matches_any(&host, std::slice::from_ref(&pattern))
+            } else {
+                host == pattern || host.strip_prefix("www.") == Some(pattern.as_str())
+            };
+            matched.then_some(target)
+        });
+        let Some(target) = target else {
+            return url.to_string();
+        };
+        if target.contains("://") {
+            let Ok(base) = url::Url::parse(target) else {
+                return url.to_string();
+            };
+            if parsed.set_scheme(base.scheme()).is_err()
+                || parsed.set_host(base.host_str()).is_err()
+                || parsed.set_port(base.port()).is_err()
+            {
+                return url.to_string();
+            }
+        } else if parsed.set_host(Some(target)).is_err() {
+            return url.to_string();
+        }
+        parsed.set_username("").ok();
+        parsed.set_password(None).ok();
+        parsed.to_string()
+    }
+}
+
+/// `www-search serve` で起動するHTTP APIサーバーの設定 (`[server]`)
+///
+/// - `listen`: 待ち受けアドレス (デフォルト `127.0.0.1:8888`)
+/// - `api_keys`: 許可するAPIキー (空なら認証なし)
+/// - `rate_limit_per_minute`: クライアントIPごとの1分あたりのリクエスト上限
+/// - `max_concurrent`: 同時に処理するリクエスト数の上限
+/// - `max_query_length`: 検索クエリの最大文字数
+/// - `enable_browse`: 任意のURLを取得する `/browse` とローカルの全文検索インデックス (`engine=local`) を
+///   公開するか (デフォルト `false`、有効にするには `api_keys` も必要)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerConfig {
+    pub listen: String,
+    pub api_keys: Vec<String>,
+    pub rate_limit_per_minute: u32,
+    pub max_concurrent: usize,
+    pub max_query_length: usize,
+    pub enable_browse: bool,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        ServerConfig {
+            listen: "127.0.0.1:8888".to_string(),
+            api_keys: Vec::new(),
+            rate_limit_per_minute: 60,
+            max_concurrent: 8,
+            max_query_length: 512,
+            enable_browse: false,
+        }
+    }
+}
+
+/// 検索・閲覧の履歴の設定 (`[history]`、[`crate::history`] を参照)
+///
+/// - `enabled`: 履歴を記録するか (デフォルト `true`)
+/// - `path`: 履歴ファイルのパス (未指定なら `$XDG_DATA_HOME/www-search/history.jsonl`)
+/// - `retention_days`: 履歴の保存日数 (未指定なら無期限)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HistoryConfig {
+    pub enabled: bool,
+    pub path: Option<PathBuf>,
+    pub retention_days: Option<u64>,
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        HistoryConfig {
+            enabled: true,
+            path: None,
+            retention_days: None,
+        }
+    }
+}
+
+/// ブックマークと「あとで読む」リストの設定 (`[bookmarks]`、[`crate::bookmarks`] を参照)
+///
+/// - `path`: ブックマークファイルのパス (未指定なら `$XDG_DATA_HOME/www-search/bookmarks.json`)
+/// - `reading_dir`: 「あとで読む」に保存したページのMarkdownを置くディレクトリ
+///   (未指定なら `$XDG_DATA_HOME/www-search/reading`)
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BookmarksConfig {
+    pub path: Option<PathBuf>,
+    pub reading_dir: Option<PathBuf>,
+}
+
+/// 閲覧したページのローカル全文検索インデックスの設定 (`[index]`、[`crate::index`] を参照)
+///
+/// - `enabled`: 閲覧したページをインデックスに保存するか (デフォルト `false`)
+/// - `path`: インデックスのディレクトリ (未指定なら `$XDG_DATA_HOME/www-search/index`)
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct IndexConfig {
+    pub enabled: bool,
+    pub path: Option<PathBuf>,
+}
+
+static CURRENT: RwLock<Option<Arc<Config>>> = RwLock::new(None);
+
+/// 現在の設定を返します。
+///
+/// [`set`] で設定が登録されていなければ、デフォルト設定を返します (設定ファイルは読み込みません)。
+pub fn current() -> Arc<Config> {
+    if let Some(config) = CURRENT.read().unwrap().as_ref() {
+        return config.clone();
+    }
+    CURRENT
+        .write()
+        .unwrap()
+        .get_or_insert_with(|| Arc::new(Config::default()))
+        .clone()
+}
+
+/// デフォルトの場所にある設定ファイルと環境変数を [`Config::load`] で読み込み、登録します。
+///
+/// 読み込みに失敗した場合はエラーを返し、登録済みの設定は変更しません。
+///
+/// ```ignore
+/// www_search::config::load()?;
+/// ```
+pub fn load() -> Result<(), String> {
+    set(Config::load()?);
+    Ok(())
+}
+
+/// プロセス全体で使用する設定を登録します。
+pub fn set(config: Config) {
+    *CURRENT.write().unwrap() = Some(Arc::new(config));
+}
+
+/// 現在の設定を変更して登録し直します。
+///
+/// ```ignore
+/// www_search::config::update(|config| {
+///     config.domains.block.push("*.contentfarm.*".to_string());
+///     config.domains.rewrite.insert("reddit.com".to_string(), "old.reddit.com".to_string());
+/// });
+/// ```
+pub fn update(f: impl FnOnce(&mut Config)) {
+    let mut config = (*current()).clone();
+    f(&mut config);
+    set(config);
+}
+
+impl Config {
+    /// デフォルトの場所にある設定ファイルを読み込み、環境変数による上書きを適用します。
+    ///
+    /// 設定ファイルが存在しない場合はデフォルト設定を使用します。
+    pub fn load() -> Result<Config, String> {
+        let mut config = match std::env::var_os("WWW_SEARCH_CONFIG") {
+            Some(path) => Config::from_file(Path::new(&path))?,
+            None => match default_path() {
+                Some(path) if path.exists() => Config::from_file(&path)?,
+                _ => Config::default(),
+            },
+        };
+        config.apply_env()?;
+        Ok(config)
+    }
+
+    /// 指定したパスの設定ファイルを読み込みます (環境変数は適用しません)。
+    pub fn from_file(path: &Path) -> Result<Config, String> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read config file {}: {}", path.display(), e))?;
+        let config: Config = toml::from_str(&text)
+            .map_err(|e| format!("Failed to parse config file {}: {}", path.display(), e))?;
+        crate::bang::validate(&config.bangs)
+            .map_err(|e| format!("Invalid [bangs] in config file {}: {}", path.display(), e))?;
+        Ok(config)
+    }
+
+    /// 環境変数による上書きを適用します。
+    ///
+    /// - `WWW_SEARCH_ENGINE`
+    /// - `WWW_SEARCH_MAX_RESULTS`
+    /// - `WWW_SEARCH_USER_AGENT`
+    /// - `WWW_SEARCH_TIMEOUT`
+    /// - `WWW_SEARCH_PROXY`
+    /// - `WWW_SEARCH_FORMAT`
+    /// - `WWW_SEARCH_API_KEY` (サーバーの許可するAPIキーに追加)
+    /// - `BRAVE_SEARCH_API_KEY` (Brave Search APIのキー)
+    /// - `SEARXNG_URL` (SearXNGインスタンスのURL)
+    /// - `GITHUB_TOKEN` (GitHub APIのトークン)
+    pub fn apply_env(&mut self) -> Result<(), String> {
+        if let Ok(engine) = std::env::var("WWW_SEARCH_ENGINE") {
+            self.search.engine = engine.parse()?;
+        }
+        if let Ok(max) = std::env::var("WWW_SEARCH_MAX_RESULTS") {
+            self.search.max_results = Some(
+                max.parse()
+                    .map_err(|_| format!("Invalid WWW_SEARCH_MAX_RESULTS: {}", max))?,
+            );
+        }
+        if let Ok(user_agent) = std::env::var("WWW_SEARCH_USER_AGENT") {
+            self.network.user_agent = Some(user_agent);
+        }
+        if let Ok(timeout) = std::env::var("WWW_SEARCH_TIMEOUT") {
+            self.network.timeout_secs = Some(
+                timeout
+                    .parse()
+                    .map_err(|_| format!("Invalid WWW_SEARCH_TIMEOUT: {}", timeout))?,
+            );
+        }
+        if let Ok(proxy) = std::env::var("WWW_SEARCH_PROXY") {
+            self.network.proxy = Some(proxy);
+        }
+        if let Ok(format) = std::env::var("WWW_SEARCH_FORMAT") {
+            self.output.format = format.parse()?;
+        }
+        if let Ok(api_key) = std::env::var("WWW_SEARCH_API_KEY") {
+            self.server.api_keys.push(api_key);
+        }
+        if let Ok(api_key) = std::env::var("BRAVE_SEARCH_API_KEY") {
+            self.engines.brave.api_key = Some(api_key);
+        }
+        if let Ok(base_url) = std::env::var("SEARXNG_URL") {
+            self.engines.searxng.base_url = Some(base_url);
+        }
+        if let Ok(token) = std::env::var("GITHUB_TOKEN") {
+            self.engines.github.api_key = Some(token);
+        }
+        Ok(())
+    }
+
+    /// 指定したエンジンの設定を返します。
+    pub fn engine(&self, engine: EngineType) -> &EngineConfig {
+        match engine {
+            EngineType::Google => &self.engines.google,
+            EngineType::DuckDuckGo => &self.engines.duckduckgo,
+            EngineType::Brave => &self.engines.brave,
+            EngineType::Searxng => &self.engines.searxng,
+            EngineType::Wikipedia => &self.engines.wikipedia,
+            EngineType::YahooJapan => &self.engines.yahoo_japan,
+            EngineType::Goo => &self.engines.goo,
+            EngineType::Github => &self.engines.github,
+            EngineType::StackExchange => &self.engines.stackexchange,
+            EngineType::Crates => &self.engines.crates,
+            EngineType::DocsRs => &self.engines.docsrs,
+            EngineType::Arxiv => &self.engines.arxiv,
+            EngineType::Crossref => &self.engines.crossref,
+            EngineType::Local => &self.engines.local,
+        }
+    }
+
+    /// ドメインの設定 (`[domains]`) と件数上限を検索結果に適用します。
+    pub fn apply_to_results(&self, results: Vec<SearchData>) -> Vec<SearchData> {
+        let mut results = self.domains.apply(results);
+        if let Some(max) = self.search.max_results {
+            results.truncate(max);
+        }
+        results
+    }
+}
+
+/// デフォルトの設定ファイルのパス
+pub fn default_path() -> Option<PathBuf> {
+    let base = match std::env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(std::env::var_os("HOME")?).join(".config"),
+    };
+    Some(base.join("www-search").join("config.toml"))
+}
+
+/// 履歴・ブックマークなどを保存するデータディレクトリ
+/// (`$XDG_DATA_HOME/www-search` または `~/.local/share/www-search`)
+pub fn data_dir() -> Option<PathBuf> {
+    let base = match std::env::var_os("XDG_DATA_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(std::env::var_os("HOME")?)
+            .join(".local")
+            .join("share"),
+    };
+    Some(base.join("www-search"))
+}
+
+/// データディレクトリのファイル (履歴など) を開くオプション。Unixでは所有者のみ読み書きできる (0600) ファイルを作成します。
+pub(crate) fn private_file_options() -> std::fs::OpenOptions {
+    let mut options = std::fs::OpenOptions::new();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options
+}
+
+/// ファイルの内容を置き換えます。同じディレクトリの一時ファイルに書き込んでから名前を変更するため、
+/// 途中で失敗しても元のファイルは壊れません。一時ファイルは [`private_file_options`] で作成します。
+pub(crate) fn write_atomic(path: &Path, contents: &str) -> Result<(), String> {
+    use std::io::Write;
+
+    let mut temp = path.as_os_str().to_owned();
+    temp.push(format!(".{}.tmp", std::process::id()));
+    let temp = PathBuf::from(temp);
+    let result = private_file_options()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(&temp)
+        .and_then(|mut file| {
+            file.write_all(contents.as_bytes())?;
+            file.sync_all()
+        })
+        .and_then(|()| std::fs::rename(&temp, path));
+    result.map_err(|e| {
+        std::fs::remove_file(&temp).ok();
+        format!("Failed to write {}: {}", path.display(), e)
+    })
+}
+
+/// 掲載ページのURL (画像検索では `source_page`)
+fn page_url(result: &SearchData) -> &str {
+    result.metadata.get("source_page").unwrap_or(&result.url)
+}
+
+/// URLのホスト名 (小文字)。URLとして解釈できない場合は `None`
+fn host(url: &str) -> Option<String> {
+    url::Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(str::to_lowercase))
+}
+
+/// ホストが、いずれかのドメイン (またはそのサブドメイン)、ワイルドカードのパターンに一致するかどうか
+fn matches_any(host: &str, domains: &[String]) -> bool {
+    domains.iter().any(|domain| {
+        let domain = domain.trim().to_lowercase();
+        if domain.contains('*') {
+            // ホスト全体と、ラベル区切りの末尾部分 (`www.example.com` → `example.com`, `com`) に照合する
+            std::iter::once(host)
+                .chain(host.match_indices('.').map(|(i, _)| &host[i + 1..]))
+                .any(|suffix| glob_match(&domain, suffix))
+        } else {
+            host == domain
+                || host
+                    .strip_suffix(domain.as_str())
+                    .is_some_and(|prefix| prefix.ends_with('.'))
+        }
+    })
+}
+
+/// `*` を任意の文字列 (空文字列を含む) として `pattern` と `text` を照合する
+pub(crate) fn glob_match(pattern: &str, text: &str) -> bool {
+    let parts: Vec<&str> = pattern.split('*').collect();
+    let (first, last) = (parts[0], parts[parts.len() - 1]);
+    if parts.len() == 1 {
+        return pattern == text;
+    }
+    let Some(mut rest) = text.strip_prefix(first) else {
+        return false;
+    };
+    for part in &parts[1..parts.len() - 1] {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn domains(patterns: &[&str]) -> Vec<String> {
+        patterns.iter().map(|p| p.to_string()).collect()
+    }
+
+    fn result(url: &str) -> SearchData {
+        SearchData {
+            url: url.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn wildcard_subdomain_pattern_excludes_apex() {
+        let patterns = domains(&["*.example.com"]);
+        assert!(matches_any("www.example.com", &patterns));
+        assert!(matches_any("a.b.example.com", &patterns));
+        assert!(!matches_any("example.com", &patterns));
+        assert!(!matches_any("example.com.evil.test", &patterns));
+    }
+
+    #[test]
+    fn plain_domain_matches_subdomains_only_on_label_boundary() {
+        let patterns = domains(&["example.com"]);
+        assert!(matches_any("example.com", &patterns));
+        assert!(matches_any("docs.example.com", &patterns));
+        assert!(!matches_any("badexample.com", &patterns));
+        assert!(!matches_any("example.com.evil.test", &patterns));
+    }
+
+    #[test]
+    fn glob_matches_prefix_middle_and_suffix() {
+        assert!(glob_match("*.contentfarm.*", "www.contentfarm.net"));
+        assert!(!glob_match("*.contentfarm.*", "contentfarm.net"));
+        assert!(glob_match("pinterest.*", "pinterest.co.uk"));
+        assert!(glob_match("a*b*c", "abc"));
+        assert!(!glob_match("a*b*c", "acb"));
+        assert!(!glob_match("ab*ba", "aba"));
+    }
+
+    #[test]
+    fn rewrite_keeps_port_and_drops_userinfo() {
+        let config = DomainsConfig {
+            rewrite: BTreeMap::from([
+                ("reddit.com".to_string(), "old.reddit.com".to_string()),
+                (
+                    "twitter.com".to_string(),
+                    "https://nitter.example.net".to_string(),
+                ),
+            ]),
+            ..Default::default()
+        };
+        assert_eq!(
+            config.rewrite_url("https://user:pw@www.reddit.com:8443/r/rust?x=1"),
+            "https://old.reddit.com:8443/r/rust?x=1"
+        );
+        assert_eq!(
+            config.rewrite_url("http://user@twitter.com:8080/rustlang"),
+            "https://nitter.example.net/rustlang"
+        );
+        assert_eq!(
+            config.rewrite_url("https://notreddit.com/"),
+            "https://notreddit.com/"
+        );
+    }
+
+    #[test]
+    fn apply_orders_pinned_boosted_and_lowered_results() {
+        let config = DomainsConfig {
+            block: domains(&["spam.test"]),
+            pin: domains(&["docs.rs"]),
+            boost: domains(&["wikipedia.org"]),
+            lower: domains(&["pinterest.*"]),
+            ..Default::default()
+        };
+        let urls: Vec<String> = config
+            .apply(vec![
+                result("https://www.pinterest.com/a"),
+                result("https://other.test/1"),
+                result("https://en.wikipedia.org/wiki/Rust"),
+                result("https://spam.test/"),
+                result("https://docs.rs/tokio"),
+                result("https://other.test/2"),
+                result("https://pinterest.jp/b"),
+            ])
+            .into_iter()
+            .map(|r| r.url)
+            .collect();
+        assert_eq!(
+            urls,
+            vec![
+                "https://docs.rs/tokio",
+                "https://en.wikipedia.org/wiki/Rust",
+                "https://other.test/1",
+                "https://other.test/2",
+                "https://www.pinterest.com/a",
+                "https://pinterest.jp/b",
+            ]
+        );
+    }
+}
@@ -0,0 +1,842 @@
+// src/crawl.rs
+
+//! 開始URLからリンクをたどってサイト全体を取得するクローラー
+//!
+//! 開始URLと同じオリジン (スキーム・ホスト・ポート) のリンク、または `include` のパターンに
+//! 一致するリンクを幅優先でたどり、各ページの本文を [`browse::html_to_markdown`] でMarkdownに
+//! 変換します。深さ・ページ数の上限、同時接続数、リクエストの間隔を指定でき、`robots.txt`
+//! (`Disallow`, `Allow`, `Crawl-delay`) に従います。`rel="nofollow"` のリンクはたどりません。
+//! リダイレクトは自動では追わず、リダイレクト先をリンクと同じようにキューに入れるため、
+//! リダイレクト先にもクロールの範囲と `robots.txt` を適用します。
+//!
+//! 取得したページは [`CrawlWriter`] でディレクトリ (ページごとのMarkdownと `manifest.json`)
+//! またはJSON Linesのファイルに書き出せます。
+//!
+//! ```ignore
+//! use futures::StreamExt;
+//! use www_search::crawl::{self, CrawlOptions, CrawlOutput, CrawlWriter};
+//!
+//! let options = CrawlOptions { max_depth: 3, ..Default::default() };
+//! let mut writer = CrawlWriter::new("https://docs.example.com/", &CrawlOutput::Directory("docs".into()))?;
+//! let mut pages = crawl::crawl("https://docs.example.com/", options);
+//! while let Some(page) = pages.next().await {
+//!     match page {
+//!         Ok(page) => writer.write(&page)?,
+//!         Err(e) => writer.error(&e),
+//!     }
+//! }
+//! writer.finish()?;
+//! ```
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::stream::{self, BoxStream, StreamExt};
+use scraper::{Html, Selector};
+use serde::Serialize;
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+use url::Url;
+
+use crate::{browse, canonical, config, search};
+
+/// `robots.txt` の `User-agent` と照合するクローラーの名前
+const ROBOTS_AGENT: &str = "www-search";
+
+/// `robots.txt` の `Crawl-delay` として従う間隔の上限
+const MAX_CRAWL_DELAY: Duration = Duration::from_secs(30);
+
+/// ファイル名に使用するURLのパス要素の最大文字数
+const MAX_SEGMENT_CHARS: usize = 80;
+
+/// クロールのオプション
+///
+/// - `max_depth`: 開始URLからたどるリンクの深さの上限 (0なら開始URLのみ)
+/// - `max_pages`: 取得するページ数の上限
+/// - `concurrency`: 同時に取得するページ数
+/// - `delay`: リクエストを開始する間隔 (`robots.txt` の `Crawl-delay` の方が長ければそちらを使用。
+///   `Crawl-delay` は30秒を上限とします)
+/// - `include`: 空でなければ、いずれかに一致するURLのリンクだけをたどる (同じオリジンに限定しない)。
+///   `*` は任意の文字列に一致します (`https://docs.example.com/guide/*`)
+/// - `exclude`: 一致するURLのリンクはたどらない
+/// - `respect_robots`: `robots.txt` に従うか
+#[derive(Debug, Clone)]
+pub struct CrawlOptions {
+    pub max_depth: usize,
+    pub max_pages: usize,
+    pub concurrency: usize,
+    pub delay: Duration,
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+    pub respect_robots: bool,
+}
+
+impl Default for CrawlOptions {
+    fn default() -> Self {
+        CrawlOptions {
+            max_depth: 2,
+            max_pages: 50,
+            concurrency: 4,
+            delay: Duration::from_millis(500),
+            include: Vec::new(),
+            exclude: Vec::new(),
+            respect_robots: true,
+        }
+    }
+}
+
+/// 取得したページ
+///
+/// - `url`: ページのURL
+/// - `title`: `<title>` の内容
+/// - `depth`: 開始URLからのリンクの深さ
+/// - `fetched_at`: 取得した日時 (UTCのISO 8601形式)
+/// - `markdown`: 本文のMarkdown
+/// - `links`: ページ内のリンクのうち、クロールの対象になるもの (書き出しには含めません)
+#[derive(Debug, Clone, Serialize)]
+pub struct CrawledPage {
+    pub url: String,
+    pub title: String,
+    pub depth: usize,
+    pub fetched_at: String,
+    pub markdown: String,
+    #[serde(skip)]
+    pub links: Vec<String>,
+}
+
+/// クロール結果の書き出し先
+///
+/// - `Directory`: ページごとのMarkdown (`<ホスト>/<パス>.md`) と `manifest.json` を書き出すディレクトリ
+/// - `Jsonl`: 1行に1ページ ([`CrawledPage`] のJSON) を書き出すファイル
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CrawlOutput {
+    Directory(PathBuf),
+    Jsonl(PathBuf),
+}
+
+impl CrawlOutput {
+    /// パスの拡張子が `.jsonl` ならJSON Lines、それ以外はディレクトリとして扱います。
+    pub fn from_path(path: impl Into<PathBuf>) -> CrawlOutput {
+        let path = path.into();
+        if path.extension().is_some_and(|e| e == "jsonl") {
+            CrawlOutput::Jsonl(path)
+        } else {
+            CrawlOutput::Directory(path)
+        }
+    }
+}
+
+/// `manifest.json` の内容
+#[derive(Debug, Clone, Serialize)]
+pub struct Manifest {
+    pub start_url: String,
+    pub crawled_at: String,
+    pub pages: Vec<ManifestEntry>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<String>,
+}
+
+/// `manifest.json` のページの項目。`file` は書き出し先ディレクトリからの相対パスです。
+#[derive(Debug, Clone, Serialize)]
+pub struct ManifestEntry {
+    pub url: String,
+    pub title: String,
+    pub depth: usize,
+    pub fetched_at: String,
+    pub file: String,
+}
+
+/// クロール結果をディレクトリまたはJSON Linesのファイルに書き出す
+pub struct CrawlWriter {
+    output: CrawlOutput,
+    manifest: Manifest,
+    files: HashSet<PathBuf>,
+    jsonl: Option<std::fs::File>,
+}
+
+impl CrawlWriter {
+    /// 書き出し先を作成します (JSON Linesのファイルは空にします)。
+    pub fn new(start_url: &str, output: &CrawlOutput) -> Result<CrawlWriter, String> {
+        let jsonl = match output {
+            CrawlOutput::Directory(dir) => {
+                std::fs::create_dir_all(dir)
+                    .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+                None
+            }
+            CrawlOutput::Jsonl(path) => {
+                if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+                    std::fs::create_dir_all(dir)
+                        .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+                }
+                Some(
+                    std::fs::File::create(path)
+                        .map_err(|e| format!("Failed to create {}: {}", path.display(), e))?,
+                )
+            }
+        };
+        Ok(CrawlWriter {
+            output: output.clone(),
+            manifest: Manifest {
+                start_url: start_url.to_string(),
+                crawled_at: search::now_iso(),
+                pages: Vec::new(),
+                errors: Vec::new(),
+            },
+            files: HashSet::new(),
+            jsonl,
+        })
+    }
+
+    /// ページを1件書き出します。ディレクトリの場合は書き出したファイルのパスを返します。
+    pub fn write(&mut self, page: &CrawledPage) -> Result<Option<PathBuf>, String> {
+        if let Some(file) = &mut self.jsonl {
+            let line = serde_json::to_string(page)
+                .map_err(|e| format!("Failed to serialize page: {}", e))?;
+            writeln!(file, "{}", line).map_err(|e| format!("Failed to write page: {}", e))?;
+            return Ok(None);
+        }
+        let CrawlOutput::Directory(dir) = &self.output else {
+            return Ok(None);
+        };
+        let relative = unique_path(&page_path(&page.url), &mut self.files);
+        let path = dir.join(&relative);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        let title = if page.title.is_empty() {
+            &page.url
+        } else {
+            &page.title
+        };
+        let content = format!(
+            "# {}\n\n<{}>\n\n{}\n",
+            title,
+            page.url,
+            page.markdown.trim_end()
+        );
+        config::write_atomic(&path, &content)?;
+        self.manifest.pages.push(ManifestEntry {
+            url: page.url.clone(),
+            title: page.title.clone(),
+            depth: page.depth,
+            fetched_at: page.fetched_at.clone(),
+            file: relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/"),
+        });
+        Ok(Some(path))
+    }
+
+    /// 取得に失敗したページのエラーを `manifest.json` に記録します。
+    pub fn error(&mut self, message: &str) {
+        self.manifest.errors.push(message.to_string());
+    }
+
+    /// 書き出しを完了します。ディレクトリの場合は `manifest.json` を書き出してそのパスを返します。
+    pub fn finish(mut self) -> Result<PathBuf, String> {
+        match &self.output {
+            CrawlOutput::Jsonl(path) => {
+                if let Some(file) = &mut self.jsonl {
+                    file.flush()
+                        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+                }
+                Ok(path.clone())
+            }
+            CrawlOutput::Directory(dir) => {
+                let path = dir.join("manifest.json");
+                let json = serde_json::to_string_pretty(&self.manifest)
+                    .map_err(|e| format!("Failed to serialize manifest: {}", e))?;
+                config::write_atomic(&path, &(json + "\n"))?;
+                Ok(path)
+            }
+        }
+    }
+}
+
+/// 開始URLからクロールし、取得したページを1件ずつ返すストリームを作成します。
+///
+/// 取得・変換に失敗したページは `Err` を1件流し、クロールを続けます。
+/// ストリームは遅延評価のため、必要なページを受け取った後は取得しません。
+pub fn crawl(
+    start_url: &str,
+    options: CrawlOptions,
+) -> BoxStream<'static, Result<CrawledPage, String>> {
+    let start = match Url::parse(start_url) {
+        Ok(url) if matches!(url.scheme(), "http" | "https") => url,
+        _ => {
+            return stream::once(futures::future::ready(Err(format!(
+                "Invalid start URL: {}",
+                start_url
+            ))))
+            .boxed();
+        }
+    };
+    let clients = search::client(None, Some(search::API_USER_AGENT)).and_then(|robots_client| {
+        let client = search::client_builder(None, Some(search::API_USER_AGENT))?
+            .redirect(reqwest::redirect::Policy::none())
+            .build()
+            .map_err(|e| format!("Failed to build reqwest client: {}", e))?;
+        Ok((client, robots_client))
+    });
+    let (client, robots_client) = match clients {
+        Ok(clients) => clients,
+        Err(e) => return stream::once(futures::future::ready(Err(e))).boxed(),
+    };
+    let state = Crawler {
+        client,
+        robots_client,
+        queue: VecDeque::from([(start.to_string(), 0)]),
+        seen: HashSet::from([canonical::dedup_key(start.as_str())]),
+        redirects: HashSet::new(),
+        start,
+        options,
+        fetched: 0,
+        robots: HashMap::new(),
+        next_request: Arc::new(Mutex::new(Instant::now())),
+    };
+    stream::unfold(state, |mut crawler| async move {
+        let batch = crawler.next_batch().await?;
+        Some((stream::iter(batch), crawler))
+    })
+    .flatten()
+    .boxed()
+}
+
+/// クロールの状態
+struct Crawler {
+    /// ページを取得するクライアント (リダイレクトを追わない)
+    client: reqwest::Client,
+    /// `robots.txt` を取得するクライアント (リダイレクトを追う)
+    robots_client: reqwest::Client,
+    start: Url,
+    options: CrawlOptions,
+    /// 取得待ちの (URL, 深さ)
+    queue: VecDeque<(String, usize)>,
+    /// キューに入れたことのあるURL ([`canonical::dedup_key`] で正規化したもの)
+    seen: HashSet<String>,
+    /// 正規化すると同じURLになるリダイレクト (末尾の `/` の付け外しなど) で追ったURL
+    redirects: HashSet<String>,
+    /// 取得を試みたページ数
+    fetched: usize,
+    /// オリジンごとの `robots.txt`
+    robots: HashMap<String, Robots>,
+    /// 次のリクエストを開始できる時刻
+    next_request: Arc<Mutex<Instant>>,
+}
+
+impl Crawler {
+    /// キューから最大 `concurrency` 件のページを並行して取得する。クロールが終了したら `None`
+    async fn next_batch(&mut self) -> Option<Vec<Result<CrawledPage, String>>> {
+        let mut batch = Vec::new();
+        let mut blocked = Vec::new();
+        while batch.len() < self.options.concurrency.max(1) && self.fetched < self.options.max_pages
+        {
+            let Some((url, depth)) = self.queue.pop_front() else {
+                break;
+            };
+            if self.options.respect_robots && !self.allowed(&url).await {
+                blocked.push(Err(format!("Skipped {}: disallowed by robots.txt", url)));
+                continue;
+            }
+            self.fetched += 1;
+            batch.push((url, depth));
+        }
+        if batch.is_empty() && blocked.is_empty() {
+            return None;
+        }
+        let delay = self.delay();
+        let pages = futures::future::join_all(batch.into_iter().map(|(url, depth)| {
+            let client = self.client.clone();
+            let next_request = self.next_request.clone();
+            async move {
+                wait_turn(&next_request, delay).await;
+                fetch_page(&client, &url, depth).await
+            }
+        }))
+        .await;
+
+        let mut results = blocked;
+        for page in pages {
+            let page = match page {
+                Ok(Fetched::Page(page)) => Ok(page),
+                Ok(Fetched::Redirect { from, to, depth }) => {
+                    // リダイレクトはページ数に数えず、リダイレクト先を同じ深さでキューに入れる
+                    self.fetched -= 1;
+                    if let Some(error) = self.follow_redirect(&from, &to, depth) {
+                        results.push(Err(error));
+                    }
+                    continue;
+                }
+                Err(e) => Err(e),
+            };
+            if let Ok(page) = &page
+                && page.depth < self.options.max_depth
+            {
+                for link in &page.links {
+                    if self.in_scope(link) && self.seen.insert(canonical::dedup_key(link)) {
+                        self.queue.push_back((link.clone(), page.depth + 1));
+                    }
+                }
+            }
+            results.push(page);
+        }
+        Some(results)
+    }
+
+    /// リダイレクト先をキューの先頭に入れる。範囲外のリダイレクトはエラーメッセージを返す
+    ///
+    /// 開始URLのリダイレクト (`http` → `https` など) は、リダイレクト先を開始URLとして扱います。
+    fn follow_redirect(&mut self, from: &str, to: &str, depth: usize) -> Option<String> {
+        if depth == 0
+            && from == self.start.as_str()
+            && let Ok(start) = Url::parse(to)
+        {
+            self.start = start;
+        }
+        if !self.in_scope(to) {
+            return Some(format!(
+                "Skipped {}: redirected out of scope to {}",
+                from, to
+            ));
+        }
+        let canonical_to = canonical::dedup_key(to);
+        let unseen = if canonical_to == canonical::dedup_key(from) {
+            self.redirects.insert(to.to_string())
+        } else {
+            self.seen.insert(canonical_to)
+        };
+        if unseen {
+            self.queue.push_front((to.to_string(), depth));
+        }
+        None
+    }
+
+    /// リンクをたどる対象か
+    fn in_scope(&self, url: &str) -> bool {
+        let Ok(parsed) = Url::parse(url) else {
+            return false;
+        };
+        if self
+            .options
+            .exclude
+            .iter()
+            .any(|pattern| config::glob_match(pattern, url))
+        {
+            return false;
+        }
+        if self.options.include.is_empty() {
+            parsed.origin() == self.start.origin()
+        } else {
+            self.options
+                .include
+                .iter()
+                .any(|pattern| config::glob_match(pattern, url))
+        }
+    }
+
+    /// `robots.txt` がURLの取得を許可しているか (オリジンごとに初回のみ取得する)
+    async fn allowed(&mut self, url: &str) -> bool {
+        let Ok(parsed) = Url::parse(url) else {
+            return false;
+        };
+        let origin = parsed.origin().ascii_serialization();
+        if !self.robots.contains_key(&origin) {
+            let robots = fetch_robots(&self.robots_client, &origin).await;
+            self.robots.insert(origin.clone(), robots);
+        }
+        let path = match parsed.query() {
+            Some(query) => format!("{}?{}", parsed.path(), query),
+            None => parsed.path().to_string(),
+        };
+        self.robots[&origin].allows(&path)
+    }
+
+    /// リクエストの間隔 (`Crawl-delay` の最大値とオプションの長い方)
+    fn delay(&self) -> Duration {
+        self.robots
+            .values()
+            .filter_map(|r| r.crawl_delay)
+            .filter(|_| self.options.respect_robots)
+            .fold(self.options.delay, Duration::max)
+    }
+}
+
+/// 前のリクエストの開始から `delay` 経過するまで待つ
+async fn wait_turn(next_request: &Mutex<Instant>, delay: Duration) {
+    let mut next = next_request.lock().await;
+    tokio::time::sleep_until(*next).await;
+    *next = Instant::now() + delay;
+}
+
+/// ページの取得結果
+enum Fetched {
+    Page(CrawledPage),
+    /// リダイレクト先 (`to`) はまだ取得していない
+    Redirect {
+        from: String,
+        to: String,
+        depth: usize,
+    },
+}
+
+/// ページを取得し、本文のMarkdownとクロール対象のリンクを取り出す (リダイレクトは追わずに返す)
+async fn fetch_page(client: &reqwest::Client, url: &str, depth: usize) -> Result<Fetched, String> {
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch {}: {}", url, e))?;
+    if response.status().is_redirection()
+        && let Some(location) = response
+            .headers()
+            .get(reqwest::header::LOCATION)
+            .and_then(|v| v.to_str().ok())
+    {
+        let mut to = response
+            .url()
+            .join(location.trim())
+            .map_err(|e| format!("Invalid redirect from {}: {}", url, e))?;
+        if !matches!(to.scheme(), "http" | "https") {
+            return Err(format!("Skipped {}: redirected to {}", url, to));
+        }
+        to.set_fragment(None);
+        return Ok(Fetched::Redirect {
+            from: url.to_string(),
+            to: to.to_string(),
+            depth,
+        });
+    }
+    if !response.status().is_success() {
+        return Err(format!(
+            "Failed to fetch {}: status {}",
+            url,
+            response.status()
+        ));
+    }
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("")
+        .to_lowercase();
+    if !content_type.is_empty() && !content_type.contains("html") {
+        return Err(format!(
+            "Skipped {}: not an HTML page ({})",
+            url, content_type
+        ));
+    }
+    let mut final_url = response.url().clone();
+    final_url.set_fragment(None);
+    let final_url = final_url.to_string();
+    let html = response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read {}: {}", url, e))?;
+    let markdown = browse::html_to_markdown(&html, &final_url)?;
+    let (title, links) = title_and_links(&html, &final_url);
+    Ok(Fetched::Page(CrawledPage {
+        url: final_url,
+        title,
+        depth,
+        fetched_at: search::now_iso(),
+        markdown,
+        links,
+    }))
+}
+
+/// HTMLから `<title>` と、`rel="nofollow"` でない `http`/`https` のリンク (フラグメントを除いた絶対URL) を取り出す
+fn title_and_links(html: &str, base_url: &str) -> (String, Vec<String>) {
+    let document = Html::parse_document(html);
+    let title_selector = Selector::parse("title").unwrap();
+    let title = document
+        .select(&title_selector)
+        .next()
+        .map(search::element_text)
+        .unwrap_or_default();
+    let base_selector = Selector::parse("base[href]").unwrap();
+    let base = document
+        .select(&base_selector)
+        .next()
+        .and_then(|b| {
+            Url::parse(base_url)
+                .ok()?
+                .join(b.value().attr("href")?)
+                .ok()
+        })
+        .or_else(|| Url::parse(base_url).ok());
+    let Some(base) = base else {
+        return (title, Vec::new());
+    };
+    let link_selector = Selector::parse("a[href]").unwrap();
+    let mut links = Vec::new();
+    for link in document.select(&link_selector) {
+        let nofollow = link.value().attr("rel").is_some_and(|rel| {
+            rel.split_whitespace()
+                .any(|r| r.eq_ignore_ascii_case("nofollow"))
+        });
+        let Some(mut url) = link
+            .value()
+            .attr("href")
+            .and_then(|h| base.join(h.trim()).ok())
+        else {
+            continue;
+        };
+        if nofollow || !matches!(url.scheme(), "http" | "https") {
+            continue;
+        }
+        url.set_fragment(None);
+        let url = url.to_string();
+        if !links.contains(&url) {
+            links.push(url);
+        }
+    }
+    (title, links)
+}
+
+/// `robots.txt` のうち、このクローラーに適用される規則
+#[derive(Debug, Clone, Default)]
+struct Robots {
+    /// (許可するか, パスのパターン)
+    rules: Vec<(bool, String)>,
+    crawl_delay: Option<Duration>,
+}
+
+impl Robots {
+    /// すべてのパスを禁止する規則
+    fn disallow_all() -> Robots {
+        Robots {
+            rules: vec![(false, "/".to_string())],
+            crawl_delay: None,
+        }
+    }
+
+    /// `robots.txt` を解析する。`www-search` を名指しするグループがあればそれを、なければ `*` のグループを使う
+    ///
+    /// `User-agent` の値はプロダクトトークン (`/` より前) を大文字・小文字を区別せずに比較します。
+    fn parse(text: &str) -> Robots {
+        // (User-agentの一覧, 規則)
+        let mut groups: Vec<(Vec<String>, Robots)> = Vec::new();
+        let mut in_agents = false;
+        for line in text.lines() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let (key, value) = (key.trim().to_lowercase(), value.trim());
+            if key == "user-agent" {
+                if !in_agents {
+                    groups.push((Vec::new(), Robots::default()));
+                }
+                in_agents = true;
+                if let Some((agents, _)) = groups.last_mut() {
+                    agents.push(value.to_lowercase());
+                }
+                continue;
+            }
+            in_agents = false;
+            let Some((_, robots)) = groups.last_mut() else {
+                continue;
+            };
+            match key.as_str() {
+                "allow" if !value.is_empty() => robots.rules.push((true, value.to_string())),
+                "disallow" if !value.is_empty() => robots.rules.push((false, value.to_string())),
+                "crawl-delay" => {
+                    robots.crawl_delay = value
+                        .parse::<f64>()
+                        .ok()
+                        .filter(|d| *d >= 0.0)
+                        .map(|d| Duration::from_secs_f64(d.min(MAX_CRAWL_DELAY.as_secs_f64())));
+                }
+                _ => {}
+            }
+        }
+        let named = |(agents, _): &&(Vec<String>, Robots)| {
+            agents
+                .iter()
+                .any(|a| a.split('/').next().unwrap_or("").trim() == ROBOTS_AGENT)
+        };
+        let wildcard = |(agents, _): &&(Vec<String>, Robots)| agents.iter().any(|a| a == "*");
+        let selected: Vec<&(Vec<String>, Robots)> = if groups.iter().any(|g| named(&g)) {
+            groups.iter().filter(named).collect()
+        } else {
+            groups.iter().filter(wildcard).collect()
+        };
+        let mut robots = Robots::default();
+        for (_, group) in selected {
+            robots.rules.extend(group.rules.iter().cloned());
+            robots.crawl_delay = robots.crawl_delay.max(group.crawl_delay);
+        }
+        robots
+    }
+
+    /// パス (クエリを含む) の取得を許可しているか。最も長く一致した規則に従い、同じ長さなら `Allow` を優先する
+    fn allows(&self, path: &str) -> bool {
+        let mut best: Option<(usize, bool)> = None;
+        for (allow, pattern) in &self.rules {
+            let matched = match pattern.strip_suffix('$') {
+                Some(exact) => config::glob_match(exact, path),
+                None => config::glob_match(&format!("{}*", pattern), path),
+            };
+            if matched
+                && best.is_none_or(|(len, a)| {
+                    pattern.len() > len || (pattern.len() == len && *allow && !a)
+                })
+            {
+                best = Some((pattern.len(), *allow));
+            }
+        }
+        best.is_none_or(|(_, allow)| allow)
+    }
+}
+
+/// オリジンの `robots.txt` を取得する
+///
+/// 存在しない場合 (4xx) はすべて許可し、サーバーエラー (5xx) や接続できない場合はすべて禁止します。
+async fn fetch_robots(client: &reqwest::Client, origin: &str) -> Robots {
+    let Ok(response) = client.get(format!("{}/robots.txt", origin)).send().await else {
+        return Robots::disallow_all();
+    };
+    let status = response.status();
+    if status.is_success() {
+        match response.text().await {
+            Ok(text) => Robots::parse(&text),
+            Err(_) => Robots::disallow_all(),
+        }
+    } else if status.is_client_error() {
+        Robots::default()
+    } else {
+        Robots::disallow_all()
+    }
+}
+
+/// ページのURLから書き出すMarkdownの相対パス (`<ホスト>/<パス>.md`) を作る
+fn page_path(url: &str) -> PathBuf {
+    let Ok(parsed) = Url::parse(url) else {
+        return PathBuf::from("page.md");
+    };
+    let mut path = PathBuf::from(sanitize(parsed.host_str().unwrap_or("page")));
+    let segments: Vec<String> = parsed
+        .path_segments()
+        .map(|s| {
+            s.filter(|s| !s.is_empty())
+                .map(|s| {
+                    urlencoding::decode(s)
+                        .map(|d| d.into_owned())
+                        .unwrap_or(s.to_string())
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let directory = parsed.path().ends_with('/') || segments.is_empty();
+    let (dirs, last) = if directory {
+        (&segments[..], "index".to_string())
+    } else {
+        let last = &segments[segments.len() - 1];
+        let stem = [".html", ".htm", ".php", ".aspx"]
+            .iter()
+            .find_map(|ext| last.strip_suffix(ext))
+            .unwrap_or(last);
+        (&segments[..segments.len() - 1], stem.to_string())
+    };
+    for dir in dirs {
+        path.push(sanitize(dir));
+    }
+    let mut name = sanitize(&last);
+    if let Some(query) = parsed.query() {
+        name = format!("{}_{}", name, sanitize(query));
+    }
+    if name.is_empty() {
+        name = "index".to_string();
+    }
+    path.push(format!("{}.md", name));
+    path
+}
+
+/// 書き出し済みのパスと重複しないパスにする (`name-1.md` など)
+fn unique_path(path: &Path, used: &mut HashSet<PathBuf>) -> PathBuf {
+    let stem = path.with_extension("");
+    let mut candidate = path.to_path_buf();
+    let mut n = 1;
+    while used.contains(&candidate) {
+        candidate = PathBuf::from(format!("{}-{}.md", stem.display(), n));
+        n += 1;
+    }
+    used.insert(candidate.clone());
+    candidate
+}
+
+/// パス要素に使えない文字を `_` に置き換え、長さを制限する
+fn sanitize(name: &str) -> String {
+    let sanitized: String = name
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .take(MAX_SEGMENT_CHARS)
+        .collect();
+    sanitized.trim_matches(|c| c == '_' || c == '.').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn named_group_takes_priority_over_wildcard() {
+        let robots = Robots::parse(
+            "User-agent: *\nDisallow: /\n\nUser-agent: Www-Search/1.0\nDisallow: /private\nCrawl-delay: 2\n",
+        );
+        assert!(robots.allows("/docs"));
+        assert!(!robots.allows("/private/a"));
+        assert_eq!(robots.crawl_delay, Some(Duration::from_secs(2)));
+
+        let robots =
+            Robots::parse("User-agent: otherbot\nDisallow: /\n\nUser-agent: *\nDisallow: /tmp\n");
+        assert!(robots.allows("/docs"));
+        assert!(!robots.allows("/tmp/a"));
+    }
+
+    #[test]
+    fn longer_allow_overrides_disallow_all() {
+        let robots = Robots::parse("User-agent: *\nDisallow: /\nAllow: /public\n");
+        assert!(robots.allows("/public"));
+        assert!(robots.allows("/public/a.html?x=1"));
+        assert!(!robots.allows("/"));
+        assert!(!robots.allows("/private"));
+    }
+
+    #[test]
+    fn wildcard_and_end_anchor() {
+        let robots = Robots::parse("User-agent: *\nDisallow: /*.pdf$\n");
+        assert!(!robots.allows("/a.pdf"));
+        assert!(!robots.allows("/docs/b.pdf"));
+        assert!(robots.allows("/a.pdf?download=1"));
+        assert!(robots.allows("/a.pdfx"));
+    }
+
+    #[test]
+    fn page_paths_stay_inside_the_host_directory() {
+        let path = |url: &str| page_path(url).to_string_lossy().replace('\\', "/");
+        assert_eq!(path("https://example.com/"), "example.com/index.md");
+        assert_eq!(path("https://example.com/a/"), "example.com/a/index.md");
+        assert_eq!(
+            path("https://example.com/a.html?x=1"),
+            "example.com/a_x_1.md"
+        );
+        assert_eq!(path("https://example.com/%2e%2e/x"), "example.com/x.md");
+        assert_eq!(
+            path("https://example.com/a%2F..%2F..%2Fb"),
+            "example.com/a_.._.._b.md"
+        );
+    }
+}
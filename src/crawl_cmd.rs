@@ -0,0 +1,56 @@
+// src/crawl_cmd.rs
+
+//! `www-search crawl <URL>` サブコマンド
+//!
+//! 開始URLからリンクをたどってページを取得し、`--output` に書き出します。
+//! `--output` の拡張子が `.jsonl` ならJSON Linesのファイル、それ以外はディレクトリ
+//! (ページごとのMarkdownと `manifest.json`) に書き出します (省略時は `crawl` ディレクトリ)。
+
+use futures::StreamExt;
+use www_search::crawl::{self, CrawlOptions, CrawlOutput, CrawlWriter};
+
+/// クロールを実行し、進捗を表示します。
+pub async fn run(
+    start_url: &str,
+    options: CrawlOptions,
+    output: CrawlOutput,
+) -> Result<(), String> {
+    if start_url.is_empty() {
+        return Err(
+            "Usage: crawl <URL> [--depth N] [--max-pages N] [--output DIR|FILE.jsonl]".to_string(),
+        );
+    }
+    match url::Url::parse(start_url) {
+        Ok(url) if matches!(url.scheme(), "http" | "https") => {}
+        _ => return Err(format!("Invalid start URL: {}", start_url)),
+    }
+    let mut writer = CrawlWriter::new(start_url, &output)?;
+    let mut pages = crawl::crawl(start_url, options);
+    let (mut saved, mut failed) = (0, 0);
+    while let Some(page) = pages.next().await {
+        match page {
+            Ok(page) => {
+                let path = writer.write(&page)?;
+                crate::index_page(&page.url, &page.title, &page.markdown).await;
+                saved += 1;
+                match path {
+                    Some(path) => println!("[{}] {} -> {}", page.depth, page.url, path.display()),
+                    None => println!("[{}] {}", page.depth, page.url),
+                }
+            }
+            Err(e) => {
+                eprintln!("{}", e);
+                writer.error(&e);
+                failed += 1;
+            }
+        }
+    }
+    let path = writer.finish()?;
+    println!(
+        "Crawled {} pages ({} skipped or failed). Output: {}",
+        saved,
+        failed,
+        path.display()
+    );
+    Ok(())
+}
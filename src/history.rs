@@ -0,0 +1,234 @@
+// src/history.rs
+
+//! 検索・閲覧の履歴をJSON Lines形式のファイルに保存するモジュール
+//!
+//! 履歴は `[history] path` (未指定なら `$XDG_DATA_HOME/www-search/history.jsonl`
+//! または `~/.local/share/www-search/history.jsonl`) に1行1件で追記します。
+//! `[history] enabled = false` で記録を無効にでき、`retention_days` より古い履歴は
+//! 記録時に削除します (1回の実行で1度だけ)。Unixでは履歴ファイルを所有者のみ読み書きできる
+//! パーミッション (0600) で作成します。
+//!
+//! ```toml
+//! [history]
+//! enabled = true
+//! retention_days = 90
+//! ```
+
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{EngineType, SearchData, Vertical, config, search};
+
+/// この実行で保存期間を過ぎた履歴を削除したか
+static PRUNED: AtomicBool = AtomicBool::new(false);
+
+/// 履歴の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EntryKind {
+    /// 検索
+    Search,
+    /// ページの閲覧
+    Browse,
+}
+
+/// 履歴の1件
+///
+/// - `timestamp`: 記録した日時 (UTCのISO 8601形式)
+/// - `kind`: 検索か閲覧か
+/// - `query`: 検索クエリ (閲覧では、そのページを開いた検索のクエリ。URLを直接開いた場合は空)
+/// - `engines`: 検索したエンジンの名前
+/// - `vertical`: 検索の種類 (`web`, `news` など)
+/// - `results`: 検索結果 (タイトルとURL)
+/// - `url`, `title`: 閲覧したページのURLとタイトル
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Entry {
+    pub timestamp: String,
+    pub kind: EntryKind,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub query: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub engines: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vertical: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub results: Vec<ResultRef>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+}
+
+/// 履歴に保存する検索結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResultRef {
+    pub title: String,
+    pub url: String,
+}
+
+impl Entry {
+    /// 現在時刻の検索の履歴を作成します。
+    pub fn search(
+        query: &str,
+        engines: &[EngineType],
+        vertical: Vertical,
+        results: &[SearchData],
+    ) -> Entry {
+        Entry {
+            timestamp: search::now_iso(),
+            kind: EntryKind::Search,
+            query: query.to_string(),
+            engines: engines.iter().map(|e| e.name().to_string()).collect(),
+            vertical: (vertical != Vertical::Web).then(|| vertical.name().to_string()),
+            results: results
+                .iter()
+                .map(|r| ResultRef {
+                    title: r.title.clone(),
+                    url: r.url.clone(),
+                })
+                .collect(),
+            url: None,
+            title: None,
+        }
+    }
+
+    /// 現在時刻の閲覧の履歴を作成します。
+    ///
+    /// `query` はページを開いた検索のクエリ (URLを直接開いた場合は空文字列) です。
+    pub fn browse(url: &str, title: Option<&str>, query: &str) -> Entry {
+        Entry {
+            timestamp: search::now_iso(),
+            kind: EntryKind::Browse,
+            query: query.to_string(),
+            engines: Vec::new(),
+            vertical: None,
+            results: Vec::new(),
+            url: Some(url.to_string()),
+            title: title.filter(|t| !t.is_empty()).map(str::to_string),
+        }
+    }
+
+    /// 履歴の内容が文字列を含むか (大文字・小文字を区別しない)
+    pub fn matches(&self, term: &str) -> bool {
+        let term = term.to_lowercase();
+        let contains = |s: &str| s.to_lowercase().contains(&term);
+        contains(&self.query)
+            || self.url.as_deref().is_some_and(contains)
+            || self.title.as_deref().is_some_and(contains)
+            || self
+                .results
+                .iter()
+                .any(|r| contains(&r.title) || contains(&r.url))
+    }
+}
+
+/// 履歴ファイルのパス。設定がなく、ホームディレクトリも分からない場合は `None` を返します。
+pub fn path() -> Option<PathBuf> {
+    if let Some(path) = config::current().history.path.clone() {
+        return Some(path);
+    }
+    Some(config::data_dir()?.join("history.jsonl"))
+}
+
+/// 履歴を1件追記します。
+///
+/// 設定で履歴が無効 (`[history] enabled = false`、CLIの `--incognito`) の場合は何もしません。
+/// 保存期間 (`retention_days`) が設定されている場合は、この実行で初めて記録するときに期間を過ぎた履歴を削除します。
+pub fn record(entry: &Entry) -> Result<(), String> {
+    let config = config::current();
+    if !config.history.enabled {
+        return Ok(());
+    }
+    let path = path().ok_or("Could not determine the history file path")?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    let line = serde_json::to_string(entry)
+        .map_err(|e| format!("Failed to serialize history entry: {}", e))?;
+    let mut file = config::private_file_options()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    writeln!(file, "{}", line).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    if config.history.retention_days.is_some() && !PRUNED.swap(true, Ordering::Relaxed) {
+        prune()?;
+    }
+    Ok(())
+}
+
+/// 保存されている履歴を古い順に返します。
+///
+/// 保存期間を過ぎた履歴と、読み込めない行は除きます。履歴ファイルがなければ空のリストを返します。
+pub fn load() -> Result<Vec<Entry>, String> {
+    let Some(path) = path() else {
+        return Ok(Vec::new());
+    };
+    let text = match std::fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+    };
+    let cutoff = cutoff();
+    Ok(text
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Entry>(line).ok())
+        .filter(|entry| cutoff.as_ref().is_none_or(|c| entry.timestamp >= *c))
+        .collect())
+}
+
+/// 文字列を含む履歴を古い順に返します。
+pub fn search(term: &str) -> Result<Vec<Entry>, String> {
+    Ok(load()?.into_iter().filter(|e| e.matches(term)).collect())
+}
+
+/// 保存期間を過ぎた履歴をファイルから削除します。
+///
+/// 残す履歴を一時ファイルに書き込んでから置き換えるため、途中で失敗しても履歴は失われません。
+pub fn prune() -> Result<(), String> {
+    let Some(path) = path() else {
+        return Ok(());
+    };
+    if cutoff().is_none() || !path.exists() {
+        return Ok(());
+    }
+    let entries = load()?;
+    let mut text = String::new();
+    for entry in &entries {
+        let line = serde_json::to_string(entry)
+            .map_err(|e| format!("Failed to serialize history entry: {}", e))?;
+        text.push_str(&line);
+        text.push('\n');
+    }
+    config::write_atomic(&path, &text)
+}
+
+/// すべての履歴を削除します。
+pub fn clear() -> Result<(), String> {
+    let Some(path) = path() else {
+        return Ok(());
+    };
+    match std::fs::remove_file(&path) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(format!("Failed to remove {}: {}", path.display(), e)),
+    }
+}
+
+/// すべての履歴をJSONの配列として書き出します。
+pub fn export_json() -> Result<String, String> {
+    serde_json::to_string_pretty(&load()?)
+        .map_err(|e| format!("Failed to serialize history: {}", e))
+}
+
+/// 保存期間の開始日時。保存期間が設定されていなければ `None`
+fn cutoff() -> Option<String> {
+    let days = config::current().history.retention_days?;
+    Some(search::format_unix_time(
+        search::unix_now() - (days as i64).saturating_mul(86400),
+    ))
+}
@@ -0,0 +1,120 @@
+// src/history_cmd.rs
+
+//! `www-search history` サブコマンド
+//!
+//! - `history list`: 履歴を新しい順に表示する (`--max-results` で件数を指定)
+//! - `history search <文字列>`: クエリ・URL・タイトルに文字列を含む履歴を表示する
+//! - `history rerun <番号>`: `list` の番号の検索 (閲覧の履歴ではページの閲覧) をもう一度実行する
+//! - `history clear`: すべての履歴を削除する
+//! - `history export [ファイル]`: 履歴をJSONで書き出す (ファイルを省略すると標準出力)
+
+use www_search::config::OutputFormat;
+use www_search::history::{self, Entry, EntryKind};
+
+/// サブコマンドを実行します。`rerun` の場合は再実行する履歴を返します。
+pub fn run(
+    command: &str,
+    argument: &str,
+    format: OutputFormat,
+    limit: Option<usize>,
+) -> Result<Option<Entry>, String> {
+    match command {
+        "" | "list" => print_entries(numbered(history::load()?), format, limit),
+        "search" => {
+            if argument.is_empty() {
+                return Err("Usage: history search <text>".to_string());
+            }
+            let entries = numbered(history::load()?)
+                .into_iter()
+                .filter(|(_, e)| e.matches(argument))
+                .collect();
+            print_entries(entries, format, limit)
+        }
+        "rerun" => {
+            let number: usize = argument
+                .parse()
+                .map_err(|_| "Usage: history rerun <number>".to_string())?;
+            return numbered(history::load()?)
+                .into_iter()
+                .find(|(n, _)| *n == number)
+                .map(|(_, entry)| Some(entry))
+                .ok_or_else(|| format!("No history entry #{}", number));
+        }
+        "clear" => {
+            history::clear()?;
+            println!("History cleared.");
+        }
+        "export" => {
+            let json = history::export_json()?;
+            if argument.is_empty() {
+                println!("{}", json);
+            } else {
+                std::fs::write(argument, json + "\n")
+                    .map_err(|e| format!("Failed to write {}: {}", argument, e))?;
+                println!("Exported history to {}", argument);
+            }
+        }
+        _ => {
+            return Err(format!(
+                "Unknown history command: {} (list, search, rerun, clear, export)",
+                command
+            ));
+        }
+    }
+    Ok(None)
+}
+
+/// 履歴を新しい順に並べ、1始まりの番号を付ける
+fn numbered(entries: Vec<Entry>) -> Vec<(usize, Entry)> {
+    entries
+        .into_iter()
+        .rev()
+        .enumerate()
+        .map(|(i, e)| (i + 1, e))
+        .collect()
+}
+
+fn print_entries(entries: Vec<(usize, Entry)>, format: OutputFormat, limit: Option<usize>) {
+    let entries = entries.into_iter().take(limit.unwrap_or(usize::MAX));
+    if format == OutputFormat::Json {
+        let entries: Vec<Entry> = entries.map(|(_, e)| e).collect();
+        match serde_json::to_string_pretty(&entries) {
+            Ok(json) => println!("{}", json),
+            Err(e) => eprintln!("Failed to serialize JSON: {}", e),
+        }
+        return;
+    }
+    let mut empty = true;
+    for (number, entry) in entries {
+        empty = false;
+        match entry.kind {
+            EntryKind::Search => println!(
+                "[{}] {}  search  {}{}  \"{}\" ({} results)",
+                number,
+                entry.timestamp,
+                entry.engines.join(","),
+                entry
+                    .vertical
+                    .as_deref()
+                    .map(|v| format!(" ({})", v))
+                    .unwrap_or_default(),
+                entry.query,
+                entry.results.len()
+            ),
+            EntryKind::Browse => println!(
+                "[{}] {}  browse  {}{}",
+                number,
+                entry.timestamp,
+                entry.url.as_deref().unwrap_or(""),
+                entry
+                    .title
+                    .as_deref()
+                    .map(|t| format!("  {}", t))
+                    .unwrap_or_default()
+            ),
+        }
+    }
+    if empty {
+        println!("No history.");
+    }
+}
@@ -0,0 +1,169 @@
+// src/images.rs
+
+//! 画像検索 ([`crate::Vertical::Images`]) の結果の画像をダウンロードするモジュール
+//!
+//! ファイル名は画像URLの末尾 (なければタイトル) から作成し、拡張子がない場合は
+//! `Content-Type` から補います。同名のファイルがある場合は `name-1.jpg` のように連番を付け、
+//! 既存のファイルを上書きしません。
+
+use std::path::{Path, PathBuf};
+
+use crate::SearchData;
+
+/// ファイル名の最大文字数 (拡張子を除く)
+const MAX_STEM_CHARS: usize = 80;
+
+/// 画像検索の結果の画像を、指定したディレクトリにダウンロードします。
+///
+/// # 引数
+/// - `data`: 画像検索の結果 (`url` が画像本体のURL)。
+/// - `dir`: 保存先のディレクトリ (なければ作成します)。
+///
+/// # 戻り値
+/// `Result<PathBuf, String>`:
+/// - 成功した場合: 保存したファイルのパス。
+/// - 失敗した場合: エラーメッセージ文字列。
+pub async fn download_image(data: &SearchData, dir: &Path) -> Result<PathBuf, String> {
+    let client = crate::search::client(None, Some(crate::search::BROWSER_USER_AGENT))?;
+    let mut request = client.get(&data.url);
+    if let Some(page) = data.metadata.get("source_page") {
+        request = request.header("Referer", page);
+    }
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch image: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("Failed to fetch image: HTTP {}", response.status()));
+    }
+    let content_type = content_type(response.headers());
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|e| format!("Failed to read image: {}", e))?;
+    save(data, dir, content_type.as_deref(), &bytes)
+}
+
+/// 画像検索の結果の画像を、指定したディレクトリに同期でダウンロードします。
+pub fn download_image_sync(data: &SearchData, dir: &Path) -> Result<PathBuf, String> {
+    let client = crate::search::blocking_client(None, Some(crate::search::BROWSER_USER_AGENT))?;
+    let mut request = client.get(&data.url);
+    if let Some(page) = data.metadata.get("source_page") {
+        request = request.header("Referer", page);
+    }
+    let response = request
+        .send()
+        .map_err(|e| format!("Failed to fetch image: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("Failed to fetch image: HTTP {}", response.status()));
+    }
+    let content_type = content_type(response.headers());
+    let bytes = response
+        .bytes()
+        .map_err(|e| format!("Failed to read image: {}", e))?;
+    save(data, dir, content_type.as_deref(), &bytes)
+}
+
+fn content_type(headers: &reqwest::header::HeaderMap) -> Option<String> {
+    headers
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.split(';').next().unwrap_or("").trim().to_lowercase())
+}
+
+/// 取得した画像を重複しないファイル名で保存する
+fn save(
+    data: &SearchData,
+    dir: &Path,
+    content_type: Option<&str>,
+    bytes: &[u8],
+) -> Result<PathBuf, String> {
+    if content_type.is_some_and(|t| t.starts_with("text/")) {
+        return Err(format!("Not an image: {}", data.url));
+    }
+    std::fs::create_dir_all(dir)
+        .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let (stem, extension) = file_name(data, content_type);
+    let mut path = dir.join(format!("{}.{}", stem, extension));
+    let mut n = 1;
+    while path.exists() {
+        path = dir.join(format!("{}-{}.{}", stem, n, extension));
+        n += 1;
+    }
+    std::fs::write(&path, bytes)
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    Ok(path)
+}
+
+/// 保存するファイル名を (拡張子を除いた名前, 拡張子) として返す
+fn file_name(data: &SearchData, content_type: Option<&str>) -> (String, &'static str) {
+    let last_segment = url::Url::parse(&data.url)
+        .ok()
+        .and_then(|u| {
+            u.path_segments()
+                .and_then(|mut s| s.next_back().map(str::to_string))
+        })
+        .map(|s| urlencoding::decode(&s).map(|d| d.into_owned()).unwrap_or(s))
+        .unwrap_or_default();
+    let (name, url_extension) = match last_segment.rsplit_once('.') {
+        Some((name, ext)) => (name.to_string(), extension_from_name(ext)),
+        None => (last_segment.clone(), None),
+    };
+    let extension = content_type
+        .and_then(extension_from_content_type)
+        .or(url_extension)
+        .unwrap_or("jpg");
+    let stem = sanitize(if name.is_empty() { &data.title } else { &name });
+    let stem = if stem.is_empty() {
+        "image".to_string()
+    } else {
+        stem
+    };
+    (stem, extension)
+}
+
+fn extension_from_content_type(content_type: &str) -> Option<&'static str> {
+    match content_type {
+        "image/jpeg" | "image/jpg" | "image/pjpeg" => Some("jpg"),
+        "image/png" => Some("png"),
+        "image/gif" => Some("gif"),
+        "image/webp" => Some("webp"),
+        "image/svg+xml" => Some("svg"),
+        "image/avif" => Some("avif"),
+        "image/bmp" => Some("bmp"),
+        "image/x-icon" | "image/vnd.microsoft.icon" => Some("ico"),
+        "image/tiff" => Some("tiff"),
+        _ => None,
+    }
+}
+
+fn extension_from_name(extension: &str) -> Option<&'static str> {
+    match extension.to_lowercase().as_str() {
+        "jpg" | "jpeg" | "jfif" => Some("jpg"),
+        "png" => Some("png"),
+        "gif" => Some("gif"),
+        "webp" => Some("webp"),
+        "svg" => Some("svg"),
+        "avif" => Some("avif"),
+        "bmp" => Some("bmp"),
+        "ico" => Some("ico"),
+        "tif" | "tiff" => Some("tiff"),
+        _ => None,
+    }
+}
+
+/// ファイル名に使えない文字を `_` に置き換え、長さを制限する
+fn sanitize(name: &str) -> String {
+    let sanitized: String = name
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .take(MAX_STEM_CHARS)
+        .collect();
+    sanitized.trim_matches(|c| c == '_' || c == '.').to_string()
+}
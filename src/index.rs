@@ -0,0 +1,333 @@
+// src/index.rs
+
+//! 閲覧したページのローカル全文検索インデックス (tantivy) を管理するモジュール
+//!
+//! `[index] enabled = true` の場合、CLI・TUIで閲覧したページ (とクロールしたページ) の
+//! 本文 (Markdown) とタイトル・URL・取得日時を [`add`] でインデックスに保存します。保存したページは
+//! `local` エンジン ([`crate::EngineType::Local`]) でオフラインでも検索できます。
+//!
+//! このモジュールはcargoの `index` フィーチャー (デフォルトで有効) が必要です。
+//!
+//! インデックスは `[index] path` (未指定なら `$XDG_DATA_HOME/www-search/index`) に置きます。
+//! 日本語などの分かち書きしない文字は2文字ずつ (bigram) に区切って索引付けします。
+//!
+//! ```toml
+//! [index]
+//! enabled = true
+//! ```
+
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use tantivy::collector::TopDocs;
+use tantivy::directory::MmapDirectory;
+use tantivy::schema::{
+    Field, IndexRecordOption, STORED, STRING, Schema, TextFieldIndexing, TextOptions, Value,
+};
+use tantivy::snippet::SnippetGenerator;
+use tantivy::tokenizer::{
+    LowerCaser, RemoveLongFilter, TextAnalyzer, Token, TokenStream, Tokenizer,
+};
+use tantivy::{Index, IndexWriter, TantivyDocument, Term, doc};
+
+use crate::{SearchData, canonical, config, search};
+
+/// タイトル・本文の索引付けに使用するトークナイザの名前
+const TOKENIZER: &str = "www_search";
+
+/// インデックスへの書き込みに使用するメモリ量 (tantivyの最小値)
+const WRITER_MEMORY: usize = 15_000_000;
+
+/// 検索結果の説明に使用する本文の抜粋の最大文字数
+const SNIPPET_CHARS: usize = 200;
+
+/// 同じプロセス内での書き込みを直列化するロック (tantivyのライターはインデックスごとに1つ)
+static WRITE_LOCK: Mutex<()> = Mutex::new(());
+
+/// インデックスのフィールド
+struct Fields {
+    url: Field,
+    title: Field,
+    body: Field,
+    fetched_at: Field,
+}
+
+/// インデックスのディレクトリ。設定がなく、ホームディレクトリも分からない場合は `None` を返します。
+pub fn path() -> Option<PathBuf> {
+    if let Some(path) = config::current().index.path.clone() {
+        return Some(path);
+    }
+    Some(config::data_dir()?.join("index"))
+}
+
+/// インデックスが有効か (`[index] enabled`)
+pub fn enabled() -> bool {
+    config::current().index.enabled
+}
+
+/// ページをインデックスに追加します。同じURL (正規化後) のページがあれば置き換えます。
+///
+/// インデックスが無効な場合は何もしません。書き込みとディスクへの同期は
+/// ブロッキング処理用のスレッドで行います。
+pub async fn add(url: &str, title: &str, body: &str) -> Result<(), String> {
+    if !enabled() {
+        return Ok(());
+    }
+    let (url, title, body) = (url.to_string(), title.to_string(), body.to_string());
+    tokio::task::spawn_blocking(move || add_sync(&url, &title, &body))
+        .await
+        .map_err(|e| format!("Failed to add page to index: {}", e))?
+}
+
+/// ページを同期でインデックスに追加します。
+pub fn add_sync(url: &str, title: &str, body: &str) -> Result<(), String> {
+    if !enabled() {
+        return Ok(());
+    }
+    let url = canonical::canonicalize(url);
+    let fetched_at = search::unix_now();
+    let _guard = WRITE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let (index, fields) = open()?;
+    let mut writer = writer(&index)?;
+    writer.delete_term(Term::from_field_text(fields.url, &url));
+    writer
+        .add_document(doc!(
+            fields.url => url.as_str(),
+            fields.title => title,
+            fields.body => body,
+            fields.fetched_at => search::format_unix_time(fetched_at),
+        ))
+        .map_err(|e| format!("Failed to add page to index: {}", e))?;
+    writer
+        .commit()
+        .map_err(|e| format!("Failed to commit index: {}", e))?;
+    Ok(())
+}
+
+/// URL (正規化後) のページをインデックスから削除します。
+pub fn remove(url: &str) -> Result<(), String> {
+    let url = canonical::canonicalize(url);
+    let _guard = WRITE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let (index, fields) = open()?;
+    let mut writer = writer(&index)?;
+    writer.delete_term(Term::from_field_text(fields.url, &url));
+    writer
+        .commit()
+        .map_err(|e| format!("Failed to commit index: {}", e))?;
+    Ok(())
+}
+
+/// インデックスのすべてのページを削除します。
+pub fn clear() -> Result<(), String> {
+    let _guard = WRITE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let (index, _) = open()?;
+    let mut writer = writer(&index)?;
+    writer
+        .delete_all_documents()
+        .map_err(|e| format!("Failed to clear index: {}", e))?;
+    writer
+        .commit()
+        .map_err(|e| format!("Failed to commit index: {}", e))?;
+    Ok(())
+}
+
+/// インデックスを検索し、スコアの高い順に `offset` 件目から最大 `limit` 件の結果を返します。
+///
+/// クエリはtantivyの構文 (`"フレーズ"`, `-除外`, `OR`) で解釈し、語句はすべて含むものを返します。
+/// 検索結果の `description` は本文の抜粋で、`metadata` の `fetched_at` にページを取得した日時
+/// (UTCのISO 8601形式) を格納します。インデックスがまだなければ空のリストを返します。
+pub fn search(query: &str, offset: usize, limit: usize) -> Result<Vec<SearchData>, String> {
+    let Some(dir) = path() else {
+        return Ok(Vec::new());
+    };
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let (index, fields) = open()?;
+    let reader = index
+        .reader()
+        .map_err(|e| format!("Failed to open index reader: {}", e))?;
+    let searcher = reader.searcher();
+    let mut parser =
+        tantivy::query::QueryParser::for_index(&index, vec![fields.title, fields.body]);
+    parser.set_conjunction_by_default();
+    parser.set_field_boost(fields.title, 2.0);
+    // 構文エラーの箇所は無視して、解釈できた部分で検索する
+    let (parsed, _) = parser.parse_query_lenient(query);
+    let top = searcher
+        .search(&parsed, &TopDocs::with_limit(limit).and_offset(offset))
+        .map_err(|e| format!("Failed to search index: {}", e))?;
+    let mut snippets = SnippetGenerator::create(&searcher, &*parsed, fields.body)
+        .map_err(|e| format!("Failed to create snippet: {}", e))?;
+    snippets.set_max_num_chars(SNIPPET_CHARS);
+
+    let mut results = Vec::new();
+    for (_, address) in top {
+        let document: TantivyDocument = searcher
+            .doc(address)
+            .map_err(|e| format!("Failed to read indexed page: {}", e))?;
+        let text = |field: Field| {
+            document
+                .get_first(field)
+                .and_then(|v| v.as_str().map(str::to_string))
+                .unwrap_or_default()
+        };
+        let url = text(fields.url);
+        let title = text(fields.title);
+        let snippet = snippets.snippet_from_doc(&document);
+        let description = if snippet.fragment().is_empty() {
+            text(fields.body).chars().take(SNIPPET_CHARS).collect()
+        } else {
+            snippet.fragment().to_string()
+        };
+        let mut result = SearchData {
+            title: if title.is_empty() { url.clone() } else { title },
+            url,
+            description: description.split_whitespace().collect::<Vec<_>>().join(" "),
+            ..Default::default()
+        };
+        let fetched_at = text(fields.fetched_at);
+        if !fetched_at.is_empty() {
+            result.metadata.insert("fetched_at".to_string(), fetched_at);
+        }
+        results.push(result);
+    }
+    Ok(results)
+}
+
+/// インデックスを開く (なければ作成する)
+fn open() -> Result<(Index, Fields), String> {
+    let dir = path().ok_or("Could not determine the index directory")?;
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let directory = MmapDirectory::open(&dir)
+        .map_err(|e| format!("Failed to open {}: {}", dir.display(), e))?;
+    let index = Index::open_or_create(directory, schema())
+        .map_err(|e| format!("Failed to open index {}: {}", dir.display(), e))?;
+    index.tokenizers().register(
+        TOKENIZER,
+        TextAnalyzer::builder(BigramTokenizer)
+            .filter(RemoveLongFilter::limit(40))
+            .filter(LowerCaser)
+            .build(),
+    );
+    let schema = index.schema();
+    let field = |name: &str| {
+        schema
+            .get_field(name)
+            .map_err(|e| format!("Invalid index schema: {}", e))
+    };
+    let fields = Fields {
+        url: field("url")?,
+        title: field("title")?,
+        body: field("body")?,
+        fetched_at: field("fetched_at")?,
+    };
+    Ok((index, fields))
+}
+
+fn schema() -> Schema {
+    let text = TextOptions::default()
+        .set_indexing_options(
+            TextFieldIndexing::default()
+                .set_tokenizer(TOKENIZER)
+                .set_index_option(IndexRecordOption::WithFreqsAndPositions),
+        )
+        .set_stored();
+    let mut builder = Schema::builder();
+    builder.add_text_field("url", STRING | STORED);
+    builder.add_text_field("title", text.clone());
+    builder.add_text_field("body", text);
+    builder.add_text_field("fetched_at", STORED);
+    builder.build()
+}
+
+fn writer(index: &Index) -> Result<IndexWriter, String> {
+    index
+        .writer_with_num_threads(1, WRITER_MEMORY)
+        .map_err(|e| format!("Failed to open index writer: {}", e))
+}
+
+/// 英数字などは連続する部分を1語、日本語・中国語・韓国語の文字は2文字ずつ重ねて区切るトークナイザ
+///
+/// `全文検索エンジン` は `全文`, `文検`, `検索`, … に区切ります (1文字だけの場合はその1文字)。
+#[derive(Clone)]
+struct BigramTokenizer;
+
+struct BigramTokenStream {
+    tokens: Vec<Token>,
+    index: usize,
+}
+
+impl Tokenizer for BigramTokenizer {
+    type TokenStream<'a> = BigramTokenStream;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> BigramTokenStream {
+        let mut tokens = Vec::new();
+        let mut push = |from: usize, to: usize| {
+            tokens.push(Token {
+                offset_from: from,
+                offset_to: to,
+                position: tokens.len(),
+                text: text[from..to].to_string(),
+                position_length: 1,
+            });
+        };
+        let chars: Vec<(usize, char)> = text.char_indices().collect();
+        let end_of = |i: usize| chars.get(i).map_or(text.len(), |(offset, _)| *offset);
+        let mut i = 0;
+        while i < chars.len() {
+            let (start, c) = chars[i];
+            if is_cjk(c) {
+                let mut j = i;
+                while j < chars.len() && is_cjk(chars[j].1) {
+                    j += 1;
+                }
+                if j - i == 1 {
+                    push(start, end_of(j));
+                }
+                for (k, (offset, _)) in chars[i..j - 1].iter().enumerate() {
+                    push(*offset, end_of(i + k + 2));
+                }
+                i = j;
+            } else if c.is_alphanumeric() {
+                let mut j = i;
+                while j < chars.len() && chars[j].1.is_alphanumeric() && !is_cjk(chars[j].1) {
+                    j += 1;
+                }
+                push(start, end_of(j));
+                i = j;
+            } else {
+                i += 1;
+            }
+        }
+        BigramTokenStream { tokens, index: 0 }
+    }
+}
+
+impl TokenStream for BigramTokenStream {
+    fn advance(&mut self) -> bool {
+        self.index += 1;
+        self.index <= self.tokens.len()
+    }
+
+    fn token(&self) -> &Token {
+        &self.tokens[self.index - 1]
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        &mut self.tokens[self.index - 1]
+    }
+}
+
+/// 分かち書きしない文字 (ひらがな・カタカナ・漢字・ハングル) かどうか
+fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{3005}'
+        | '\u{3040}'..='\u{30FF}'
+        | '\u{3400}'..='\u{4DBF}'
+        | '\u{4E00}'..='\u{9FFF}'
+        | '\u{AC00}'..='\u{D7AF}'
+        | '\u{F900}'..='\u{FAFF}'
+        | '\u{FF66}'..='\u{FF9F}')
+}
@@ -5,20 +5,131 @@
 //! このライブラリは、Google, Bing, DuckDuckGoなどの様々な検索エンジンを使用して、ネット検索を行うことができます。
 
 // searchモジュールを宣言し、その中の関数や型を公開する
+pub mod bang;
+pub mod bibtex;
+pub mod bookmarks;
 pub mod browse;
+pub mod canonical;
+pub mod config;
+pub mod crawl;
+pub mod history;
+pub mod images;
+#[cfg(feature = "index")]
+pub mod index;
 pub mod search;
-use crate::search::{duckduckgo, google};
+pub mod stream;
+use crate::search::{
+    arxiv, brave, crates_io, crossref, duckduckgo, github, goo, google, local, query, searxng,
+    stackexchange, wikipedia, yahoo_japan,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::ControlFlow;
+use std::str::FromStr;
 
 /// 検索エンジンの種類を定義するEnum
 ///
 /// - `Google`: Google検索 (デフォルト)
 /// - `Bing`: Bing検索
 /// - `DuckDuckGo`: DuckDuckGo検索
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
 pub enum EngineType {
     #[default]
     Google, // default
     DuckDuckGo,
+    Brave,
+    Searxng,
+    Wikipedia,
+    YahooJapan,
+    Goo,
+    Github,
+    StackExchange,
+    Crates,
+    DocsRs,
+    Arxiv,
+    Crossref,
+    Local,
+}
+
+impl EngineType {
+    /// 利用可能なすべての検索エンジン
+    pub const ALL: &'static [EngineType] = &[
+        EngineType::Google,
+        EngineType::DuckDuckGo,
+        EngineType::Brave,
+        EngineType::Searxng,
+        EngineType::Wikipedia,
+        EngineType::YahooJapan,
+        EngineType::Goo,
+        EngineType::Github,
+        EngineType::StackExchange,
+        EngineType::Crates,
+        EngineType::DocsRs,
+        EngineType::Arxiv,
+        EngineType::Crossref,
+        EngineType::Local,
+    ];
+
+    /// 設定ファイルやCLIで使用するエンジン名
+    pub fn name(self) -> &'static str {
+        match self {
+            EngineType::Google => "google",
+            EngineType::DuckDuckGo => "duckduckgo",
+            EngineType::Brave => "brave",
+            EngineType::Searxng => "searxng",
+            EngineType::Wikipedia => "wikipedia",
+            EngineType::YahooJapan => "yahoo_japan",
+            EngineType::Goo => "goo",
+            EngineType::Github => "github",
+            EngineType::StackExchange => "stackexchange",
+            EngineType::Crates => "crates",
+            EngineType::DocsRs => "docsrs",
+            EngineType::Arxiv => "arxiv",
+            EngineType::Crossref => "crossref",
+            EngineType::Local => "local",
+        }
+    }
+
+    /// 指定した検索の種類 (バーティカル) に対応しているかを返します。
+    pub fn supports(self, vertical: Vertical) -> bool {
+        match vertical {
+            Vertical::Web => true,
+            Vertical::News | Vertical::Images | Vertical::Videos => matches!(
+                self,
+                EngineType::Google | EngineType::DuckDuckGo | EngineType::Searxng
+            ),
+        }
+    }
+}
+
+impl fmt::Display for EngineType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for EngineType {
+    type Err = String;
+
+    /// エンジン名 (大文字・小文字を区別しない) から `EngineType` を取得します。
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.to_lowercase();
+        EngineType::ALL
+            .iter()
+            .copied()
+            .find(|e| e.name() == s)
+            .ok_or_else(|| format!("Unknown engine: {}", s))
+    }
+}
+
+impl TryFrom<String> for EngineType {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
 }
 
 /// 検索結果のデータを保持する構造体
@@ -26,15 +137,225 @@ pub enum EngineType {
 /// - `title`: 検索結果のタイトル
 /// - `url`: 検索結果のURL
 /// - `description`: 検索結果の概要 (オプション)
-#[derive(Debug, Clone)]
+/// - `metadata`: エンジン固有の付加情報 (例: `age` = 掲載日の表記、`favicon_host` = 表示ホスト名)
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct SearchData {
     pub title: String,       // 必須
     pub url: String,         // 必須
     pub description: String, // オプション
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub metadata: BTreeMap<String, String>, // オプション
+}
+
+/// 検索結果に付随するインスタントアンサー (DuckDuckGoのZero-click Info、Googleの強調スニペット・ナレッジパネル)
+///
+/// - `title`: 見出し (例: 記事名や人物名)
+/// - `abstract_text`: 回答・要約の本文
+/// - `source`: 出典の名前 (例: `Wikipedia`)
+/// - `url`: 出典のURL
+/// - `image`: 画像のURL
+/// - `infobox`: インフォボックスの項目 (ラベル, 値) を表示順に並べたもの
+/// - `related_topics`: 関連トピック
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Answer {
+    pub title: String,
+    pub abstract_text: String,
+    pub source: String,
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub infobox: Vec<(String, String)>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub related_topics: Vec<RelatedTopic>,
+}
+
+/// インスタントアンサーの関連トピック
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RelatedTopic {
+    pub text: String,
+    pub url: String,
+}
+
+/// 検索結果と、それに付随する情報をまとめたレスポンス
+///
+/// - `results`: 検索結果のリスト
+/// - `answer`: インスタントアンサー (エンジンが返した場合のみ)
+/// - `corrected_query`: スペル修正後のクエリ (「もしかして」「次の検索結果を表示しています」)
+/// - `auto_corrected`: エンジンが修正後のクエリで検索した (`results` が修正後のクエリの結果である) か
+/// - `related_searches`: 関連する検索キーワード
+/// - `people_also_ask`: 「他の人はこちらも質問」の質問
+/// - `warnings`: エンジンが対応していない検索演算子を絞り込みで代替した際の警告 ([`search::query`] を参照)
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SearchResponse {
+    pub results: Vec<SearchData>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub answer: Option<Answer>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub corrected_query: Option<String>,
+    pub auto_corrected: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub related_searches: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub people_also_ask: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+/// HTTP APIやMCPなど外部から受け取る `page` の上限 (エンジンのオフセット計算があふれないようにする)
+pub const MAX_PAGE: usize = 100;
+
+/// 検索のオプションを保持する構造体
+///
+/// 対応していないオプションはエンジンごとに無視されます。
+/// - `page`: 取得するページ番号 (1始まり、デフォルトは1)
+/// - `language`: 検索結果の言語 (例: `ja`, `en`)
+/// - `time_range`: 検索結果の期間
+/// - `categories`: 検索カテゴリ (SearXNGの `general`, `news`, `it` など)
+/// - `vertical`: 検索の種類 (ウェブ、ニュースなど)
+/// - `sort`: 並び順 (関連度順、新しい順)
+/// - `bangs`: クエリのbang (`!gh tokio` など) で検索先を切り替えるか (デフォルトは `false`。[`bang`] を参照)
+#[derive(Debug, Clone)]
+pub struct SearchOptions {
+    pub page: usize,
+    pub language: Option<String>,
+    pub time_range: Option<TimeRange>,
+    pub categories: Vec<String>,
+    pub vertical: Vertical,
+    pub sort: SortOrder,
+    pub bangs: bool,
+}
+
+impl Default for SearchOptions {
+    fn default() -> Self {
+        SearchOptions {
+            page: 1,
+            language: None,
+            time_range: None,
+            categories: Vec::new(),
+            vertical: Vertical::Web,
+            sort: SortOrder::Relevance,
+            bangs: false,
+        }
+    }
+}
+
+/// 検索の種類 (バーティカル)
+///
+/// - `Web`: 一般のウェブ検索 (デフォルト)
+/// - `News`: ニュース検索。`SearchData::metadata` に `source` (配信元)、`published_date` (UTCのISO 8601形式の
+///   配信日時)、`thumbnail` (サムネイル画像のURL) を格納します。Googleは `3 hours ago` などの表記を `age` にも格納します。
+/// - `Images`: 画像検索。`SearchData::url` は画像本体のURLで、`metadata` に `thumbnail`、
+///   `width`・`height` (ピクセル数)、`source_page` (掲載ページのURL)、`source` (掲載サイト) を格納します。
+/// - `Videos`: 動画検索。`SearchData::url` は動画ページのURLで、`metadata` に `duration` (再生時間)、
+///   `publisher` (配信サイト)、`channel` (チャンネル・投稿者)、`published_date` (UTCのISO 8601形式の投稿日時)、
+///   `thumbnail` を格納します。Googleは日付の表記を `age` にも格納します。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Vertical {
+    #[default]
+    Web,
+    News,
+    Images,
+    Videos,
+}
+
+impl Vertical {
+    /// CLIやAPIで使用する名前
+    pub fn name(self) -> &'static str {
+        match self {
+            Vertical::Web => "web",
+            Vertical::News => "news",
+            Vertical::Images => "images",
+            Vertical::Videos => "videos",
+        }
+    }
+}
+
+impl fmt::Display for Vertical {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Vertical {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "web" | "general" => Ok(Vertical::Web),
+            "news" => Ok(Vertical::News),
+            "images" | "image" => Ok(Vertical::Images),
+            "videos" | "video" => Ok(Vertical::Videos),
+            _ => Err(format!("Unknown vertical: {}", s)),
+        }
+    }
+}
+
+/// 検索結果の並び順
+///
+/// - `Relevance`: 関連度順 (デフォルト)
+/// - `Recency`: 新しい順 (ニュース検索などで、対応しているエンジンのみ)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    #[default]
+    Relevance,
+    Recency,
+}
+
+impl FromStr for SortOrder {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "relevance" => Ok(SortOrder::Relevance),
+            "recency" | "date" | "new" => Ok(SortOrder::Recency),
+            _ => Err(format!("Unknown sort order: {}", s)),
+        }
+    }
+}
+
+/// 検索結果の期間
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimeRange {
+    Day,
+    Week,
+    Month,
+    Year,
+}
+
+impl TimeRange {
+    /// SearXNGの `time_range` などで使用する名前
+    pub fn name(self) -> &'static str {
+        match self {
+            TimeRange::Day => "day",
+            TimeRange::Week => "week",
+            TimeRange::Month => "month",
+            TimeRange::Year => "year",
+        }
+    }
+}
+
+impl FromStr for TimeRange {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "day" | "d" => Ok(TimeRange::Day),
+            "week" | "w" => Ok(TimeRange::Week),
+            "month" | "m" => Ok(TimeRange::Month),
+            "year" | "y" => Ok(TimeRange::Year),
+            _ => Err(format!("Unknown time range: {}", s)),
+        }
+    }
 }
 
 /// 指定された検索エンジンとクエリを使用して、ウェブ検索を実行します。
 ///
+/// 検索結果には設定ファイル ([`config`]) のドメインのブロック・ブースト設定と件数上限が適用されます。
+///
 /// # 引数
 /// - `engine`: 使用する検索エンジンの種類 (`EngineType` enum)。
 /// - `query`: 検索クエリ文字列。
@@ -79,16 +400,277 @@ pub struct SearchData {
 /// }
 /// ```
 pub async fn www_search(engine: EngineType, query: String) -> Result<Vec<SearchData>, String> {
+    www_search_with_options(engine, query, &SearchOptions::default()).await
+}
+
+/// 検索オプション (ページ番号など) を指定して、ウェブ検索を実行します。
+///
+/// `options.bangs` が `true` の場合は、クエリの先頭または末尾のbang (`!gh tokio` など) で検索する
+/// エンジンを切り替えます ([`bang`] を参照)。URLテンプレートのbangの場合は、検索せずに展開したURLを
+/// 1件の結果として返します。`false` (デフォルト) の場合、bangはクエリの一部としてそのまま検索します。
+///
+/// クエリの検索演算子 (`site:`, `-除外`, `"フレーズ"` など) はエンジンが対応していればそのまま送り、
+/// 対応していなければ検索結果の絞り込みで代替します ([`search::query`] を参照)。
+///
+/// 検索結果のURLは [`canonical::canonicalize`] で正規化します (リダイレクトの解除、トラッキング用の
+/// パラメータの除去など)。
+pub async fn www_search_with_options(
+    engine: EngineType,
+    query: String,
+    options: &SearchOptions,
+) -> Result<Vec<SearchData>, String> {
+    Ok(config::current().apply_to_results(search_results(engine, query, options).await?))
+}
+
+/// [`www_search_with_options`] から `[domains]` と `max_results` の適用を除いたもの
+///
+/// 複数ページをまとめて扱う [`stream::search_stream`] は、これらをストリーム全体に1回だけ適用します。
+pub(crate) async fn search_results(
+    engine: EngineType,
+    query: String,
+    options: &SearchOptions,
+) -> Result<Vec<SearchData>, String> {
+    let (engine, query) = match resolve_bang(engine, query, options) {
+        ControlFlow::Continue(route) => route,
+        ControlFlow::Break(result) => return Ok(vec![result]),
+    };
+    check_vertical(engine, options)?;
+    let translation = query::Query::parse(&query).translate(engine);
+    let query = translation.query.clone();
     // 選択されたエンジンに基づいて適切な検索関数を呼び出す
+    let results = match engine {
+        EngineType::Google => google::search_google_with_options(query, options).await,
+        EngineType::DuckDuckGo => duckduckgo::search_duckduckgo_with_options(query, options).await,
+        EngineType::Brave => brave::search_brave(query, options).await,
+        EngineType::Searxng => searxng::search_searxng(query, options).await,
+        EngineType::Wikipedia => wikipedia::search_wikipedia(query, options).await,
+        EngineType::YahooJapan => yahoo_japan::search_yahoo_japan(query, options).await,
+        EngineType::Goo => goo::search_goo(query, options).await,
+        EngineType::Github => github::search_github(query, options).await,
+        EngineType::StackExchange => stackexchange::search_stackexchange(query, options).await,
+        EngineType::Crates => crates_io::search_crates(query, options).await,
+        EngineType::DocsRs => crates_io::search_docs_rs(query, options).await,
+        EngineType::Arxiv => arxiv::search_arxiv(query, options).await,
+        EngineType::Crossref => crossref::search_crossref(query, options).await,
+        EngineType::Local => local::search_local(query, options).await,
+    }?;
+    Ok(translation.apply(
+        results
+            .into_iter()
+            .map(canonical::canonicalize_result)
+            .collect(),
+    ))
+}
+
+/// 検索を実行し、検索結果とインスタントアンサーなどをまとめた [`SearchResponse`] を返します。
+///
+/// インスタントアンサーに対応しているのは Google (強調スニペット・ナレッジパネル) と
+/// DuckDuckGo (Instant Answer API、ウェブ検索の1ページ目のみ) です。スペル修正は Google と
+/// DuckDuckGo のウェブ検索、関連検索と「他の人はこちらも質問」は Google のみが対応しています。
+/// その他のエンジンでは `results` と `warnings` 以外は空になります。
+pub async fn www_search_response(
+    engine: EngineType,
+    query: String,
+    options: &SearchOptions,
+) -> Result<SearchResponse, String> {
+    let (engine, query) = match resolve_bang(engine, query, options) {
+        ControlFlow::Continue(route) => route,
+        ControlFlow::Break(result) => {
+            return Ok(SearchResponse {
+                results: vec![result],
+                ..Default::default()
+            });
+        }
+    };
+    check_vertical(engine, options)?;
+    let translation = query::Query::parse(&query).translate(engine);
+    let response = match engine {
+        EngineType::Google => {
+            google::search_google_response(translation.query.clone(), options).await?
+        }
+        EngineType::DuckDuckGo => {
+            duckduckgo::search_duckduckgo_response(translation.query.clone(), options).await?
+        }
+        _ => {
+            return Ok(SearchResponse {
+                results: www_search_with_options(engine, query, options).await?,
+                warnings: translation.warnings,
+                ..Default::default()
+            });
+        }
+    };
+    Ok(SearchResponse {
+        results: config::current().apply_to_results(
+            translation.apply(
+                response
+                    .results
+                    .into_iter()
+                    .map(canonical::canonicalize_result)
+                    .collect(),
+            ),
+        ),
+        warnings: translation.warnings,
+        ..response
+    })
+}
+
+/// 検索を同期で実行し、検索結果とインスタントアンサーなどをまとめた [`SearchResponse`] を返します。
+pub fn www_search_response_sync(
+    engine: EngineType,
+    query: String,
+    options: &SearchOptions,
+) -> Result<SearchResponse, String> {
+    let (engine, query) = match resolve_bang(engine, query, options) {
+        ControlFlow::Continue(route) => route,
+        ControlFlow::Break(result) => {
+            return Ok(SearchResponse {
+                results: vec![result],
+                ..Default::default()
+            });
+        }
+    };
+    check_vertical(engine, options)?;
+    let translation = query::Query::parse(&query).translate(engine);
+    let response = match engine {
+        EngineType::Google => {
+            google::search_google_response_sync(translation.query.clone(), options)?
+        }
+        EngineType::DuckDuckGo => {
+            duckduckgo::search_duckduckgo_response_sync(translation.query.clone(), options)?
+        }
+        _ => {
+            return Ok(SearchResponse {
+                results: www_search_sync_with_options(engine, query, options)?,
+                warnings: translation.warnings,
+                ..Default::default()
+            });
+        }
+    };
+    Ok(SearchResponse {
+        results: config::current().apply_to_results(
+            translation.apply(
+                response
+                    .results
+                    .into_iter()
+                    .map(canonical::canonicalize_result)
+                    .collect(),
+            ),
+        ),
+        warnings: translation.warnings,
+        ..response
+    })
+}
+
+/// 入力途中のクエリ (`prefix`) に対する検索候補を取得します。
+///
+/// Google (`complete/search?client=firefox`) と DuckDuckGo (`ac/`) の候補エンドポイントに対応し、
+/// 候補はエンジンが返した順 (関連度の高い順) に並びます。その他のエンジンはエラーを返します。
+///
+/// # 引数
+/// - `engine`: 候補を取得する検索エンジン。
+/// - `prefix`: 入力途中のクエリ文字列。空の場合は空のリストを返します。
+///
+/// # 戻り値
+/// `Result<Vec<String>, String>`:
+/// - 成功した場合: 候補の文字列のリスト。
+/// - 失敗した場合: エラーメッセージ文字列。
+pub async fn suggest(engine: EngineType, prefix: &str) -> Result<Vec<String>, String> {
+    if prefix.trim().is_empty() {
+        return Ok(Vec::new());
+    }
     match engine {
-        EngineType::Google => google::search_google(query).await,
-        EngineType::DuckDuckGo => duckduckgo::search_duckduckgo(query).await,
+        EngineType::Google => google::suggest_google(prefix).await,
+        EngineType::DuckDuckGo => duckduckgo::suggest_duckduckgo(prefix).await,
+        _ => Err(format!("{} does not support suggestions", engine)),
     }
 }
-pub fn www_search_sync(engine: EngineType, query: String) -> Result<Vec<SearchData>, String> {
-    // 選択されたエンジンに基づいて適切な検索関数を呼び出す
+
+/// 入力途中のクエリに対する検索候補を同期で取得します。
+pub fn suggest_sync(engine: EngineType, prefix: &str) -> Result<Vec<String>, String> {
+    if prefix.trim().is_empty() {
+        return Ok(Vec::new());
+    }
     match engine {
-        EngineType::Google => google::search_google_sync(query),
-        EngineType::DuckDuckGo => duckduckgo::search_duckduckgo_sync(query),
+        EngineType::Google => google::suggest_google_sync(prefix),
+        EngineType::DuckDuckGo => duckduckgo::suggest_duckduckgo_sync(prefix),
+        _ => Err(format!("{} does not support suggestions", engine)),
+    }
+}
+
+/// `options.bangs` が `true` ならクエリのbang (`!gh rust` など、[`bang`] を参照) を解決し、
+/// (検索するエンジン, bangを除いたクエリ) を返す
+///
+/// URLテンプレートのbangの場合は、展開したURLを指す検索結果1件で処理を打ち切る
+fn resolve_bang(
+    engine: EngineType,
+    query: String,
+    options: &SearchOptions,
+) -> ControlFlow<SearchData, (EngineType, String)> {
+    if !options.bangs {
+        return ControlFlow::Continue((engine, query));
+    }
+    match bang::route(&query) {
+        None => ControlFlow::Continue((engine, query)),
+        Some(bang::Route::Engine { engine, query }) => ControlFlow::Continue((engine, query)),
+        Some(route) => match route.to_result() {
+            Some(result) => ControlFlow::Break(result),
+            None => ControlFlow::Continue((engine, query)),
+        },
+    }
+}
+
+/// エンジンが指定された検索の種類に対応していなければエラーを返す
+fn check_vertical(engine: EngineType, options: &SearchOptions) -> Result<(), String> {
+    if engine.supports(options.vertical) {
+        Ok(())
+    } else {
+        Err(format!(
+            "{} does not support {} search",
+            engine, options.vertical
+        ))
     }
 }
+
+pub fn www_search_sync(engine: EngineType, query: String) -> Result<Vec<SearchData>, String> {
+    www_search_sync_with_options(engine, query, &SearchOptions::default())
+}
+
+/// 検索オプション (ページ番号など) を指定して、ウェブ検索を同期で実行します。
+pub fn www_search_sync_with_options(
+    engine: EngineType,
+    query: String,
+    options: &SearchOptions,
+) -> Result<Vec<SearchData>, String> {
+    let (engine, query) = match resolve_bang(engine, query, options) {
+        ControlFlow::Continue(route) => route,
+        ControlFlow::Break(result) => return Ok(vec![result]),
+    };
+    check_vertical(engine, options)?;
+    let translation = query::Query::parse(&query).translate(engine);
+    let query = translation.query.clone();
+    // 選択されたエンジンに基づいて適切な検索関数を呼び出す
+    let results = match engine {
+        EngineType::Google => google::search_google_sync_with_options(query, options),
+        EngineType::DuckDuckGo => duckduckgo::search_duckduckgo_sync_with_options(query, options),
+        EngineType::Brave => brave::search_brave_sync(query, options),
+        EngineType::Searxng => searxng::search_searxng_sync(query, options),
+        EngineType::Wikipedia => wikipedia::search_wikipedia_sync(query, options),
+        EngineType::YahooJapan => yahoo_japan::search_yahoo_japan_sync(query, options),
+        EngineType::Goo => goo::search_goo_sync(query, options),
+        EngineType::Github => github::search_github_sync(query, options),
+        EngineType::StackExchange => stackexchange::search_stackexchange_sync(query, options),
+        EngineType::Crates => crates_io::search_crates_sync(query, options),
+        EngineType::DocsRs => crates_io::search_docs_rs_sync(query, options),
+        EngineType::Arxiv => arxiv::search_arxiv_sync(query, options),
+        EngineType::Crossref => crossref::search_crossref_sync(query, options),
+        EngineType::Local => local::search_local_sync(query, options),
+    }?;
+    Ok(config::current().apply_to_results(
+        translation.apply(
+            results
+                .into_iter()
+                .map(canonical::canonicalize_result)
+                .collect(),
+        ),
+    ))
+}
@@ -1,27 +1,202 @@
 // src/main.rs
 
+use futures::StreamExt;
+use std::collections::BTreeSet;
 use std::io::Write;
-use www_search::{EngineType, www_search};
-mod browse;
+use std::path::PathBuf;
+use std::time::Duration;
+use www_search::config::{self, Config, OutputFormat};
+use www_search::crawl::{CrawlOptions, CrawlOutput};
+use www_search::search::query::Query;
+use www_search::stream::{StreamOptions, search_stream};
+use www_search::{
+    Answer, EngineType, SearchData, SearchOptions, SortOrder, TimeRange, Vertical, bang, bibtex,
+    bookmarks, browse, history, images, www_search_response,
+};
+mod bookmarks_cmd;
+mod crawl_cmd;
+mod history_cmd;
+mod mcp;
+mod prompt;
+#[cfg(feature = "serve")]
+mod serve;
+#[cfg(feature = "tui")]
+mod tui;
 
 #[tokio::main]
 async fn main() {
     let args: Vec<String> = std::env::args().collect();
-    let mut engine = EngineType::Google;
+    let mut engine: Option<EngineType> = None;
+    let mut extra_engines: Vec<EngineType> = Vec::new();
+    let mut pages = None;
+    let mut search_options = SearchOptions::default();
     let mut query = String::new();
     let mut url_to_browse = None;
+    let mut tui_mode = false;
+    let mut bibtex_mode = false;
+    let mut list_bangs = false;
+    let mut download_dir = PathBuf::from(".");
+    let mut config_path: Option<PathBuf> = None;
+    let mut user_agent = None;
+    let mut timeout = None;
+    let mut proxy = None;
+    let mut max_results = None;
+    let mut format = None;
+    let mut serve_mode = false;
+    let mut mcp_mode = false;
+    let mut history_mode = false;
+    let mut bookmarks_mode = false;
+    let mut crawl_mode = false;
+    let mut crawl_options = CrawlOptions::default();
+    let mut crawl_output = PathBuf::from("crawl");
+    let mut incognito = false;
+    let mut listen = None;
+    let mut api_key = None;
+    let mut enable_browse = false;
 
-    // 引数パース: --engine, --browse, 検索クエリ
+    // 引数パース: serve, mcp, history, bookmarks, crawl, --engine, --browse, --tui, 設定の上書き, 検索クエリ
     let mut i = 1;
     while i < args.len() {
         match args[i].as_str() {
+            "serve" if i == 1 => serve_mode = true,
+            "mcp" if i == 1 => mcp_mode = true,
+            "history" if i == 1 => history_mode = true,
+            "bookmarks" if i == 1 => bookmarks_mode = true,
+            "crawl" if i == 1 => crawl_mode = true,
+            "--depth" => {
+                if i + 1 < args.len() {
+                    crawl_options.max_depth = parse_or_exit::<usize>(&args[i + 1]);
+                    i += 1;
+                }
+            }
+            "--max-pages" => {
+                if i + 1 < args.len() {
+                    crawl_options.max_pages = parse_or_exit::<usize>(&args[i + 1]);
+                    i += 1;
+                }
+            }
+            "--concurrency" => {
+                if i + 1 < args.len() {
+                    crawl_options.concurrency = parse_or_exit::<usize>(&args[i + 1]);
+                    i += 1;
+                }
+            }
+            "--delay-ms" => {
+                if i + 1 < args.len() {
+                    crawl_options.delay = Duration::from_millis(parse_or_exit::<u64>(&args[i + 1]));
+                    i += 1;
+                }
+            }
+            "--include" => {
+                if i + 1 < args.len() {
+                    crawl_options.include.push(args[i + 1].clone());
+                    i += 1;
+                }
+            }
+            "--exclude" => {
+                if i + 1 < args.len() {
+                    crawl_options.exclude.push(args[i + 1].clone());
+                    i += 1;
+                }
+            }
+            "--ignore-robots" => crawl_options.respect_robots = false,
+            "--output" => {
+                if i + 1 < args.len() {
+                    crawl_output = PathBuf::from(&args[i + 1]);
+                    i += 1;
+                }
+            }
+            "--incognito" => incognito = true,
+            "--listen" => {
+                if i + 1 < args.len() {
+                    listen = Some(args[i + 1].clone());
+                    i += 1;
+                }
+            }
+            "--api-key" => {
+                if i + 1 < args.len() {
+                    api_key = Some(args[i + 1].clone());
+                    i += 1;
+                }
+            }
+            "--enable-browse" => enable_browse = true,
             "--engine" => {
                 if i + 1 < args.len() {
-                    engine = match args[i + 1].to_lowercase().as_str() {
-                        // "google" => EngineType::Google,
-                        "duckduckgo" => EngineType::DuckDuckGo,
-                        _ => EngineType::Google,
-                    };
+                    engine = Some(parse_or_exit(&args[i + 1]));
+                    i += 1;
+                }
+            }
+            "--engines" => {
+                if i + 1 < args.len() {
+                    extra_engines = args[i + 1]
+                        .split(',')
+                        .filter(|name| !name.trim().is_empty())
+                        .map(|name| parse_or_exit(name.trim()))
+                        .collect();
+                    i += 1;
+                }
+            }
+            "--pages" => {
+                if i + 1 < args.len() {
+                    pages = Some(parse_or_exit::<usize>(&args[i + 1]));
+                    i += 1;
+                }
+            }
+            "--lang" => {
+                if i + 1 < args.len() {
+                    search_options.language = Some(args[i + 1].clone());
+                    i += 1;
+                }
+            }
+            "--time-range" => {
+                if i + 1 < args.len() {
+                    search_options.time_range = Some(parse_or_exit::<TimeRange>(&args[i + 1]));
+                    i += 1;
+                }
+            }
+            "--categories" => {
+                if i + 1 < args.len() {
+                    search_options.categories = args[i + 1]
+                        .split(',')
+                        .map(|c| c.trim().to_string())
+                        .filter(|c| !c.is_empty())
+                        .collect();
+                    i += 1;
+                }
+            }
+            "--config" => {
+                if i + 1 < args.len() {
+                    config_path = Some(PathBuf::from(&args[i + 1]));
+                    i += 1;
+                }
+            }
+            "--user-agent" => {
+                if i + 1 < args.len() {
+                    user_agent = Some(args[i + 1].clone());
+                    i += 1;
+                }
+            }
+            "--timeout" => {
+                if i + 1 < args.len() {
+                    timeout = Some(parse_or_exit::<u64>(&args[i + 1]));
+                    i += 1;
+                }
+            }
+            "--proxy" => {
+                if i + 1 < args.len() {
+                    proxy = Some(args[i + 1].clone());
+                    i += 1;
+                }
+            }
+            "--max-results" | "-n" => {
+                if i + 1 < args.len() {
+                    max_results = Some(parse_or_exit::<usize>(&args[i + 1]));
+                    i += 1;
+                }
+            }
+            "--format" => {
+                if i + 1 < args.len() {
+                    format = Some(parse_or_exit::<OutputFormat>(&args[i + 1]));
                     i += 1;
                 }
             }
@@ -31,6 +206,24 @@ async fn main() {
                     i += 1;
                 }
             }
+            "--news" => search_options.vertical = Vertical::News,
+            "--images" => search_options.vertical = Vertical::Images,
+            "--videos" => search_options.vertical = Vertical::Videos,
+            "--download-dir" => {
+                if i + 1 < args.len() {
+                    download_dir = PathBuf::from(&args[i + 1]);
+                    i += 1;
+                }
+            }
+            "--sort" => {
+                if i + 1 < args.len() {
+                    search_options.sort = parse_or_exit::<SortOrder>(&args[i + 1]);
+                    i += 1;
+                }
+            }
+            "--tui" => tui_mode = true,
+            "--bibtex" => bibtex_mode = true,
+            "--list-bangs" => list_bangs = true,
             _ => {
                 query.push_str(&args[i]);
                 query.push(' ');
@@ -39,55 +232,471 @@ async fn main() {
         i += 1;
     }
     query = query.trim().to_string();
+
+    // 設定ファイル → 環境変数 → フラグの順に設定を決定する
+    let loaded = match &config_path {
+        Some(path) => Config::from_file(path).and_then(|mut c| c.apply_env().map(|_| c)),
+        None => Config::load(),
+    };
+    let mut settings = loaded.unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(2);
+    });
+    if let Some(engine) = engine {
+        settings.search.engine = engine;
+    }
+    if user_agent.is_some() {
+        settings.network.user_agent = user_agent;
+    }
+    if timeout.is_some() {
+        settings.network.timeout_secs = timeout;
+    }
+    if proxy.is_some() {
+        settings.network.proxy = proxy;
+    }
+    if max_results.is_some() {
+        settings.search.max_results = max_results;
+    }
+    if let Some(format) = format {
+        settings.output.format = format;
+    }
+    if let Some(listen) = listen {
+        settings.server.listen = listen;
+    }
+    if let Some(api_key) = api_key {
+        settings.server.api_keys.push(api_key);
+    }
+    if enable_browse {
+        settings.server.enable_browse = true;
+    }
+    if incognito {
+        settings.history.enabled = false;
+    }
+    let engine = settings.search.engine;
+    let format = settings.output.format;
+    let limit = settings.search.max_results;
+    let mut stream_options = StreamOptions {
+        engines: if extra_engines.is_empty() {
+            vec![engine]
+        } else {
+            extra_engines
+        },
+        limit: settings.search.max_results,
+        // 件数の指定だけの場合は、その件数に達するまで自動でページを送る
+        max_pages: match (pages, settings.search.max_results) {
+            (Some(pages), _) => pages,
+            (None, Some(_)) => StreamOptions::default().max_pages,
+            (None, None) => 1,
+        },
+        options: search_options,
+    };
+    config::set(settings);
+
+    if mcp_mode {
+        if let Err(e) = mcp::run().await {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+    if serve_mode {
+        #[cfg(feature = "serve")]
+        let result = serve::run().await;
+        #[cfg(not(feature = "serve"))]
+        let result: Result<(), String> = Err(missing_feature("serve"));
+        if let Err(e) = result {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if tui_mode {
+        #[cfg(feature = "tui")]
+        let result = tui::run(engine, query).await;
+        #[cfg(not(feature = "tui"))]
+        let result: Result<(), String> = Err(missing_feature("tui"));
+        if let Err(e) = result {
+            eprintln!("TUI error: {}", e);
+        }
+        return;
+    }
+    if history_mode {
+        // `history <コマンド> <引数>` の引数は検索クエリとして集めている
+        let (command, argument) = query.split_once(' ').unwrap_or((&query, ""));
+        match history_cmd::run(command, argument.trim(), format, limit) {
+            Ok(None) => return,
+            Ok(Some(entry)) => match entry.kind {
+                history::EntryKind::Search => {
+                    let engines: Vec<EngineType> = entry
+                        .engines
+                        .iter()
+                        .filter_map(|name| name.parse().ok())
+                        .collect();
+                    if !engines.is_empty() {
+                        stream_options.engines = engines;
+                    }
+                    if let Some(vertical) = entry.vertical.and_then(|v| v.parse().ok()) {
+                        stream_options.options.vertical = vertical;
+                    }
+                    query = entry.query;
+                }
+                history::EntryKind::Browse => {
+                    url_to_browse = entry.url;
+                    query = entry.query;
+                }
+            },
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(2);
+            }
+        }
+    }
+    if bookmarks_mode {
+        // `bookmarks <コマンド> <引数>` の引数は検索クエリとして集めている
+        let (command, argument) = query.split_once(' ').unwrap_or((&query, ""));
+        if let Err(e) = bookmarks_cmd::run(command, argument.trim(), format, limit) {
+            eprintln!("{}", e);
+            std::process::exit(2);
+        }
+        return;
+    }
+    if crawl_mode {
+        // `crawl <URL>` のURLは検索クエリとして集めている
+        if let Err(e) =
+            crawl_cmd::run(&query, crawl_options, CrawlOutput::from_path(crawl_output)).await
+        {
+            eprintln!("{}", e);
+            std::process::exit(2);
+        }
+        return;
+    }
+    if list_bangs {
+        for (name, target) in bang::list() {
+            println!("!{:<10} {}", name, target);
+        }
+        return;
+    }
+    let images_mode = stream_options.options.vertical == Vertical::Images;
     if query.is_empty() && url_to_browse.is_none() {
-        print!("query: ");
-        std::io::stdout().flush().unwrap();
-        let mut s = String::new();
-        std::io::stdin().read_line(&mut s).ok();
-        query = s.trim().to_string();
+        // Tabキーで検索候補を補完する
+        match prompt::read_query(engine).await {
+            Some(s) => query = s,
+            None => return,
+        }
+    }
+
+    // bang (`!gh tokio` など) でエンジンを選ぶ。URLテンプレートのbangはそのページを閲覧する
+    match bang::route(&query) {
+        Some(bang::Route::Engine {
+            engine,
+            query: rest,
+        }) => {
+            stream_options.engines = vec![engine];
+            query = rest;
+        }
+        Some(bang::Route::Url { url, .. }) => url_to_browse = Some(url),
+        None => {}
     }
 
     if let Some(url) = url_to_browse {
+        record_history(&history::Entry::browse(&url, None, &query));
+        if format == OutputFormat::Json {
+            match browse_page(&url).await {
+                Ok(md) => print_json(&serde_json::json!({ "url": url, "markdown": md })),
+                Err(e) => eprintln!("Failed to browse: {}", e),
+            }
+            return;
+        }
         println!("\n--- Browse Mode ---");
-        match browse::fetch_and_markdown(&url).await {
+        match browse_page(&url).await {
             Ok(md) => println!("\n# Page Content (Markdown)\n\n{}", md),
             Err(e) => eprintln!("Failed to browse: {}", e),
         }
         return;
     }
 
+    // エンジンが対応していない検索演算子を絞り込みで代替する場合は警告する
+    let parsed_query = Query::parse(&query);
+    for engine in &stream_options.engines {
+        for warning in parsed_query.translate(*engine).warnings {
+            eprintln!("Warning: {}", warning);
+        }
+    }
+
+    let searched_engines = stream_options.engines.clone();
+    let vertical = stream_options.options.vertical;
+    if format == OutputFormat::Json {
+        let mut results = Vec::new();
+        let mut stream = search_stream(query.clone(), stream_options);
+        while let Some(result) = stream.next().await {
+            match result {
+                Ok(data) => results.push(data),
+                Err(e) => eprintln!("Error during search: {}", e),
+            }
+        }
+        record_history(&history::Entry::search(
+            &query,
+            &searched_engines,
+            vertical,
+            &results,
+        ));
+        print_json(&results);
+        return;
+    }
+
     println!("--- WWW Search Library ---");
-    println!("\nSearching with {:?} for: '{}'", engine, query);
-    match www_search(engine, query.clone()).await {
-        Ok(results) => {
-            if results.is_empty() {
-                println!("  No results found.");
-                return;
-            }
-            for (i, result) in results.iter().enumerate() {
-                println!(
-                    "[{}] {}\n    {}\n    {}\n---",
-                    i + 1,
-                    result.title,
-                    result.url,
-                    result.description
-                );
-            }
-            print!("\nSelect result number to browse (or Enter to skip): ");
-            std::io::stdout().flush().unwrap();
-            let mut sel = String::new();
-            std::io::stdin().read_line(&mut sel).ok();
-            if let Ok(idx) = sel.trim().parse::<usize>() {
-                if idx > 0 && idx <= results.len() {
-                    let url = &results[idx - 1].url;
-                    println!("\n--- Browsing: {} ---", url);
-                    match browse::fetch_and_markdown(url).await {
-                        Ok(md) => println!("\n# Page Content (Markdown)\n\n{}", md),
-                        Err(e) => eprintln!("Failed to browse: {}", e),
+    let names: Vec<&str> = stream_options.engines.iter().map(|e| e.name()).collect();
+    println!("\nSearching with {} for: '{}'", names.join(", "), query);
+    let mut results: Vec<SearchData> = Vec::new();
+    if let ([engine], 1) = (stream_options.engines.as_slice(), stream_options.max_pages) {
+        // 1エンジン・1ページの検索では、インスタントアンサーを検索結果より先に表示する
+        match www_search_response(*engine, query.clone(), &stream_options.options).await {
+            Ok(response) => {
+                if let Some(corrected) = &response.corrected_query {
+                    if response.auto_corrected {
+                        println!("Showing results for: {}", corrected);
+                    } else {
+                        println!("Did you mean: {}?", corrected);
+                    }
+                }
+                if let Some(answer) = &response.answer {
+                    print_answer(answer);
+                }
+                let limit = stream_options.limit.unwrap_or(usize::MAX);
+                for result in response.results.into_iter().take(limit) {
+                    print_result(results.len() + 1, &result);
+                    results.push(result);
+                }
+                if !response.people_also_ask.is_empty() {
+                    println!("People also ask:");
+                    for question in &response.people_also_ask {
+                        println!("  - {}", question);
                     }
                 }
+                if !response.related_searches.is_empty() {
+                    println!(
+                        "Related searches: {}",
+                        response.related_searches.join(" | ")
+                    );
+                }
             }
+            Err(e) => eprintln!("Error during search: {}", e),
         }
-        Err(e) => eprintln!("Error during search: {}", e),
+    } else {
+        // 検索結果を受け取った順に表示する
+        let mut stream = search_stream(query.clone(), stream_options);
+        while let Some(result) = stream.next().await {
+            match result {
+                Ok(result) => {
+                    print_result(results.len() + 1, &result);
+                    results.push(result);
+                }
+                Err(e) => eprintln!("Error during search: {}", e),
+            }
+        }
+    }
+    record_history(&history::Entry::search(
+        &query,
+        &searched_engines,
+        vertical,
+        &results,
+    ));
+    if results.is_empty() {
+        println!("  No results found.");
+        return;
+    }
+    if bibtex_mode {
+        print!("\nSelect result numbers to export as BibTeX (e.g. 1,3-5 or all): ");
+        std::io::stdout().flush().unwrap();
+        let mut sel = String::new();
+        std::io::stdin().read_line(&mut sel).ok();
+        let selected: Vec<SearchData> = parse_selection(&sel, results.len())
+            .into_iter()
+            .map(|idx| results[idx - 1].clone())
+            .collect();
+        if !selected.is_empty() {
+            println!("\n{}", bibtex::to_bibtex(&selected));
+        }
+        return;
+    }
+    if images_mode {
+        print!("\nSelect image numbers to download (e.g. 1,3-5 or all): ");
+        std::io::stdout().flush().unwrap();
+        let mut sel = String::new();
+        std::io::stdin().read_line(&mut sel).ok();
+        for idx in parse_selection(&sel, results.len()) {
+            match images::download_image(&results[idx - 1], &download_dir).await {
+                Ok(path) => println!("[{}] Saved {}", idx, path.display()),
+                Err(e) => eprintln!("[{}] {}", idx, e),
+            }
+        }
+        return;
+    }
+    // 番号で閲覧、`b 番号 [タグ]` でブックマーク (続けて選択可能)、`r 番号 [タグ]` で閲覧して
+    // 「あとで読む」に保存する
+    loop {
+        print!(
+            "\nSelect result number to browse (b N [tags]: bookmark, r N [tags]: browse and save to the reading list, Enter: skip): "
+        );
+        std::io::stdout().flush().unwrap();
+        let mut sel = String::new();
+        std::io::stdin().read_line(&mut sel).ok();
+        let mut words = sel.trim().splitn(3, ' ');
+        let (action, number) = match words.next().unwrap_or("") {
+            "" => return,
+            action @ ("b" | "r") => (action, words.next().unwrap_or("")),
+            number => ("", number),
+        };
+        let tags = bookmarks::parse_tags(words.next().unwrap_or(""));
+        let Some(result) = number
+            .parse::<usize>()
+            .ok()
+            .filter(|idx| *idx > 0 && *idx <= results.len())
+            .map(|idx| &results[idx - 1])
+        else {
+            eprintln!("Invalid selection: {}", sel.trim());
+            continue;
+        };
+        if action == "b" {
+            match bookmarks::add(result, &tags, &query) {
+                Ok(bookmark) => println!("Bookmarked [{}] {}", bookmark.id, bookmark.title),
+                Err(e) => eprintln!("Failed to bookmark: {}", e),
+            }
+            continue;
+        }
+        let url = &result.url;
+        record_history(&history::Entry::browse(url, Some(&result.title), &query));
+        println!("\n--- Browsing: {} ---", url);
+        match browse_page(url).await {
+            Ok(md) => {
+                println!("\n# Page Content (Markdown)\n\n{}", md);
+                if action == "r" {
+                    match bookmarks::save_for_reading(result, &md, &tags, &query) {
+                        Ok(bookmark) => {
+                            println!("Saved to the reading list [{}]", bookmark.id)
+                        }
+                        Err(e) => eprintln!("Failed to save to the reading list: {}", e),
+                    }
+                }
+            }
+            Err(e) => eprintln!("Failed to browse: {}", e),
+        }
+        return;
+    }
+}
+
+/// ページを取得して本文のMarkdownを返し、`[index] enabled` ならローカルの全文検索インデックスにも保存する
+async fn browse_page(url: &str) -> Result<String, String> {
+    let page = browse::fetch_page(url).await?;
+    index_page(url, &page.title, &page.markdown).await;
+    Ok(page.markdown)
+}
+
+/// `[index] enabled` なら、ページをローカルの全文検索インデックスに保存する (失敗は警告のみ)
+#[cfg(feature = "index")]
+async fn index_page(url: &str, title: &str, markdown: &str) {
+    if let Err(e) = www_search::index::add(url, title, markdown).await {
+        eprintln!("Warning: Failed to index {}: {}", url, e);
+    }
+}
+
+#[cfg(not(feature = "index"))]
+async fn index_page(_url: &str, _title: &str, _markdown: &str) {}
+
+/// フィーチャーを無効にしてビルドした機能を使おうとした場合のエラーメッセージ
+#[cfg(not(all(feature = "serve", feature = "tui")))]
+fn missing_feature(feature: &str) -> String {
+    format!("www-search was built without the `{}` feature", feature)
+}
+
+/// 検索結果を1件表示する
+fn print_result(number: usize, result: &SearchData) {
+    println!(
+        "[{}] {}\n    {}\n    {}",
+        number, result.title, result.url, result.description
+    );
+    if !result.metadata.is_empty() {
+        let metadata: Vec<String> = result
+            .metadata
+            .iter()
+            .map(|(key, value)| format!("{}: {}", key, value))
+            .collect();
+        println!("    ({})", metadata.join(", "));
+    }
+    println!("---");
+}
+
+/// インスタントアンサーを表示する
+fn print_answer(answer: &Answer) {
+    println!("\n=== Answer ===");
+    if !answer.title.is_empty() {
+        println!("{}", answer.title);
+    }
+    if !answer.abstract_text.is_empty() {
+        println!("{}", answer.abstract_text);
+    }
+    for (label, value) in &answer.infobox {
+        println!("  {}: {}", label, value);
+    }
+    match (answer.source.is_empty(), answer.url.is_empty()) {
+        (false, false) => println!("Source: {} <{}>", answer.source, answer.url),
+        (false, true) => println!("Source: {}", answer.source),
+        (true, false) => println!("Source: <{}>", answer.url),
+        (true, true) => {}
+    }
+    if !answer.related_topics.is_empty() {
+        println!("Related:");
+        for topic in answer.related_topics.iter().take(5) {
+            println!("  - {} <{}>", topic.text, topic.url);
+        }
+    }
+    println!("==============\n");
+}
+
+/// 履歴を記録する。記録に失敗しても検索・閲覧は続ける
+fn record_history(entry: &history::Entry) {
+    if let Err(e) = history::record(entry) {
+        eprintln!("Failed to record history: {}", e);
+    }
+}
+
+/// 引数の値をパースし、失敗した場合はエラーを表示して終了する
+fn parse_or_exit<T>(value: &str) -> T
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    value.parse().unwrap_or_else(|e| {
+        eprintln!("Invalid argument '{}': {}", value, e);
+        std::process::exit(2);
+    })
+}
+
+/// `1,3-5` や `all` 形式の番号指定を、1始まりの結果番号の昇順のリストに変換する (範囲外の番号は無視する)
+fn parse_selection(input: &str, len: usize) -> Vec<usize> {
+    let input = input.trim();
+    if input.eq_ignore_ascii_case("all") {
+        return (1..=len).collect();
+    }
+    let mut selected = BTreeSet::new();
+    for part in input.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let (start, end) = match part.split_once('-') {
+            Some((start, end)) => (start.trim().parse::<usize>(), end.trim().parse::<usize>()),
+            None => (part.parse(), part.parse()),
+        };
+        if let (Ok(start), Ok(end)) = (start, end) {
+            // `1-99999999999` のような巨大な範囲でも結果の件数分しか回らないようにする
+            selected.extend(start.max(1)..=end.min(len));
+        }
+    }
+    selected.into_iter().collect()
+}
+
+/// 値をJSONとして標準出力に表示する
+fn print_json<T: serde::Serialize>(value: &T) {
+    match serde_json::to_string_pretty(value) {
+        Ok(json) => println!("{}", json),
+        Err(e) => eprintln!("Failed to serialize JSON: {}", e),
     }
 }
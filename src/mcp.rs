@@ -0,0 +1,393 @@
+// src/mcp.rs
+
+//! `www-search mcp` で起動するModel Context Protocol (MCP) のstdioサーバー
+//!
+//! 標準入出力で改行区切りのJSON-RPC 2.0メッセージをやり取りし、以下のツールを提供します。
+//! - `web_search`: [`www_search_response`] による検索 (警告・関連検索・回答を含む)
+//! - `fetch_page`: [`browse::fetch_and_markdown`] によるページ本文の取得 (offset/lengthでページング)
+//!
+//! 標準出力はプロトコル専用のため、ログやエラーは標準エラー出力に書き出します。
+
+use serde_json::{Value, json};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use www_search::{
+    EngineType, MAX_PAGE, SearchOptions, SortOrder, TimeRange, Vertical, browse,
+    www_search_response,
+};
+
+const PROTOCOL_VERSION: &str = "2025-06-18";
+
+/// `fetch_page` で一度に返すデフォルトの文字数
+const DEFAULT_PAGE_LENGTH: usize = 8000;
+
+/// 標準入力が閉じられるまでMCPサーバーとして動作します。
+pub async fn run() -> Result<(), String> {
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    let mut stdout = tokio::io::stdout();
+    while let Some(line) = lines
+        .next_line()
+        .await
+        .map_err(|e| format!("Failed to read stdin: {}", e))?
+    {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let response = match serde_json::from_str::<Value>(&line) {
+            Ok(message) => handle_message(message).await,
+            Err(e) => Some(error_response(
+                Value::Null,
+                -32700,
+                format!("Parse error: {}", e),
+            )),
+        };
+        // 通知 (idのないメッセージ) には応答しない
+        if let Some(response) = response {
+            let mut text = response.to_string();
+            text.push('\n');
+            stdout
+                .write_all(text.as_bytes())
+                .await
+                .map_err(|e| format!("Failed to write stdout: {}", e))?;
+            stdout
+                .flush()
+                .await
+                .map_err(|e| format!("Failed to flush stdout: {}", e))?;
+        }
+    }
+    Ok(())
+}
+
+async fn handle_message(message: Value) -> Option<Value> {
+    let id = message.get("id").cloned()?;
+    let method = message.get("method").and_then(Value::as_str).unwrap_or("");
+    let params = message.get("params").cloned().unwrap_or(Value::Null);
+    let result = match method {
+        "initialize" => Ok(json!({
+            "protocolVersion": PROTOCOL_VERSION,
+            "capabilities": { "tools": {} },
+            "serverInfo": {
+                "name": env!("CARGO_PKG_NAME"),
+                "version": env!("CARGO_PKG_VERSION"),
+            },
+        })),
+        "ping" => Ok(json!({})),
+        "tools/list" => Ok(json!({ "tools": tool_definitions() })),
+        "tools/call" => Ok(call_tool(&params).await),
+        _ => Err((-32601, format!("Method not found: {}", method))),
+    };
+    Some(match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err((code, message)) => error_response(id, code, message),
+    })
+}
+
+fn error_response(id: Value, code: i64, message: String) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+}
+
+/// `tools/list` で返すツールの定義 (入力・出力のJSON Schemaを含む)
+fn tool_definitions() -> Value {
+    let engines: Vec<&str> = EngineType::ALL.iter().map(|e| e.name()).collect();
+    json!([
+        {
+            "name": "web_search",
+            "title": "Web search",
+            "description": "Search the web and return a ranked list of results (title, URL, snippet). Cite results by their URL.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "query": { "type": "string", "description": "Search query" },
+                    "engine": {
+                        "type": "string",
+                        "enum": engines,
+                        "description": "Search engine to use (defaults to the configured engine)",
+                    },
+                    "page": { "type": "integer", "minimum": 1, "maximum": MAX_PAGE, "description": "Result page number (1-based)" },
+                    "max_results": { "type": "integer", "minimum": 1, "description": "Maximum number of results to return" },
+                    "language": { "type": "string", "description": "Result language code such as en or ja (not supported by every engine)" },
+                    "time_range": {
+                        "type": "string",
+                        "enum": ["day", "week", "month", "year"],
+                        "description": "Only return results from this recent period",
+                    },
+                    "vertical": {
+                        "type": "string",
+                        "enum": ["web", "news", "images", "videos"],
+                        "description": "Kind of results: general web pages (default), news articles, images (url is the full-size image) or videos",
+                    },
+                    "sort": {
+                        "type": "string",
+                        "enum": ["relevance", "recency"],
+                        "description": "Result order; recency sorts news by publication time",
+                    },
+                },
+                "required": ["query"],
+            },
+            "outputSchema": {
+                "type": "object",
+                "properties": {
+                    "query": { "type": "string" },
+                    "engine": { "type": "string" },
+                    "results": {
+                        "type": "array",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "position": { "type": "integer" },
+                                "title": { "type": "string" },
+                                "url": { "type": "string" },
+                                "description": { "type": "string" },
+                                "metadata": {
+                                    "type": "object",
+                                    "additionalProperties": { "type": "string" },
+                                    "description": "Engine-specific details such as age, favicon_host, or source/published_date/thumbnail for news",
+                                },
+                            },
+                            "required": ["position", "title", "url", "description"],
+                        },
+                    },
+                    "corrected_query": { "type": "string", "description": "Spelling correction suggested or applied by the engine" },
+                    "auto_corrected": { "type": "boolean", "description": "Whether the results are for corrected_query instead of the original query" },
+                    "related_searches": { "type": "array", "items": { "type": "string" } },
+                    "people_also_ask": { "type": "array", "items": { "type": "string" } },
+                    "warnings": { "type": "array", "items": { "type": "string" }, "description": "Query operators the engine does not support and that were emulated by filtering the results" },
+                    "answer": {
+                        "type": "object",
+                        "description": "Instant answer (featured snippet, knowledge panel or DuckDuckGo Zero-click Info) when the engine returned one",
+                        "properties": {
+                            "title": { "type": "string" },
+                            "abstract_text": { "type": "string" },
+                            "source": { "type": "string" },
+                            "url": { "type": "string" },
+                            "image": { "type": "string" },
+                            "infobox": {
+                                "type": "array",
+                                "items": { "type": "array", "items": { "type": "string" }, "minItems": 2, "maxItems": 2 },
+                                "description": "[label, value] pairs in display order",
+                            },
+                            "related_topics": {
+                                "type": "array",
+                                "items": {
+                                    "type": "object",
+                                    "properties": { "text": { "type": "string" }, "url": { "type": "string" } },
+                                },
+                            },
+                        },
+                    },
+                },
+                "required": ["query", "engine", "results"],
+            },
+        },
+        {
+            "name": "fetch_page",
+            "title": "Fetch web page",
+            "description": "Fetch a web page and return its main content as Markdown. Long pages are split; pass next_offset as offset to continue reading.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "url": { "type": "string", "description": "http(s) URL of the page" },
+                    "offset": { "type": "integer", "minimum": 0, "description": "Character offset to start reading from (default 0)" },
+                    "length": { "type": "integer", "minimum": 1, "description": "Maximum number of characters to return (default 8000)" },
+                },
+                "required": ["url"],
+            },
+            "outputSchema": {
+                "type": "object",
+                "properties": {
+                    "url": { "type": "string" },
+                    "content": { "type": "string" },
+                    "offset": { "type": "integer" },
+                    "total_length": { "type": "integer" },
+                    "next_offset": { "type": ["integer", "null"] },
+                },
+                "required": ["url", "content", "offset", "total_length", "next_offset"],
+            },
+        },
+    ])
+}
+
+/// `tools/call` を処理する。ツールの実行エラーは `isError: true` の結果として返す。
+async fn call_tool(params: &Value) -> Value {
+    let name = params.get("name").and_then(Value::as_str).unwrap_or("");
+    let arguments = params.get("arguments").cloned().unwrap_or(json!({}));
+    let result = match name {
+        "web_search" => web_search(&arguments).await,
+        "fetch_page" => fetch_page(&arguments).await,
+        _ => Err(format!("Unknown tool: {}", name)),
+    };
+    match result {
+        Ok((text, structured)) => json!({
+            "content": [{ "type": "text", "text": text }],
+            "structuredContent": structured,
+            "isError": false,
+        }),
+        Err(e) => json!({
+            "content": [{ "type": "text", "text": e }],
+            "isError": true,
+        }),
+    }
+}
+
+async fn web_search(arguments: &Value) -> Result<(String, Value), String> {
+    let query = arguments
+        .get("query")
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|q| !q.is_empty())
+        .ok_or("Missing argument: query")?
+        .to_string();
+    let engine = match arguments.get("engine").and_then(Value::as_str) {
+        Some(name) => name.parse::<EngineType>()?,
+        None => www_search::config::current().search.engine,
+    };
+    let options = SearchOptions {
+        page: arguments
+            .get("page")
+            .and_then(Value::as_u64)
+            .unwrap_or(1)
+            .clamp(1, MAX_PAGE as u64) as usize,
+        language: arguments
+            .get("language")
+            .and_then(Value::as_str)
+            .map(str::to_string),
+        time_range: match arguments.get("time_range").and_then(Value::as_str) {
+            Some(time_range) => Some(time_range.parse::<TimeRange>()?),
+            None => None,
+        },
+        vertical: match arguments.get("vertical").and_then(Value::as_str) {
+            Some(vertical) => vertical.parse::<Vertical>()?,
+            None => Vertical::Web,
+        },
+        sort: match arguments.get("sort").and_then(Value::as_str) {
+            Some(sort) => sort.parse::<SortOrder>()?,
+            None => SortOrder::Relevance,
+        },
+        bangs: true,
+        ..Default::default()
+    };
+    let response = www_search_response(engine, query.clone(), &options).await?;
+    let mut results = response.results;
+    if let Some(max) = arguments.get("max_results").and_then(Value::as_u64) {
+        results.truncate(max as usize);
+    }
+
+    let mut text = String::new();
+    for warning in &response.warnings {
+        text.push_str(&format!("Warning: {}\n", warning));
+    }
+    if let Some(corrected) = &response.corrected_query {
+        if response.auto_corrected {
+            text.push_str(&format!("Showing results for: {}\n", corrected));
+        } else {
+            text.push_str(&format!("Did you mean: {}?\n", corrected));
+        }
+    }
+    if let Some(answer) = &response.answer {
+        text.push_str(&format!("Answer: {}\n", answer.abstract_text));
+        for (label, value) in &answer.infobox {
+            text.push_str(&format!("  {}: {}\n", label, value));
+        }
+        if !answer.url.is_empty() {
+            text.push_str(&format!("Source: {}\n", answer.url));
+        }
+        text.push('\n');
+    }
+    text.push_str(&format!("Search results for \"{}\" ({}):\n", query, engine));
+    if results.is_empty() {
+        text.push_str("No results found.\n");
+    }
+    let items: Vec<Value> = results
+        .iter()
+        .enumerate()
+        .map(|(i, r)| {
+            text.push_str(&format!(
+                "\n[{}] {}\n{}\n{}\n",
+                i + 1,
+                r.title,
+                r.url,
+                r.description
+            ));
+            json!({
+                "position": i + 1,
+                "title": r.title,
+                "url": r.url,
+                "description": r.description,
+                "metadata": r.metadata,
+            })
+        })
+        .collect();
+    let mut structured = json!({
+        "query": query,
+        "engine": engine.name(),
+        "results": items,
+    });
+    if let Some(answer) = &response.answer {
+        structured["answer"] = json!(answer);
+    }
+    if let Some(corrected) = &response.corrected_query {
+        structured["corrected_query"] = json!(corrected);
+        structured["auto_corrected"] = json!(response.auto_corrected);
+    }
+    if !response.related_searches.is_empty() {
+        text.push_str(&format!(
+            "\nRelated searches: {}\n",
+            response.related_searches.join(" | ")
+        ));
+        structured["related_searches"] = json!(response.related_searches);
+    }
+    if !response.people_also_ask.is_empty() {
+        text.push_str("\nPeople also ask:\n");
+        for question in &response.people_also_ask {
+            text.push_str(&format!("- {}\n", question));
+        }
+        structured["people_also_ask"] = json!(response.people_also_ask);
+    }
+    if !response.warnings.is_empty() {
+        structured["warnings"] = json!(response.warnings);
+    }
+    Ok((text, structured))
+}
+
+async fn fetch_page(arguments: &Value) -> Result<(String, Value), String> {
+    let url = arguments
+        .get("url")
+        .and_then(Value::as_str)
+        .ok_or("Missing argument: url")?
+        .to_string();
+    if !(url.starts_with("http://") || url.starts_with("https://")) {
+        return Err("Only http(s) URLs are supported".to_string());
+    }
+    let offset = arguments.get("offset").and_then(Value::as_u64).unwrap_or(0) as usize;
+    let length = arguments
+        .get("length")
+        .and_then(Value::as_u64)
+        .map(|l| l.max(1) as usize)
+        .unwrap_or(DEFAULT_PAGE_LENGTH);
+
+    let markdown = browse::fetch_and_markdown(&url).await?;
+    // 文字単位で切り出す (UTF-8の境界を壊さないため)
+    let total_length = markdown.chars().count();
+    let content: String = markdown.chars().skip(offset).take(length).collect();
+    let end = offset + content.chars().count();
+    let next_offset = (end < total_length).then_some(end);
+
+    let mut text = content.clone();
+    if let Some(next) = next_offset {
+        text.push_str(&format!(
+            "\n\n[Content truncated: showing characters {}-{} of {}. Call fetch_page with offset={} to continue.]",
+            offset, end, total_length, next
+        ));
+    }
+    let structured = json!({
+        "url": url,
+        "content": content,
+        "offset": offset,
+        "total_length": total_length,
+        "next_offset": next_offset,
+    });
+    Ok((text, structured))
+}
@@ -0,0 +1,150 @@
+// src/prompt.rs
+
+//! 対話モードのクエリ入力欄
+//!
+//! 端末で実行している場合は1行エディタとして動作し、Tabキーで検索候補 ([`www_search::suggest`]) を
+//! 補完します。Tabを続けて押すと次の候補、Shift+Tabで前の候補に切り替わり、候補を一巡すると
+//! 入力した文字列に戻ります。端末でない場合 (パイプ入力など) や、`tui` フィーチャーを無効にして
+//! ビルドした場合は1行をそのまま読み込みます。
+
+use std::io::Write;
+
+#[cfg(feature = "tui")]
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+#[cfg(feature = "tui")]
+use ratatui::crossterm::terminal;
+use www_search::EngineType;
+#[cfg(feature = "tui")]
+use www_search::suggest;
+
+const PROMPT: &str = "query: ";
+
+/// Tab補完の状態
+#[cfg(feature = "tui")]
+struct Completion {
+    /// 補完を始めたときの入力
+    prefix: String,
+    items: Vec<String>,
+    /// 選択中の候補 (`items.len()` のときは `prefix` に戻っている)
+    index: usize,
+}
+
+/// クエリを1行読み込みます。Ctrl+C / Esc で入力を中止した場合は `None` を返します。
+#[cfg(feature = "tui")]
+pub async fn read_query(engine: EngineType) -> Option<String> {
+    use std::io::IsTerminal;
+
+    if !std::io::stdin().is_terminal() || !std::io::stdout().is_terminal() {
+        return read_line();
+    }
+    if terminal::enable_raw_mode().is_err() {
+        return None;
+    }
+    let result = edit_line(engine).await;
+    terminal::disable_raw_mode().ok();
+    print!("\r\n");
+    std::io::stdout().flush().ok();
+    result.map(|s| s.trim().to_string())
+}
+
+/// クエリを1行読み込みます。
+#[cfg(not(feature = "tui"))]
+pub async fn read_query(_engine: EngineType) -> Option<String> {
+    read_line()
+}
+
+/// 補完なしで1行読み込む
+fn read_line() -> Option<String> {
+    print!("{}", PROMPT);
+    std::io::stdout().flush().ok();
+    let mut s = String::new();
+    std::io::stdin().read_line(&mut s).ok();
+    Some(s.trim().to_string())
+}
+
+#[cfg(feature = "tui")]
+async fn edit_line(engine: EngineType) -> Option<String> {
+    let mut line = String::new();
+    let mut completion: Option<Completion> = None;
+    let mut hint = String::new();
+    loop {
+        render(&line, &hint);
+        let Ok(Event::Key(key)) = event::read() else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        match key.code {
+            KeyCode::Enter => return Some(line),
+            KeyCode::Esc => return None,
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return None,
+            KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                line.clear();
+                completion = None;
+                hint.clear();
+            }
+            KeyCode::Tab | KeyCode::BackTab => {
+                if completion.is_none() {
+                    hint = "[...]".to_string();
+                    render(&line, &hint);
+                    match suggest(engine, &line).await {
+                        Ok(items) if !items.is_empty() => {
+                            completion = Some(Completion {
+                                prefix: line.clone(),
+                                index: items.len(),
+                                items,
+                            });
+                        }
+                        Ok(_) => hint = "[no suggestions]".to_string(),
+                        Err(e) => hint = format!("[{}]", e),
+                    }
+                }
+                if let Some(c) = completion.as_mut() {
+                    // 候補の後ろに入力した文字列を置き、一巡すると元に戻るようにする
+                    let len = c.items.len() + 1;
+                    c.index = if key.code == KeyCode::BackTab {
+                        (c.index + len - 1) % len
+                    } else {
+                        (c.index + 1) % len
+                    };
+                    line = c.items.get(c.index).unwrap_or(&c.prefix).clone();
+                    hint = if c.index < c.items.len() {
+                        format!("[{}/{}]", c.index + 1, c.items.len())
+                    } else {
+                        String::new()
+                    };
+                }
+            }
+            KeyCode::Backspace => {
+                line.pop();
+                completion = None;
+                hint.clear();
+            }
+            KeyCode::Char(ch) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                line.push(ch);
+                completion = None;
+                hint.clear();
+            }
+            _ => {}
+        }
+    }
+}
+
+/// 入力欄を再描画し、カーソルを入力の末尾 (ヒントの手前) に置く
+#[cfg(feature = "tui")]
+fn render(line: &str, hint: &str) {
+    let mut stdout = std::io::stdout();
+    write!(stdout, "\r\x1b[2K{}{}", PROMPT, line).ok();
+    if !hint.is_empty() {
+        let hint = format!("  {}", hint);
+        write!(
+            stdout,
+            "\x1b[2m{}\x1b[0m\x1b[{}D",
+            hint,
+            hint.chars().count()
+        )
+        .ok();
+    }
+    stdout.flush().ok();
+}
@@ -2,9 +2,423 @@
 
 //! このモジュールは、各種検索エンジンモジュールを再エクスポートします。
 
+use std::time::Duration;
+
+use crate::{EngineType, config};
+
 // googleモジュールを宣言し、その中の関数を公開する
 pub mod google;
 // duckduckgoモジュールを宣言し、その中の関数を公開する
 pub mod duckduckgo;
+// braveモジュールを宣言し、その中の関数を公開する
+pub mod brave;
+// searxngモジュールを宣言し、その中の関数を公開する
+pub mod searxng;
+// wikipediaモジュールを宣言し、その中の関数を公開する
+pub mod wikipedia;
+// yahoo_japanモジュールを宣言し、その中の関数を公開する
+pub mod yahoo_japan;
+// gooモジュールを宣言し、その中の関数を公開する
+pub mod goo;
+// githubモジュールを宣言し、その中の関数を公開する
+pub mod github;
+// stackexchangeモジュールを宣言し、その中の関数を公開する
+pub mod stackexchange;
+// crates_ioモジュールを宣言し、その中の関数を公開する
+pub mod crates_io;
+// arxivモジュールを宣言し、その中の関数を公開する
+pub mod arxiv;
+// crossrefモジュールを宣言し、その中の関数を公開する
+pub mod crossref;
+// localモジュールを宣言し、その中の関数を公開する
+pub mod local;
+// 検索演算子の解析とエンジンごとの変換を行うqueryモジュール
+pub mod query;
 
 // 必要に応じて、各検索エンジンの共通ヘルパー関数や共通エラー処理などをここに定義できます。
+
+/// 検索エンジンへのリクエストで使用するデフォルトのUser-Agent
+pub(crate) const DEFAULT_USER_AGENT: &str = "w3m (w3m/0.5.3+git20230121)";
+
+/// JavaScript前提のエンドポイント (DuckDuckGoのニュースなど) で使用するブラウザのUser-Agent
+pub(crate) const BROWSER_USER_AGENT: &str =
+    "Mozilla/5.0 (X11; Linux x86_64; rv:128.0) Gecko/20100101 Firefox/128.0";
+
+/// 公開API (Wikimedia、GitHub、crates.io など) へのリクエストで使用するUser-Agent
+///
+/// 各APIの利用ポリシーに従い、クライアント名とバージョンを名乗ります。
+/// Wikimediaのポリシーでは連絡先も必要なため、リポジトリのURLを含めます。
+pub(crate) const API_USER_AGENT: &str = concat!(
+    "www-search/",
+    env!("CARGO_PKG_VERSION"),
+    " (+https://github.com/The-Infinitys/www-search)"
+);
+
+/// 設定から決定したHTTPクライアントのパラメータ
+struct ClientSettings {
+    user_agent: Option<String>,
+    timeout: Option<Duration>,
+    proxy: Option<String>,
+}
+
+impl ClientSettings {
+    /// エンジン別設定 → `[network]` → `default_user_agent` の順に値を決定する
+    fn resolve(engine: Option<EngineType>, default_user_agent: Option<&str>) -> Self {
+        let config = config::current();
+        let engine_config = engine.map(|e| config.engine(e));
+        ClientSettings {
+            user_agent: engine_config
+                .and_then(|e| e.user_agent.clone())
+                .or_else(|| config.network.user_agent.clone())
+                .or_else(|| default_user_agent.map(str::to_string)),
+            timeout: engine_config
+                .and_then(|e| e.timeout_secs)
+                .or(config.network.timeout_secs)
+                .map(Duration::from_secs),
+            proxy: config.network.proxy.clone(),
+        }
+    }
+}
+
+/// 設定 (User-Agent、タイムアウト、プロキシ) を反映した非同期HTTPクライアントを構築する
+///
+/// `engine` が `None` の場合はエンジン別設定を使用しません (browse機能など)。
+pub(crate) fn client(
+    engine: Option<EngineType>,
+    default_user_agent: Option<&str>,
+) -> Result<reqwest::Client, String> {
+    client_builder(engine, default_user_agent)?
+        .build()
+        .map_err(|e| format!("Failed to build reqwest client: {}", e))
+}
+
+/// 設定 (User-Agent、タイムアウト、プロキシ) を反映した非同期HTTPクライアントのビルダーを返す
+pub(crate) fn client_builder(
+    engine: Option<EngineType>,
+    default_user_agent: Option<&str>,
+) -> Result<reqwest::ClientBuilder, String> {
+    let settings = ClientSettings::resolve(engine, default_user_agent);
+    let mut builder = reqwest::ClientBuilder::new().cookie_store(true);
+    if let Some(user_agent) = settings.user_agent {
+        builder = builder.user_agent(user_agent);
+    }
+    if let Some(timeout) = settings.timeout {
+        builder = builder.timeout(timeout);
+    }
+    if let Some(proxy) = settings.proxy {
+        let proxy = reqwest::Proxy::all(&proxy).map_err(|e| format!("Invalid proxy URL: {}", e))?;
+        builder = builder.proxy(proxy);
+    }
+    Ok(builder)
+}
+
+/// 設定 (User-Agent、タイムアウト、プロキシ) を反映した同期HTTPクライアントを構築する
+pub(crate) fn blocking_client(
+    engine: Option<EngineType>,
+    default_user_agent: Option<&str>,
+) -> Result<reqwest::blocking::Client, String> {
+    let settings = ClientSettings::resolve(engine, default_user_agent);
+    let mut builder = reqwest::blocking::ClientBuilder::new().cookie_store(true);
+    if let Some(user_agent) = settings.user_agent {
+        builder = builder.user_agent(user_agent);
+    }
+    if let Some(timeout) = settings.timeout {
+        builder = builder.timeout(timeout);
+    }
+    if let Some(proxy) = settings.proxy {
+        let proxy = reqwest::Proxy::all(&proxy).map_err(|e| format!("Invalid proxy URL: {}", e))?;
+        builder = builder.proxy(proxy);
+    }
+    builder
+        .build()
+        .map_err(|e| format!("Failed to build reqwest client: {}", e))
+}
+
+/// リクエストを送信し、成功ステータス (2xx) のレスポンス本文を文字列で返す
+///
+/// `name` はエラーメッセージに表示するエンジン名です。
+pub(crate) async fn fetch_text(
+    request: reqwest::RequestBuilder,
+    name: &str,
+) -> Result<String, String> {
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("Failed to send request to {}: {}", name, e))?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "{} request failed with status: {}",
+            name,
+            response.status()
+        ));
+    }
+    response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to get text from {} response: {}", name, e))
+}
+
+/// リクエストを同期で送信し、成功ステータス (2xx) のレスポンス本文を文字列で返す
+pub(crate) fn fetch_text_sync(
+    request: reqwest::blocking::RequestBuilder,
+    name: &str,
+) -> Result<String, String> {
+    let response = request
+        .send()
+        .map_err(|e| format!("Failed to send request to {}: {}", name, e))?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "{} request failed with status: {}",
+            name,
+            response.status()
+        ));
+    }
+    response
+        .text()
+        .map_err(|e| format!("Failed to get text from {} response: {}", name, e))
+}
+
+/// APIが返すHTML断片 (`<strong>` などを含むスニペット) からタグを除き、文字参照を展開したテキストを返す
+pub(crate) fn html_to_text(fragment: &str) -> String {
+    let html = scraper::Html::parse_fragment(fragment);
+    let text = html.root_element().text().collect::<String>();
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// 要素内のテキストを連結し、連続する空白を1つにまとめて返す
+pub(crate) fn element_text(element: scraper::ElementRef) -> String {
+    element
+        .text()
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// URLのホスト名 (先頭の `www.` を除く) を返す
+pub(crate) fn url_host(url: &str) -> Option<String> {
+    url::Url::parse(url)
+        .ok()?
+        .host_str()
+        .map(|h| h.trim_start_matches("www.").to_string())
+}
+
+/// 現在のUNIX時間 (秒)
+pub(crate) fn unix_now() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+/// UNIX時間 (秒) をUTCのISO 8601形式 (`2025-01-02T03:04:05Z`) に変換する
+pub(crate) fn format_unix_time(secs: i64) -> String {
+    let days = secs.div_euclid(86400);
+    let time = secs.rem_euclid(86400);
+    // 1970-01-01からの日数を年月日に変換する (Howard Hinnantの civil_from_days)
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        time / 3600,
+        time % 3600 / 60,
+        time % 60
+    )
+}
+
+/// 現在時刻をUTCのISO 8601形式 (`2025-01-02T03:04:05Z`) で返す
+pub(crate) fn now_iso() -> String {
+    format_unix_time(unix_now())
+}
+
+/// ISO 8601形式の日時をUNIX時間 (秒) に変換する
+///
+/// [`format_unix_time`] の逆変換です。`2025-01-02T03:04:05Z` のほか、UTCからの時差
+/// (`+09:00`, `-0500`)、小数の秒 (`03:04:05.123`)、日付と時刻の間の空白、秒の省略と
+/// 時刻の省略 (その日の0時) を受け付けます。時差がない場合はUTCとみなします。
+/// 形式が異なる場合は `None` を返します。
+pub(crate) fn parse_unix_time(text: &str) -> Option<i64> {
+    let text = text.trim();
+    let (date, time) = text.split_once(['T', ' ']).unwrap_or((text, "00:00"));
+    let mut date = date.splitn(3, '-').map(|p| p.parse::<i64>().ok());
+    let (year, month, day) = (date.next()??, date.next()??, date.next()??);
+    let time = time.trim();
+    let (time, offset) = if let Some(time) = time.strip_suffix(['Z', 'z']) {
+        (time, 0)
+    } else if let Some(sign) = time.rfind(['+', '-']) {
+        let (hours, minutes) = match time[sign + 1..].split_once(':') {
+            Some((hours, minutes)) => (hours, minutes),
+            None if time.len() - sign > 3 => time[sign + 1..].split_at(2),
+            None => (&time[sign + 1..], "0"),
+        };
+        let offset = hours.parse::<i64>().ok()? * 3600 + minutes.parse::<i64>().ok()? * 60;
+        let offset = if time[sign..].starts_with('-') {
+            -offset
+        } else {
+            offset
+        };
+        (&time[..sign], offset)
+    } else {
+        (time, 0)
+    };
+    // 小数の秒は切り捨てる
+    let time = time.trim().split('.').next()?;
+    let mut time = time.splitn(3, ':').map(|p| p.parse::<i64>().ok());
+    let (hour, minute) = (time.next()??, time.next()??);
+    let second = time.next().unwrap_or(Some(0))?;
+    if !(0..24).contains(&hour) || !(0..60).contains(&minute) || !(0..=60).contains(&second) {
+        return None;
+    }
+    Some(days_from_civil(year, month, day)? * 86400 + hour * 3600 + minute * 60 + second - offset)
+}
+
+/// 検索結果ページに表示される日付の表記をUNIX時間 (秒) に変換する
+///
+/// `3 hours ago` / `3 時間前` / `yesterday` などの相対表記は `now` を基準に、
+/// `2024-06-05`, `2024/06/05`, `2024年6月5日`, `Jun 5, 2024`, `5 Jun 2024` はその日の0時 (UTC) に変換します。
+/// 日付として解釈できない場合 (`Channel 4` など) は `None` を返します。
+pub(crate) fn parse_date_text(text: &str, now: i64) -> Option<i64> {
+    let text = text.trim();
+    let lower = text.to_lowercase();
+    match lower.as_str() {
+        "today" | "今日" => return Some(now),
+        "yesterday" | "昨日" => return Some(now - 86400),
+        _ => {}
+    }
+    let unit_secs = |unit: &str| -> Option<i64> {
+        let unit = unit.trim_end_matches('s');
+        Some(match unit {
+            "second" | "sec" | "秒" => 1,
+            "minute" | "min" | "分" => 60,
+            "hour" | "hr" | "時間" => 3600,
+            "day" | "日" => 86400,
+            "week" | "週間" => 7 * 86400,
+            "month" | "か月" | "ヶ月" | "カ月" => 30 * 86400,
+            "year" | "yr" | "年" => 365 * 86400,
+            _ => return None,
+        })
+    };
+    // `3 hours ago`, `an hour ago`
+    if let Some(rest) = lower.strip_suffix(" ago") {
+        let (count, unit) = rest.split_once(' ')?;
+        let count = match count {
+            "a" | "an" => 1,
+            count => count.parse::<i64>().ok()?,
+        };
+        return Some(now - count * unit_secs(unit.trim())?);
+    }
+    // `3 時間前`, `3か月前`
+    if let Some(rest) = text.strip_suffix('前') {
+        let digits = rest.find(|c: char| !c.is_ascii_digit())?;
+        let count = rest[..digits].parse::<i64>().ok()?;
+        return Some(now - count * unit_secs(rest[digits..].trim())?);
+    }
+    // `2024年6月5日`
+    if let Some(rest) = text.strip_suffix('日') {
+        let (year, rest) = rest.split_once('年')?;
+        let (month, day) = rest.split_once('月')?;
+        let (year, month, day) = (year.trim(), month.trim(), day.trim());
+        return Some(
+            days_from_civil(year.parse().ok()?, month.parse().ok()?, day.parse().ok()?)? * 86400,
+        );
+    }
+    // `2024-06-05`, `2024/06/05`
+    let numbers: Vec<&str> = text.split(['-', '/']).collect();
+    if numbers.len() == 3 && numbers[0].len() == 4 {
+        let mut numbers = numbers.iter().map(|n| n.parse::<i64>().ok());
+        let (year, month, day) = (numbers.next()??, numbers.next()??, numbers.next()??);
+        return Some(days_from_civil(year, month, day)? * 86400);
+    }
+    // `Jun 5, 2024`, `June 5, 2024`, `5 Jun 2024`
+    let words: Vec<&str> = lower.split([' ', ',']).filter(|w| !w.is_empty()).collect();
+    if words.len() == 3 {
+        let (month, day) = match month_number(words[0]) {
+            Some(month) => (month, words[1]),
+            None => (month_number(words[1])?, words[0]),
+        };
+        return Some(days_from_civil(words[2].parse().ok()?, month, day.parse().ok()?)? * 86400);
+    }
+    None
+}
+
+/// 英語の月名 (`jun`, `june`, `sept.`) を月の番号に変換する
+fn month_number(name: &str) -> Option<i64> {
+    const MONTHS: [&str; 12] = [
+        "january",
+        "february",
+        "march",
+        "april",
+        "may",
+        "june",
+        "july",
+        "august",
+        "september",
+        "october",
+        "november",
+        "december",
+    ];
+    let name = name.trim_end_matches('.');
+    if name.len() < 3 {
+        return None;
+    }
+    let index = MONTHS.iter().position(|m| m.starts_with(name))?;
+    Some(index as i64 + 1)
+}
+
+/// 年月日を1970-01-01からの日数に変換する (Howard Hinnantの days_from_civil)
+///
+/// 月または日が範囲外の場合は `None` を返す
+fn days_from_civil(year: i64, month: i64, day: i64) -> Option<i64> {
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y.rem_euclid(400);
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    Some(era * 146097 + doe - 719468)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_utc_times() {
+        assert_eq!(parse_unix_time("1970-01-01T00:00:00Z"), Some(0));
+        assert_eq!(
+            parse_unix_time(&format_unix_time(1_717_581_845)),
+            Some(1_717_581_845)
+        );
+        assert_eq!(parse_unix_time("2024-06-05"), Some(1_717_545_600));
+        assert_eq!(parse_unix_time("2024-06-05 10:04"), Some(1_717_581_840));
+    }
+
+    #[test]
+    fn parses_offsets_and_fractions() {
+        let utc = parse_unix_time("2024-06-05T10:04:05Z");
+        assert_eq!(parse_unix_time("2024-06-05T19:04:05+09:00"), utc);
+        assert_eq!(parse_unix_time("2024-06-05T05:04:05-0500"), utc);
+        assert_eq!(parse_unix_time("2024-06-05T10:04:05.1234567"), utc);
+        assert_eq!(parse_unix_time("2024-06-05T10:04:05.123+00:00"), utc);
+    }
+
+    #[test]
+    fn rejects_other_text() {
+        assert_eq!(parse_unix_time("3 hours ago"), None);
+        assert_eq!(parse_unix_time("2024-06-05T25:00:00Z"), None);
+        assert_eq!(parse_unix_time(""), None);
+    }
+}
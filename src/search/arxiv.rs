@@ -0,0 +1,196 @@
+// src/search/arxiv.rs
+
+//! arXiv API (`export.arxiv.org/api/query`) のAtomフィードを検索するモジュール
+//!
+//! クエリに `ti:` や `au:` などのフィールド指定が含まれていない場合は、全フィールド (`all:`) を検索します。
+//!
+//! `SearchData::description` には要旨 (abstract) を格納し、
+//! `SearchData::metadata` には次の値を格納します ([`crate::bibtex`] で使用します)。
+//! - `authors`: 著者 (`; ` 区切り)
+//! - `year`: 投稿年
+//! - `published_date`: 投稿日時
+//! - `doi`: DOI (出版済みの場合)
+//! - `venue`: 掲載誌 (`journal_ref`、なければ `arXiv`)
+//! - `arxiv_id`: arXiv ID (バージョン番号なし)
+//! - `primary_category`: 主カテゴリ (例: `cs.CL`)
+//! - `pdf_url`: PDFのURL
+//! - `entry_type`: BibTeXのエントリ種別 (`article` / `misc`)
+
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+
+use crate::search;
+use crate::{EngineType, SearchData, SearchOptions};
+
+const API_URL: &str = "https://export.arxiv.org/api/query";
+
+/// 1ページあたりの取得件数
+const PAGE_SIZE: usize = 20;
+
+/// arXivで論文を検索します。
+///
+/// # 引数
+/// - `query`: 検索クエリ文字列 (arXivのフィールド指定 `ti:`, `au:`, `abs:`, `cat:` なども使用可能)。
+/// - `options`: 検索オプション (ページ番号)。
+///
+/// # 戻り値
+/// `Result<Vec<SearchData>, String>`:
+/// - 成功した場合: 検索結果のリスト (`Vec<SearchData>`)。
+/// - 失敗した場合: エラーメッセージ文字列。
+pub async fn search_arxiv(
+    query: String,
+    options: &SearchOptions,
+) -> Result<Vec<SearchData>, String> {
+    let client = search::client(Some(EngineType::Arxiv), Some(search::API_USER_AGENT))?;
+    let request = client.get(API_URL).query(&params(&query, options));
+    parse_data(&search::fetch_text(request, "arXiv").await?)
+}
+
+/// arXivで論文を同期で検索します。
+pub fn search_arxiv_sync(
+    query: String,
+    options: &SearchOptions,
+) -> Result<Vec<SearchData>, String> {
+    let client = search::blocking_client(Some(EngineType::Arxiv), Some(search::API_USER_AGENT))?;
+    let request = client.get(API_URL).query(&params(&query, options));
+    parse_data(&search::fetch_text_sync(request, "arXiv")?)
+}
+
+fn params(query: &str, options: &SearchOptions) -> Vec<(&'static str, String)> {
+    const FIELDS: [&str; 9] = [
+        "ti:", "au:", "abs:", "co:", "jr:", "cat:", "rn:", "id:", "all:",
+    ];
+    let search_query = if FIELDS.iter().any(|f| query.contains(f)) {
+        query.to_string()
+    } else {
+        format!("all:{}", query)
+    };
+    vec![
+        ("search_query", search_query),
+        ("start", ((options.page.max(1) - 1) * PAGE_SIZE).to_string()),
+        ("max_results", PAGE_SIZE.to_string()),
+    ]
+}
+
+#[derive(Debug, Deserialize)]
+struct Feed {
+    #[serde(default)]
+    entry: Vec<Entry>,
+}
+
+/// Atomの `<entry>` (名前空間の接頭辞 `arxiv:` を除いた要素名で対応付ける)
+#[derive(Debug, Deserialize)]
+struct Entry {
+    id: String,
+    title: String,
+    #[serde(default)]
+    summary: String,
+    #[serde(default)]
+    published: Option<String>,
+    #[serde(default)]
+    author: Vec<Author>,
+    #[serde(default)]
+    link: Vec<Link>,
+    #[serde(default)]
+    doi: Option<String>,
+    #[serde(default)]
+    journal_ref: Option<String>,
+    #[serde(default)]
+    primary_category: Option<Category>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Author {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Link {
+    #[serde(rename = "@href")]
+    href: String,
+    #[serde(default, rename = "@title")]
+    title: Option<String>,
+    #[serde(default, rename = "@rel")]
+    rel: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Category {
+    #[serde(rename = "@term")]
+    term: String,
+}
+
+/// arXiv APIのAtomフィードを `SearchData` のベクターに変換します。
+///
+/// タイトルと要旨に含まれる改行・連続する空白は1つの空白にまとめます。
+fn parse_data(xml: &str) -> Result<Vec<SearchData>, String> {
+    let feed: Feed = quick_xml::de::from_str(xml)
+        .map_err(|e| format!("Failed to parse arXiv response: {}", e))?;
+    let results = feed
+        .entry
+        .into_iter()
+        .map(|entry| {
+            let mut metadata = BTreeMap::new();
+            let authors: Vec<String> = entry.author.into_iter().map(|a| a.name).collect();
+            if !authors.is_empty() {
+                metadata.insert("authors".to_string(), authors.join("; "));
+            }
+            if let Some(published) = entry.published {
+                metadata.insert("year".to_string(), published.chars().take(4).collect());
+                metadata.insert("published_date".to_string(), published);
+            }
+            if let Some(doi) = entry.doi.filter(|d| !d.is_empty()) {
+                metadata.insert("doi".to_string(), doi);
+            }
+            let journal_ref = entry.journal_ref.map(|j| collapse_whitespace(&j));
+            metadata.insert(
+                "entry_type".to_string(),
+                if journal_ref.is_some() {
+                    "article"
+                } else {
+                    "misc"
+                }
+                .to_string(),
+            );
+            metadata.insert(
+                "venue".to_string(),
+                journal_ref.unwrap_or_else(|| "arXiv".to_string()),
+            );
+            // `http://arxiv.org/abs/2101.00001v2` → `2101.00001`
+            let arxiv_id = entry.id.rsplit("/abs/").next().unwrap_or(&entry.id);
+            let arxiv_id = match arxiv_id.rfind('v') {
+                Some(i) if arxiv_id[i + 1..].chars().all(|c| c.is_ascii_digit()) => &arxiv_id[..i],
+                _ => arxiv_id,
+            };
+            metadata.insert("arxiv_id".to_string(), arxiv_id.to_string());
+            if let Some(category) = entry.primary_category {
+                metadata.insert("primary_category".to_string(), category.term);
+            }
+            if let Some(pdf) = entry
+                .link
+                .iter()
+                .find(|l| l.title.as_deref() == Some("pdf"))
+            {
+                metadata.insert("pdf_url".to_string(), pdf.href.clone());
+            }
+            let url = entry
+                .link
+                .iter()
+                .find(|l| l.rel.as_deref() == Some("alternate"))
+                .map(|l| l.href.clone())
+                .unwrap_or(entry.id);
+            SearchData {
+                title: collapse_whitespace(&entry.title),
+                url,
+                description: collapse_whitespace(&entry.summary),
+                metadata,
+            }
+        })
+        .collect();
+    Ok(results)
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
@@ -0,0 +1,274 @@
+// src/search/brave.rs
+
+//! Brave Search (search.brave.com) の検索モジュール
+//!
+//! 通常は検索結果ページのHTMLをパースします。設定 (`[engines.brave] api_key`) または
+//! 環境変数 `BRAVE_SEARCH_API_KEY` でAPIキーが指定されている場合は、
+//! Brave Search API (`/res/v1/web/search`) のJSONを使用します。
+//!
+//! `SearchData::metadata` には次の値を格納します。
+//! - `age`: 掲載日の表記 (例: `2 days ago`, `June 29, 2025`)
+//! - `favicon_host`: ファビコンと共に表示されるホスト名
+
+use std::collections::BTreeMap;
+
+use scraper::{Html, Selector};
+use serde::Deserialize;
+
+use crate::search::{self, element_text, url_host};
+use crate::{EngineType, SearchData, SearchOptions, TimeRange, config};
+
+const HTML_URL: &str = "https://search.brave.com/search";
+const API_URL: &str = "https://api.search.brave.com/res/v1/web/search";
+/// APIで指定できる `offset` の最大値
+const API_MAX_OFFSET: usize = 9;
+
+/// Brave Searchで検索を実行します。APIキーが設定されていればAPIを使用します。
+///
+/// APIで取得できるのは10ページ目までで、それより後のページは空の結果を返します。
+///
+/// # 引数
+/// - `query`: 検索クエリ文字列。
+/// - `options`: 検索オプション (ページ番号など)。
+///
+/// # 戻り値
+/// `Result<Vec<SearchData>, String>`:
+/// - 成功した場合: 検索結果のリスト (`Vec<SearchData>`)。
+/// - 失敗した場合: エラーメッセージ文字列。
+pub async fn search_brave(
+    query: String,
+    options: &SearchOptions,
+) -> Result<Vec<SearchData>, String> {
+    let client = search::client(Some(EngineType::Brave), Some(search::DEFAULT_USER_AGENT))?;
+    match api_key() {
+        Some(_) if api_offset(options) > API_MAX_OFFSET => Ok(Vec::new()),
+        Some(key) => {
+            let request = client
+                .get(API_URL)
+                .query(&api_params(&query, options))
+                .header("Accept", "application/json")
+                .header("X-Subscription-Token", key);
+            parse_api(&search::fetch_text(request, "Brave Search API").await?)
+        }
+        None => {
+            let request = client.get(HTML_URL).query(&html_params(&query, options));
+            Ok(parse_data(&search::fetch_text(request, "Brave").await?))
+        }
+    }
+}
+
+/// Brave Searchで検索を同期で実行します。APIキーが設定されていればAPIを使用します。
+pub fn search_brave_sync(
+    query: String,
+    options: &SearchOptions,
+) -> Result<Vec<SearchData>, String> {
+    let client =
+        search::blocking_client(Some(EngineType::Brave), Some(search::DEFAULT_USER_AGENT))?;
+    match api_key() {
+        Some(_) if api_offset(options) > API_MAX_OFFSET => Ok(Vec::new()),
+        Some(key) => {
+            let request = client
+                .get(API_URL)
+                .query(&api_params(&query, options))
+                .header("Accept", "application/json")
+                .header("X-Subscription-Token", key);
+            parse_api(&search::fetch_text_sync(request, "Brave Search API")?)
+        }
+        None => {
+            let request = client.get(HTML_URL).query(&html_params(&query, options));
+            Ok(parse_data(&search::fetch_text_sync(request, "Brave")?))
+        }
+    }
+}
+
+fn api_key() -> Option<String> {
+    config::current()
+        .engines
+        .brave
+        .api_key
+        .clone()
+        .filter(|k| !k.is_empty())
+}
+
+/// HTML版のクエリパラメータ。`offset` はページ番号 (0始まり)、`tf` は期間です。
+fn html_params(query: &str, options: &SearchOptions) -> Vec<(&'static str, String)> {
+    let mut params = vec![
+        ("q", query.to_string()),
+        ("source", "web".to_string()),
+        ("offset", (options.page.max(1) - 1).to_string()),
+    ];
+    if let Some(time_range) = options.time_range {
+        params.push(("tf", freshness(time_range).to_string()));
+    }
+    params
+}
+
+/// API版のクエリパラメータ。`offset` はページ番号 (0始まり、最大 [`API_MAX_OFFSET`]) です。
+fn api_params(query: &str, options: &SearchOptions) -> Vec<(&'static str, String)> {
+    let mut params = vec![
+        ("q", query.to_string()),
+        ("count", "20".to_string()),
+        ("offset", api_offset(options).to_string()),
+    ];
+    if let Some(time_range) = options.time_range {
+        params.push(("freshness", freshness(time_range).to_string()));
+    }
+    if let Some(language) = &options.language {
+        params.push(("search_lang", language.clone()));
+    }
+    params
+}
+
+/// APIの `offset` (0始まりのページ番号)
+fn api_offset(options: &SearchOptions) -> usize {
+    options.page.max(1) - 1
+}
+
+/// Brave Searchの期間指定の値
+fn freshness(time_range: TimeRange) -> &'static str {
+    match time_range {
+        TimeRange::Day => "pd",
+        TimeRange::Week => "pw",
+        TimeRange::Month => "pm",
+        TimeRange::Year => "py",
+    }
+}
+
+/// Brave Searchの検索結果HTMLを `SearchData` のベクターにパースします。
+///
+/// 各結果は `div.snippet[data-type="web"]` で、以下の要素から値を抽出します。
+/// - **URL**: 結果内の最初の `http(s)` リンク
+/// - **タイトル**: `.title` (なければリンクのテキスト)
+/// - **説明**: `.snippet-description` または `.generic-snippet .content`
+/// - **age**: 説明の先頭にある `.t-secondary` (末尾の ` -` は除去)
+/// - **favicon_host**: `.netloc` (なければURLのホスト名)
+///
+/// # 重要な注意点:
+/// Brave SearchのHTML構造は変更される可能性があるため、複数のセレクタを順に試します。
+fn parse_data(html: &str) -> Vec<SearchData> {
+    let document = Html::parse_document(html);
+    let snippet_selector = Selector::parse(r#"div.snippet[data-type="web"]"#).unwrap();
+    let link_selector = Selector::parse(r#"a[href^="http"]"#).unwrap();
+    let title_selector = Selector::parse(".title").unwrap();
+    let description_selector =
+        Selector::parse(".snippet-description, .generic-snippet .content").unwrap();
+    let age_selector = Selector::parse(".t-secondary").unwrap();
+    let netloc_selector = Selector::parse(".netloc").unwrap();
+
+    let mut results = Vec::new();
+    for snippet in document.select(&snippet_selector) {
+        let Some(link) = snippet.select(&link_selector).next() else {
+            continue;
+        };
+        let url = link.value().attr("href").unwrap_or("").to_string();
+        let title = snippet
+            .select(&title_selector)
+            .next()
+            .map(element_text)
+            .unwrap_or_else(|| element_text(link));
+        if title.is_empty() || url.is_empty() {
+            continue;
+        }
+
+        let mut metadata = BTreeMap::new();
+        let mut description = String::new();
+        if let Some(desc) = snippet.select(&description_selector).next() {
+            description = element_text(desc);
+            if let Some(age) = desc.select(&age_selector).next() {
+                let age_text = element_text(age);
+                if let Some(rest) = description.strip_prefix(&age_text) {
+                    description = rest.trim().to_string();
+                }
+                let age_text = age_text.trim_end_matches('-').trim().to_string();
+                if !age_text.is_empty() {
+                    metadata.insert("age".to_string(), age_text);
+                }
+            }
+        }
+        let host = snippet
+            .select(&netloc_selector)
+            .next()
+            .map(|netloc| {
+                // `example.com › docs` のようなパンくず表記からホスト名のみを取り出す
+                let text = element_text(netloc);
+                text.split('›').next().unwrap_or("").trim().to_string()
+            })
+            .filter(|h| !h.is_empty())
+            .or_else(|| url_host(&url));
+        if let Some(host) = host {
+            metadata.insert("favicon_host".to_string(), host);
+        }
+
+        results.push(SearchData {
+            title,
+            url,
+            description,
+            metadata,
+        });
+    }
+    results
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiResponse {
+    #[serde(default)]
+    web: Option<ApiWeb>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiWeb {
+    #[serde(default)]
+    results: Vec<ApiResult>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiResult {
+    title: String,
+    url: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    age: Option<String>,
+    #[serde(default)]
+    meta_url: Option<ApiMetaUrl>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiMetaUrl {
+    #[serde(default)]
+    hostname: Option<String>,
+}
+
+/// Brave Search APIのJSONレスポンスを `SearchData` のベクターに変換します。
+///
+/// タイトルと説明に含まれる `<strong>` などのタグは除去します。
+fn parse_api(json: &str) -> Result<Vec<SearchData>, String> {
+    let response: ApiResponse = serde_json::from_str(json)
+        .map_err(|e| format!("Failed to parse Brave Search API response: {}", e))?;
+    let results = response
+        .web
+        .map(|web| web.results)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|r| {
+            let mut metadata = BTreeMap::new();
+            if let Some(age) = r.age.filter(|a| !a.is_empty()) {
+                metadata.insert("age".to_string(), age);
+            }
+            if let Some(host) = r
+                .meta_url
+                .and_then(|m| m.hostname)
+                .or_else(|| url_host(&r.url))
+            {
+                metadata.insert("favicon_host".to_string(), host);
+            }
+            SearchData {
+                title: search::html_to_text(&r.title),
+                description: search::html_to_text(&r.description),
+                url: r.url,
+                metadata,
+            }
+        })
+        .collect();
+    Ok(results)
+}
@@ -0,0 +1,179 @@
+// src/search/crates_io.rs
+
+//! crates.io API (`crates.io/api/v1/crates`) を使った crates.io / docs.rs の検索モジュール
+//!
+//! - [`search_crates`]: クレートを検索し、crates.io のページを返します。
+//! - [`search_docs_rs`]: クエリの先頭 (`serde::Deserialize` なら `serde`) でクレートを検索し、
+//!   docs.rs のドキュメントのURLを返します。残りの部分 (`Deserialize`) はrustdocの
+//!   検索パラメータ (`?search=`) として付加し、アイテムを直接検索できるようにします。
+//!
+//! `SearchData::metadata` には次の値を格納します。
+//! - `version`: 最新の安定版 (なければ最新版)
+//! - `downloads`: 総ダウンロード数
+//! - `recent_downloads`: 直近90日のダウンロード数
+//! - `repository`: リポジトリのURL
+//! - `updated_at`: 最終更新日時
+
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+
+use crate::search;
+use crate::{EngineType, SearchData, SearchOptions};
+
+const API_URL: &str = "https://crates.io/api/v1/crates";
+
+/// 1ページあたりの取得件数
+const PAGE_SIZE: usize = 20;
+
+/// crates.ioでクレートを検索します。
+///
+/// # 引数
+/// - `query`: 検索クエリ文字列。
+/// - `options`: 検索オプション (ページ番号)。
+///
+/// # 戻り値
+/// `Result<Vec<SearchData>, String>`:
+/// - 成功した場合: 検索結果のリスト (`Vec<SearchData>`)。
+/// - 失敗した場合: エラーメッセージ文字列。
+pub async fn search_crates(
+    query: String,
+    options: &SearchOptions,
+) -> Result<Vec<SearchData>, String> {
+    let client = search::client(Some(EngineType::Crates), Some(search::API_USER_AGENT))?;
+    let request = client.get(API_URL).query(&params(&query, options));
+    parse_data(&search::fetch_text(request, "crates.io").await?, None)
+}
+
+/// crates.ioでクレートを同期で検索します。
+pub fn search_crates_sync(
+    query: String,
+    options: &SearchOptions,
+) -> Result<Vec<SearchData>, String> {
+    let client = search::blocking_client(Some(EngineType::Crates), Some(search::API_USER_AGENT))?;
+    let request = client.get(API_URL).query(&params(&query, options));
+    parse_data(&search::fetch_text_sync(request, "crates.io")?, None)
+}
+
+/// docs.rsのドキュメントを検索します。
+///
+/// `クレート名::アイテム名` の形式で、アイテムも指定できます。
+pub async fn search_docs_rs(
+    query: String,
+    options: &SearchOptions,
+) -> Result<Vec<SearchData>, String> {
+    let (krate, item) = split_item(&query);
+    let client = search::client(Some(EngineType::DocsRs), Some(search::API_USER_AGENT))?;
+    let request = client.get(API_URL).query(&params(krate, options));
+    parse_data(&search::fetch_text(request, "crates.io").await?, Some(item))
+}
+
+/// docs.rsのドキュメントを同期で検索します。
+pub fn search_docs_rs_sync(
+    query: String,
+    options: &SearchOptions,
+) -> Result<Vec<SearchData>, String> {
+    let (krate, item) = split_item(&query);
+    let client = search::blocking_client(Some(EngineType::DocsRs), Some(search::API_USER_AGENT))?;
+    let request = client.get(API_URL).query(&params(krate, options));
+    parse_data(&search::fetch_text_sync(request, "crates.io")?, Some(item))
+}
+
+fn params(query: &str, options: &SearchOptions) -> Vec<(&'static str, String)> {
+    vec![
+        ("q", query.to_string()),
+        ("page", options.page.max(1).to_string()),
+        ("per_page", PAGE_SIZE.to_string()),
+    ]
+}
+
+/// docs.rs用のクエリを (クレート名, アイテム名) に分割する
+fn split_item(query: &str) -> (&str, &str) {
+    let query = query.trim();
+    query
+        .split_once("::")
+        .map(|(krate, item)| (krate.trim(), item.trim()))
+        .unwrap_or((query, ""))
+}
+
+#[derive(Debug, Deserialize)]
+struct Response {
+    #[serde(default)]
+    crates: Vec<Crate>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Crate {
+    name: String,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    max_stable_version: Option<String>,
+    #[serde(default)]
+    max_version: Option<String>,
+    #[serde(default)]
+    downloads: Option<u64>,
+    #[serde(default)]
+    recent_downloads: Option<u64>,
+    #[serde(default)]
+    repository: Option<String>,
+    #[serde(default)]
+    updated_at: Option<String>,
+}
+
+/// crates.io APIのJSONレスポンスを `SearchData` のベクターに変換します。
+///
+/// `docs_item` が `Some` の場合はdocs.rsのURLを返し、空でなければrustdocの検索パラメータとして付加します。
+fn parse_data(json: &str, docs_item: Option<&str>) -> Result<Vec<SearchData>, String> {
+    let response: Response = serde_json::from_str(json)
+        .map_err(|e| format!("Failed to parse crates.io response: {}", e))?;
+    let results = response
+        .crates
+        .into_iter()
+        .map(|c| {
+            let mut metadata = BTreeMap::new();
+            if let Some(version) = c.max_stable_version.or(c.max_version) {
+                metadata.insert("version".to_string(), version);
+            }
+            if let Some(downloads) = c.downloads {
+                metadata.insert("downloads".to_string(), downloads.to_string());
+            }
+            if let Some(recent) = c.recent_downloads {
+                metadata.insert("recent_downloads".to_string(), recent.to_string());
+            }
+            if let Some(repository) = c.repository.filter(|r| !r.is_empty()) {
+                metadata.insert("repository".to_string(), repository);
+            }
+            if let Some(updated_at) = c.updated_at {
+                metadata.insert("updated_at".to_string(), updated_at);
+            }
+            let (title, url) = match docs_item {
+                None => (
+                    c.name.clone(),
+                    format!("https://crates.io/crates/{}", c.name),
+                ),
+                Some(item) => {
+                    // docs.rsではクレート名のハイフンはアンダースコアに置き換えられる
+                    let mut url = format!(
+                        "https://docs.rs/{}/latest/{}/",
+                        c.name,
+                        c.name.replace('-', "_")
+                    );
+                    if item.is_empty() {
+                        (c.name.clone(), url)
+                    } else {
+                        url.push_str(&format!("?search={}", urlencoding::encode(item)));
+                        (format!("{}::{}", c.name, item), url)
+                    }
+                }
+            };
+            SearchData {
+                title,
+                url,
+                description: c.description.unwrap_or_default().trim().to_string(),
+                metadata,
+            }
+        })
+        .collect();
+    Ok(results)
+}
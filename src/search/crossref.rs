@@ -0,0 +1,235 @@
+// src/search/crossref.rs
+
+//! Crossref REST API (`api.crossref.org/works`) で文献を検索するモジュール
+//!
+//! 設定 (`[engines.crossref] api_key`) にメールアドレスを指定すると、`mailto` パラメータとして送信し
+//! Crossrefの "polite" プールを利用します。
+//!
+//! `SearchData::description` には要旨 (登録されている場合) を格納し、
+//! `SearchData::metadata` には次の値を格納します ([`crate::bibtex`] で使用します)。
+//! - `authors`: 著者 (`; ` 区切り)
+//! - `year`: 発行年
+//! - `doi`: DOI
+//! - `venue`: 掲載誌・書籍名 (`container-title`)
+//! - `publisher`, `volume`, `issue`, `pages`: 書誌情報
+//! - `pdf_url`: PDFのURL (登録されている場合)
+//! - `entry_type`: BibTeXのエントリ種別 (`article` / `inproceedings` / `book` など)
+
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+
+use crate::search;
+use crate::{EngineType, SearchData, SearchOptions, config};
+
+const API_URL: &str = "https://api.crossref.org/works";
+
+/// 1ページあたりの取得件数
+const PAGE_SIZE: usize = 20;
+
+/// Crossrefで文献を検索します。
+///
+/// # 引数
+/// - `query`: 検索クエリ文字列。
+/// - `options`: 検索オプション (ページ番号)。
+///
+/// # 戻り値
+/// `Result<Vec<SearchData>, String>`:
+/// - 成功した場合: 検索結果のリスト (`Vec<SearchData>`)。
+/// - 失敗した場合: エラーメッセージ文字列。
+pub async fn search_crossref(
+    query: String,
+    options: &SearchOptions,
+) -> Result<Vec<SearchData>, String> {
+    let client = search::client(Some(EngineType::Crossref), Some(search::API_USER_AGENT))?;
+    let request = client.get(API_URL).query(&params(&query, options));
+    parse_data(&search::fetch_text(request, "Crossref").await?)
+}
+
+/// Crossrefで文献を同期で検索します。
+pub fn search_crossref_sync(
+    query: String,
+    options: &SearchOptions,
+) -> Result<Vec<SearchData>, String> {
+    let client = search::blocking_client(Some(EngineType::Crossref), Some(search::API_USER_AGENT))?;
+    let request = client.get(API_URL).query(&params(&query, options));
+    parse_data(&search::fetch_text_sync(request, "Crossref")?)
+}
+
+fn params(query: &str, options: &SearchOptions) -> Vec<(&'static str, String)> {
+    let mut params = vec![
+        ("query", query.to_string()),
+        ("rows", PAGE_SIZE.to_string()),
+        (
+            "offset",
+            ((options.page.max(1) - 1) * PAGE_SIZE).to_string(),
+        ),
+    ];
+    if let Some(mailto) = config::current()
+        .engines
+        .crossref
+        .api_key
+        .clone()
+        .filter(|m| !m.is_empty())
+    {
+        params.push(("mailto", mailto));
+    }
+    params
+}
+
+#[derive(Debug, Deserialize)]
+struct Response {
+    message: Message,
+}
+
+#[derive(Debug, Deserialize)]
+struct Message {
+    #[serde(default)]
+    items: Vec<Work>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Work {
+    #[serde(rename = "DOI")]
+    doi: String,
+    #[serde(rename = "URL", default)]
+    url: Option<String>,
+    #[serde(default)]
+    title: Vec<String>,
+    #[serde(default)]
+    author: Vec<Author>,
+    #[serde(default)]
+    issued: Option<DateParts>,
+    #[serde(rename = "container-title", default)]
+    container_title: Vec<String>,
+    #[serde(rename = "abstract", default)]
+    abstract_text: Option<String>,
+    #[serde(rename = "type", default)]
+    work_type: Option<String>,
+    #[serde(default)]
+    publisher: Option<String>,
+    #[serde(default)]
+    volume: Option<String>,
+    #[serde(default)]
+    issue: Option<String>,
+    #[serde(default)]
+    page: Option<String>,
+    #[serde(default)]
+    link: Vec<Link>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Author {
+    #[serde(default)]
+    given: Option<String>,
+    #[serde(default)]
+    family: Option<String>,
+    #[serde(default)]
+    name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DateParts {
+    #[serde(rename = "date-parts", default)]
+    date_parts: Vec<Vec<Option<i64>>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Link {
+    #[serde(rename = "URL")]
+    url: String,
+    #[serde(rename = "content-type", default)]
+    content_type: Option<String>,
+}
+
+/// Crossrefの文献の種類をBibTeXのエントリ種別に対応付ける
+fn entry_type(work_type: &str) -> &'static str {
+    match work_type {
+        "journal-article" => "article",
+        "proceedings-article" => "inproceedings",
+        "book" | "monograph" | "edited-book" => "book",
+        "book-chapter" | "book-section" | "book-part" => "incollection",
+        "dissertation" => "phdthesis",
+        "report" => "techreport",
+        _ => "misc",
+    }
+}
+
+/// Crossref APIのJSONレスポンスを `SearchData` のベクターに変換します。
+///
+/// 要旨はJATS XML (`<jats:p>` など) のため、タグを除いたテキストに変換します。
+fn parse_data(json: &str) -> Result<Vec<SearchData>, String> {
+    let response: Response = serde_json::from_str(json)
+        .map_err(|e| format!("Failed to parse Crossref response: {}", e))?;
+    let results = response
+        .message
+        .items
+        .into_iter()
+        .map(|work| {
+            let mut metadata = BTreeMap::new();
+            let authors: Vec<String> = work
+                .author
+                .iter()
+                .filter_map(|a| match (&a.given, &a.family, &a.name) {
+                    (Some(given), Some(family), _) => Some(format!("{} {}", given, family)),
+                    (None, Some(family), _) => Some(family.clone()),
+                    (_, None, Some(name)) => Some(name.clone()),
+                    _ => None,
+                })
+                .collect();
+            if !authors.is_empty() {
+                metadata.insert("authors".to_string(), authors.join("; "));
+            }
+            if let Some(year) = work
+                .issued
+                .as_ref()
+                .and_then(|d| d.date_parts.first())
+                .and_then(|parts| parts.first().copied().flatten())
+            {
+                metadata.insert("year".to_string(), year.to_string());
+            }
+            metadata.insert("doi".to_string(), work.doi.clone());
+            if let Some(venue) = work.container_title.first() {
+                metadata.insert("venue".to_string(), venue.clone());
+            }
+            for (key, value) in [
+                ("publisher", work.publisher),
+                ("volume", work.volume),
+                ("issue", work.issue),
+                ("pages", work.page),
+            ] {
+                if let Some(value) = value.filter(|v| !v.is_empty()) {
+                    metadata.insert(key.to_string(), value);
+                }
+            }
+            if let Some(pdf) = work
+                .link
+                .iter()
+                .find(|l| l.content_type.as_deref() == Some("application/pdf"))
+            {
+                metadata.insert("pdf_url".to_string(), pdf.url.clone());
+            }
+            metadata.insert(
+                "entry_type".to_string(),
+                entry_type(work.work_type.as_deref().unwrap_or("")).to_string(),
+            );
+            let url = work
+                .url
+                .unwrap_or_else(|| format!("https://doi.org/{}", work.doi));
+            SearchData {
+                title: work
+                    .title
+                    .first()
+                    .map(|t| search::html_to_text(t))
+                    .unwrap_or_else(|| work.doi.clone()),
+                url,
+                description: work
+                    .abstract_text
+                    .map(|a| search::html_to_text(&a))
+                    .unwrap_or_default(),
+                metadata,
+            }
+        })
+        .collect();
+    Ok(results)
+}
@@ -1,45 +1,298 @@
 // src/search/duckduckgo.rs
 
-use reqwest;
-use reqwest::blocking;
 use scraper::{Html, Selector};
-use std::str::FromStr;
+use serde::Deserialize;
+use std::collections::BTreeMap;
 
-use crate::SearchData; // lib.rsからSearchData構造体をインポート
+use crate::search; // 設定を反映したHTTPクライアントの構築に使用
+use crate::{
+    Answer, EngineType, RelatedTopic, SearchData, SearchOptions, SearchResponse, SortOrder,
+    TimeRange, Vertical, canonical,
+}; // lib.rsからSearchData構造体をインポート
 
 /// DuckDuckGo検索を実行し、lite版のHTMLをパースして検索結果を返す
 ///
-/// 実際には、HTTPリクエストを送信し、DuckDuckGoの検索結果ページをパースするロジックがここに入ります。
-/// 現在は、指定されたクエリに基づいてダミーの検索結果を返します。
-///
 /// # 引数
 /// - `query`: 検索クエリ文字列。
 ///
 /// # 戻り値
 /// `Result<Vec<SearchData>, String>`:
-/// - 成功した場合: ダミーの検索結果リスト。
-/// - 失敗した場合: 常に成功として空でないリストを返します (エラー処理は将来の実装用)。
+/// - 成功した場合: 検索結果のリスト (`Vec<SearchData>`)。
+/// - 失敗した場合: エラーメッセージ文字列。
 pub async fn search_duckduckgo(query: String) -> Result<Vec<SearchData>, String> {
-    let url = format!(
-        "https://lite.duckduckgo.com/lite/?q={}",
-        urlencoding::encode(&query)
-    );
-    let client = match reqwest::ClientBuilder::new()
-        .user_agent("w3m (w3m/0.5.3+git20230121)")
-        .build()
-    {
-        Ok(c) => c,
-        Err(e) => return Err(format!("Failed to build reqwest client: {}", e)),
-    };
-    let html = match client.get(&url).send().await {
+    search_duckduckgo_with_options(query, &SearchOptions::default()).await
+}
+
+/// 検索オプション (ページ番号など) を指定してDuckDuckGo検索を実行します。
+///
+/// `options.vertical` がニュースの場合はニュース検索 (`news.js`)、画像の場合は画像検索 (`i.js`)、
+/// 動画の場合は動画検索 (`v.js`) を使用します。
+pub async fn search_duckduckgo_with_options(
+    query: String,
+    options: &SearchOptions,
+) -> Result<Vec<SearchData>, String> {
+    match options.vertical {
+        Vertical::News => return search_news(query, options).await,
+        Vertical::Images => return search_images(query, options).await,
+        Vertical::Videos => return search_videos(query, options).await,
+        Vertical::Web => {}
+    }
+    Ok(parse_data(&fetch_lite(&query, options).await?))
+}
+
+/// lite版の検索結果ページのHTMLを取得する
+async fn fetch_lite(query: &str, options: &SearchOptions) -> Result<String, String> {
+    let url = build_url(query, options);
+    let client = search::client(
+        Some(EngineType::DuckDuckGo),
+        Some(search::DEFAULT_USER_AGENT),
+    )?;
+    match client.get(&url).send().await {
         Ok(resp) => match resp.text().await {
-            Ok(t) => t,
-            Err(e) => return Err(format!("Failed to get text from DuckDuckGo: {}", e)),
+            Ok(t) => Ok(t),
+            Err(e) => Err(format!("Failed to get text from DuckDuckGo: {}", e)),
         },
-        Err(e) => return Err(format!("Failed to send request to DuckDuckGo: {}", e)),
-    };
-    // HTMLパース
-    let document = Html::parse_document(&html);
+        Err(e) => Err(format!("Failed to send request to DuckDuckGo: {}", e)),
+    }
+}
+
+/// DuckDuckGo検索を同期で実行する関数
+pub fn search_duckduckgo_sync(query: String) -> Result<Vec<SearchData>, String> {
+    search_duckduckgo_sync_with_options(query, &SearchOptions::default())
+}
+
+/// 検索オプション (ページ番号など) を指定してDuckDuckGo検索を同期で実行します。
+pub fn search_duckduckgo_sync_with_options(
+    query: String,
+    options: &SearchOptions,
+) -> Result<Vec<SearchData>, String> {
+    match options.vertical {
+        Vertical::News => return search_news_sync(query, options),
+        Vertical::Images => return search_images_sync(query, options),
+        Vertical::Videos => return search_videos_sync(query, options),
+        Vertical::Web => {}
+    }
+    Ok(parse_data(&fetch_lite_sync(&query, options)?))
+}
+
+/// lite版の検索結果ページのHTMLを同期で取得する
+fn fetch_lite_sync(query: &str, options: &SearchOptions) -> Result<String, String> {
+    let url = build_url(query, options);
+    let client = search::blocking_client(
+        Some(EngineType::DuckDuckGo),
+        Some(search::DEFAULT_USER_AGENT),
+    )?;
+    match client.get(&url).send() {
+        Ok(resp) => match resp.text() {
+            Ok(t) => Ok(t),
+            Err(e) => Err(format!("Failed to get text from DuckDuckGo: {}", e)),
+        },
+        Err(e) => Err(format!("Failed to send request to DuckDuckGo: {}", e)),
+    }
+}
+
+/// DuckDuckGoの検索候補 (`ac/`) を取得します。
+///
+/// # 戻り値
+/// 候補の文字列のリスト (DuckDuckGoが返した順)。
+pub async fn suggest_duckduckgo(prefix: &str) -> Result<Vec<String>, String> {
+    let client = search::client(
+        Some(EngineType::DuckDuckGo),
+        Some(search::BROWSER_USER_AGENT),
+    )?;
+    let request = client.get(SUGGEST_URL).query(&[("q", prefix)]);
+    parse_suggestions(&search::fetch_text(request, "DuckDuckGo Suggest").await?)
+}
+
+/// DuckDuckGoの検索候補を同期で取得します。
+pub fn suggest_duckduckgo_sync(prefix: &str) -> Result<Vec<String>, String> {
+    let client = search::blocking_client(
+        Some(EngineType::DuckDuckGo),
+        Some(search::BROWSER_USER_AGENT),
+    )?;
+    let request = client.get(SUGGEST_URL).query(&[("q", prefix)]);
+    parse_suggestions(&search::fetch_text_sync(request, "DuckDuckGo Suggest")?)
+}
+
+const SUGGEST_URL: &str = "https://duckduckgo.com/ac/";
+
+#[derive(Debug, Deserialize)]
+struct Suggestion {
+    phrase: String,
+}
+
+/// `[{"phrase": "候補1"}, ...]` 形式の応答から候補を取り出す
+fn parse_suggestions(json: &str) -> Result<Vec<String>, String> {
+    let suggestions: Vec<Suggestion> = serde_json::from_str(json)
+        .map_err(|e| format!("Failed to parse DuckDuckGo Suggest response: {}", e))?;
+    Ok(suggestions.into_iter().map(|s| s.phrase).collect())
+}
+
+/// 検索オプションを指定してDuckDuckGo検索を実行し、検索結果とインスタントアンサーなどを返します。
+///
+/// ウェブ検索では、lite版の結果ページからスペル修正を取り出します。1ページ目では、インスタントアンサーを
+/// Instant Answer API (`api.duckduckgo.com`) から検索と並行して取得します。取得に失敗した場合は、
+/// 検索結果のみを返します。lite版には関連検索が表示されないため、`related_searches` は常に空です。
+pub async fn search_duckduckgo_response(
+    query: String,
+    options: &SearchOptions,
+) -> Result<SearchResponse, String> {
+    if options.vertical != Vertical::Web {
+        return Ok(SearchResponse {
+            results: search_duckduckgo_with_options(query, options).await?,
+            ..Default::default()
+        });
+    }
+    if options.page > 1 {
+        let html = fetch_lite(&query, options).await?;
+        return Ok(page_response(&html, &query, None));
+    }
+    let (html, answer) = futures::join!(fetch_lite(&query, options), instant_answer(&query));
+    Ok(page_response(&html?, &query, answer.ok().flatten()))
+}
+
+/// 検索オプションを指定してDuckDuckGo検索を同期で実行し、検索結果とインスタントアンサーなどを返します。
+pub fn search_duckduckgo_response_sync(
+    query: String,
+    options: &SearchOptions,
+) -> Result<SearchResponse, String> {
+    if options.vertical != Vertical::Web {
+        return Ok(SearchResponse {
+            results: search_duckduckgo_sync_with_options(query, options)?,
+            ..Default::default()
+        });
+    }
+    let html = fetch_lite_sync(&query, options)?;
+    if options.page > 1 {
+        return Ok(page_response(&html, &query, None));
+    }
+    Ok(page_response(
+        &html,
+        &query,
+        instant_answer_sync(&query).ok().flatten(),
+    ))
+}
+
+/// lite版の結果ページと、別途取得したインスタントアンサーから `SearchResponse` を組み立てる
+fn page_response(html: &str, query: &str, answer: Option<Answer>) -> SearchResponse {
+    let (corrected_query, auto_corrected) = parse_correction(html, query);
+    SearchResponse {
+        results: parse_data(html),
+        answer,
+        corrected_query,
+        auto_corrected,
+        ..Default::default()
+    }
+}
+
+/// lite版の結果ページからスペル修正を取り出す
+///
+/// 修正後のクエリは、元のクエリと異なるクエリで検索し直すリンク (`?q=...`) のテキストです。
+/// 「Including results for」と表示されている場合は修正後のクエリの結果を含むため `auto_corrected` とし、
+/// 「Did you mean」の場合は提案のみとします。
+fn parse_correction(html: &str, query: &str) -> (Option<String>, bool) {
+    let document = Html::parse_document(html);
+    let link_selector = Selector::parse("a[href]").unwrap();
+    for link in document.select(&link_selector) {
+        let href = link.value().attr("href").unwrap_or("");
+        let Ok(url) =
+            url::Url::parse("https://lite.duckduckgo.com/lite/").and_then(|b| b.join(href))
+        else {
+            continue;
+        };
+        if url.host_str() != Some("lite.duckduckgo.com") {
+            continue;
+        }
+        let Some(q) = url
+            .query_pairs()
+            .find(|(k, _)| k == "q")
+            .map(|(_, v)| v.trim().to_string())
+        else {
+            continue;
+        };
+        if q.is_empty() || q.eq_ignore_ascii_case(query.trim()) {
+            continue;
+        }
+        // リンクを含む行 (または段落) の文言で、自動修正か提案かを判定する
+        let context = link
+            .ancestors()
+            .filter_map(scraper::ElementRef::wrap)
+            .find(|e| matches!(e.value().name(), "td" | "p" | "div"))
+            .map(|e| e.text().collect::<String>())
+            .unwrap_or_default()
+            .to_lowercase();
+        if context.contains("including results for") || context.contains("showing results for") {
+            return (Some(q), true);
+        }
+        if context.contains("did you mean") {
+            return (Some(q), false);
+        }
+    }
+    (None, false)
+}
+
+const INSTANT_ANSWER_URL: &str = "https://api.duckduckgo.com/";
+
+/// Instant Answer APIでクエリに対するZero-click Infoを取得します。
+///
+/// 回答がない場合は `Ok(None)` を返します。
+pub async fn instant_answer(query: &str) -> Result<Option<Answer>, String> {
+    let client = search::client(Some(EngineType::DuckDuckGo), Some(search::API_USER_AGENT))?;
+    let request = client
+        .get(INSTANT_ANSWER_URL)
+        .query(&instant_answer_params(query));
+    parse_instant_answer(&search::fetch_text(request, "DuckDuckGo Instant Answer").await?)
+}
+
+/// Instant Answer APIでクエリに対するZero-click Infoを同期で取得します。
+pub fn instant_answer_sync(query: &str) -> Result<Option<Answer>, String> {
+    let client =
+        search::blocking_client(Some(EngineType::DuckDuckGo), Some(search::API_USER_AGENT))?;
+    let request = client
+        .get(INSTANT_ANSWER_URL)
+        .query(&instant_answer_params(query));
+    parse_instant_answer(&search::fetch_text_sync(
+        request,
+        "DuckDuckGo Instant Answer",
+    )?)
+}
+
+fn instant_answer_params(query: &str) -> Vec<(&'static str, String)> {
+    vec![
+        ("q", query.to_string()),
+        ("format", "json".to_string()),
+        ("no_html", "1".to_string()),
+        ("no_redirect", "1".to_string()),
+        ("skip_disambig", "1".to_string()),
+    ]
+}
+
+/// DuckDuckGo lite版のURLを構築します。2ページ目以降は `s` (開始位置) と `dc` を指定し、
+/// 期間は `df` で指定します。
+fn build_url(query: &str, options: &SearchOptions) -> String {
+    let mut url = format!(
+        "https://lite.duckduckgo.com/lite/?q={}",
+        urlencoding::encode(query)
+    );
+    let page = options.page.max(1);
+    if page > 1 {
+        let offset = (page - 1) * 10;
+        url.push_str(&format!("&s={}&dc={}", offset, offset + 1));
+    }
+    if let Some(time_range) = options.time_range {
+        url.push_str(match time_range {
+            TimeRange::Day => "&df=d",
+            TimeRange::Week => "&df=w",
+            TimeRange::Month => "&df=m",
+            TimeRange::Year => "&df=y",
+        });
+    }
+    url
+}
+
+/// DuckDuckGo lite版の検索結果HTMLを `SearchData` のベクターにパースします。
+fn parse_data(html: &str) -> Vec<SearchData> {
+    let document = Html::parse_document(html);
     let mut results = Vec::new();
     // 検索結果は form > div.results > a.result-link などの構造
     let result_selector = Selector::parse("a.result-link").unwrap();
@@ -47,20 +300,9 @@ pub async fn search_duckduckgo(query: String) -> Result<Vec<SearchData>, String>
         let title = a.text().collect::<Vec<_>>().join("").trim().to_string();
         // URL抽出
         let href = a.value().attr("href").unwrap_or("");
-        let url = if href.starts_with("//") {
-            let abs = format!("https:{}", href);
-            if let Ok(parsed) = url::Url::from_str(&abs) {
-                parsed
-                    .query_pairs()
-                    .find(|(k, _)| k == "uddg")
-                    .map(|(_, v)| v.into_owned())
-                    .unwrap_or(abs)
-            } else {
-                abs
-            }
-        } else {
-            href.to_string()
-        };
+        // `//duckduckgo.com/l/?uddg=...` のリダイレクトを解除する
+        let url = canonical::resolve("https://lite.duckduckgo.com/lite/", href)
+            .unwrap_or_else(|| href.to_string());
         // description: aの親td→親tr→次の兄弟trのtd.result-snippet
         let mut description = String::new();
         if let Some(parent_td) = a.parent().and_then(scraper::ElementRef::wrap) {
@@ -88,80 +330,533 @@ pub async fn search_duckduckgo(query: String) -> Result<Vec<SearchData>, String>
                 title,
                 url,
                 description,
+                ..Default::default()
             });
         }
     }
-    Ok(results)
+    results
 }
 
-/// DuckDuckGo検索を同期で実行する関数
-pub fn search_duckduckgo_sync(query: String) -> Result<Vec<SearchData>, String> {
-    let url = format!(
-        "https://lite.duckduckgo.com/lite/?q={}",
-        urlencoding::encode(&query)
-    );
-    let client = match blocking::ClientBuilder::new()
-        .user_agent("w3m (w3m/0.5.3+git20230121)")
-        .build()
-    {
-        Ok(c) => c,
-        Err(e) => return Err(format!("Failed to build reqwest client: {}", e)),
+/// DuckDuckGoのJSONエンドポイント (`news.js` など) の1ページあたりの件数
+const JSON_PAGE_SIZE: usize = 30;
+
+/// DuckDuckGoのニュース検索を実行します。
+///
+/// 検索ページから取得したトークン (`vqd`) を付けて `news.js` を呼び出します。
+async fn search_news(query: String, options: &SearchOptions) -> Result<Vec<SearchData>, String> {
+    let client = search::client(
+        Some(EngineType::DuckDuckGo),
+        Some(search::BROWSER_USER_AGENT),
+    )?;
+    let vqd = fetch_vqd(&client, &query).await?;
+    let request = client
+        .get("https://duckduckgo.com/news.js")
+        .query(&news_params(&query, &vqd, options))
+        .header("Referer", "https://duckduckgo.com/");
+    parse_news(
+        &search::fetch_text(request, "DuckDuckGo News").await?,
+        options.sort,
+    )
+}
+
+/// DuckDuckGoのニュース検索を同期で実行します。
+fn search_news_sync(query: String, options: &SearchOptions) -> Result<Vec<SearchData>, String> {
+    let client = search::blocking_client(
+        Some(EngineType::DuckDuckGo),
+        Some(search::BROWSER_USER_AGENT),
+    )?;
+    let vqd = fetch_vqd_sync(&client, &query)?;
+    let request = client
+        .get("https://duckduckgo.com/news.js")
+        .query(&news_params(&query, &vqd, options))
+        .header("Referer", "https://duckduckgo.com/");
+    parse_news(
+        &search::fetch_text_sync(request, "DuckDuckGo News")?,
+        options.sort,
+    )
+}
+
+/// DuckDuckGoの画像検索を実行します。
+///
+/// 検索ページから取得したトークン (`vqd`) を付けて `i.js` を呼び出します。
+async fn search_images(query: String, options: &SearchOptions) -> Result<Vec<SearchData>, String> {
+    let client = search::client(
+        Some(EngineType::DuckDuckGo),
+        Some(search::BROWSER_USER_AGENT),
+    )?;
+    let vqd = fetch_vqd(&client, &query).await?;
+    let request = client
+        .get("https://duckduckgo.com/i.js")
+        .query(&image_params(&query, &vqd, options))
+        .header("Referer", "https://duckduckgo.com/");
+    parse_images(&search::fetch_text(request, "DuckDuckGo Images").await?)
+}
+
+/// DuckDuckGoの画像検索を同期で実行します。
+fn search_images_sync(query: String, options: &SearchOptions) -> Result<Vec<SearchData>, String> {
+    let client = search::blocking_client(
+        Some(EngineType::DuckDuckGo),
+        Some(search::BROWSER_USER_AGENT),
+    )?;
+    let vqd = fetch_vqd_sync(&client, &query)?;
+    let request = client
+        .get("https://duckduckgo.com/i.js")
+        .query(&image_params(&query, &vqd, options))
+        .header("Referer", "https://duckduckgo.com/");
+    parse_images(&search::fetch_text_sync(request, "DuckDuckGo Images")?)
+}
+
+/// DuckDuckGoの動画検索を実行します。
+///
+/// 検索ページから取得したトークン (`vqd`) を付けて `v.js` を呼び出します。
+async fn search_videos(query: String, options: &SearchOptions) -> Result<Vec<SearchData>, String> {
+    let client = search::client(
+        Some(EngineType::DuckDuckGo),
+        Some(search::BROWSER_USER_AGENT),
+    )?;
+    let vqd = fetch_vqd(&client, &query).await?;
+    let request = client
+        .get("https://duckduckgo.com/v.js")
+        .query(&video_params(&query, &vqd, options))
+        .header("Referer", "https://duckduckgo.com/");
+    parse_videos(
+        &search::fetch_text(request, "DuckDuckGo Videos").await?,
+        options.sort,
+    )
+}
+
+/// DuckDuckGoの動画検索を同期で実行します。
+fn search_videos_sync(query: String, options: &SearchOptions) -> Result<Vec<SearchData>, String> {
+    let client = search::blocking_client(
+        Some(EngineType::DuckDuckGo),
+        Some(search::BROWSER_USER_AGENT),
+    )?;
+    let vqd = fetch_vqd_sync(&client, &query)?;
+    let request = client
+        .get("https://duckduckgo.com/v.js")
+        .query(&video_params(&query, &vqd, options))
+        .header("Referer", "https://duckduckgo.com/");
+    parse_videos(
+        &search::fetch_text_sync(request, "DuckDuckGo Videos")?,
+        options.sort,
+    )
+}
+
+/// 検索ページを取得し、JSONエンドポイントの呼び出しに必要なトークン (`vqd`) を取り出す
+pub(crate) async fn fetch_vqd(client: &reqwest::Client, query: &str) -> Result<String, String> {
+    let request = client.get("https://duckduckgo.com/").query(&[("q", query)]);
+    extract_vqd(&search::fetch_text(request, "DuckDuckGo").await?)
+        .ok_or_else(|| "Failed to get the vqd token from DuckDuckGo".to_string())
+}
+
+/// 検索ページを同期で取得し、トークン (`vqd`) を取り出す
+pub(crate) fn fetch_vqd_sync(
+    client: &reqwest::blocking::Client,
+    query: &str,
+) -> Result<String, String> {
+    let request = client.get("https://duckduckgo.com/").query(&[("q", query)]);
+    extract_vqd(&search::fetch_text_sync(request, "DuckDuckGo")?)
+        .ok_or_else(|| "Failed to get the vqd token from DuckDuckGo".to_string())
+}
+
+/// `vqd="4-123..."` や `vqd=4-123...&` の形式で埋め込まれたトークンを探す
+fn extract_vqd(html: &str) -> Option<String> {
+    html.match_indices("vqd").find_map(|(i, _)| {
+        let rest = html[i + 3..].trim_start_matches(['=', ':', '"', '\'', ' ']);
+        let token: String = rest
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '_')
+            .collect();
+        (token.len() > 4).then_some(token)
+    })
+}
+
+/// `SearchOptions::language` をDuckDuckGoの地域コード (`kl`/`l`) に変換する
+pub(crate) fn region(options: &SearchOptions) -> &'static str {
+    match options.language.as_deref() {
+        Some("en") => "us-en",
+        Some("ja") => "jp-jp",
+        Some("de") => "de-de",
+        Some("fr") => "fr-fr",
+        Some("es") => "es-es",
+        Some("it") => "it-it",
+        Some("zh") => "cn-zh",
+        Some("ko") => "kr-kr",
+        _ => "wt-wt",
+    }
+}
+
+fn news_params(query: &str, vqd: &str, options: &SearchOptions) -> Vec<(&'static str, String)> {
+    let mut params = vec![
+        ("q", query.to_string()),
+        ("vqd", vqd.to_string()),
+        ("l", region(options).to_string()),
+        ("o", "json".to_string()),
+        ("noamp", "1".to_string()),
+        (
+            "s",
+            ((options.page.max(1) - 1) * JSON_PAGE_SIZE).to_string(),
+        ),
+    ];
+    if let Some(time_range) = options.time_range {
+        params.push((
+            "df",
+            match time_range {
+                TimeRange::Day => "d",
+                TimeRange::Week => "w",
+                TimeRange::Month => "m",
+                TimeRange::Year => "y",
+            }
+            .to_string(),
+        ));
+    }
+    params
+}
+
+/// 画像検索のパラメータ。期間は `f=time:Day` の形式で指定します。
+fn image_params(query: &str, vqd: &str, options: &SearchOptions) -> Vec<(&'static str, String)> {
+    let time = match options.time_range {
+        Some(TimeRange::Day) => "time:Day",
+        Some(TimeRange::Week) => "time:Week",
+        Some(TimeRange::Month) => "time:Month",
+        Some(TimeRange::Year) => "time:Year",
+        None => "",
     };
-    let html = match client.get(&url).send() {
-        Ok(resp) => match resp.text() {
-            Ok(t) => t,
-            Err(e) => return Err(format!("Failed to get text from DuckDuckGo: {}", e)),
-        },
-        Err(e) => return Err(format!("Failed to send request to DuckDuckGo: {}", e)),
+    vec![
+        ("q", query.to_string()),
+        ("vqd", vqd.to_string()),
+        ("l", region(options).to_string()),
+        ("o", "json".to_string()),
+        ("f", format!("{},,,,,", time)),
+        ("p", "1".to_string()),
+        (
+            "s",
+            ((options.page.max(1) - 1) * JSON_PAGE_SIZE).to_string(),
+        ),
+    ]
+}
+
+/// 動画検索のパラメータ。期間は `f=publishedAfter:d` の形式で指定します (1年以内の指定はありません)。
+fn video_params(query: &str, vqd: &str, options: &SearchOptions) -> Vec<(&'static str, String)> {
+    let published_after = match options.time_range {
+        Some(TimeRange::Day) => "publishedAfter:d",
+        Some(TimeRange::Week) => "publishedAfter:w",
+        Some(TimeRange::Month) => "publishedAfter:m",
+        Some(TimeRange::Year) | None => "",
     };
-    let document = Html::parse_document(&html);
-    let mut results = Vec::new();
-    let result_selector = Selector::parse("a.result-link").unwrap();
-    for a in document.select(&result_selector) {
-        let title = a.text().collect::<Vec<_>>().join("").trim().to_string();
-        let href = a.value().attr("href").unwrap_or("");
-        let url = if href.starts_with("//") {
-            let abs = format!("https:{}", href);
-            if let Ok(parsed) = url::Url::from_str(&abs) {
-                parsed
-                    .query_pairs()
-                    .find(|(k, _)| k == "uddg")
-                    .map(|(_, v)| v.into_owned())
-                    .unwrap_or(abs)
-            } else {
-                abs
+    vec![
+        ("q", query.to_string()),
+        ("vqd", vqd.to_string()),
+        ("l", region(options).to_string()),
+        ("o", "json".to_string()),
+        ("f", format!("{},,,", published_after)),
+        ("p", "1".to_string()),
+        (
+            "s",
+            ((options.page.max(1) - 1) * JSON_PAGE_SIZE).to_string(),
+        ),
+    ]
+}
+
+#[derive(Debug, Deserialize)]
+struct NewsResponse {
+    #[serde(default)]
+    results: Vec<NewsResult>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NewsResult {
+    title: String,
+    url: String,
+    #[serde(default)]
+    excerpt: String,
+    #[serde(default)]
+    source: Option<String>,
+    #[serde(default)]
+    date: Option<i64>,
+    #[serde(default)]
+    image: Option<String>,
+}
+
+/// `news.js` のJSONを `SearchData` のベクターに変換し、必要なら新しい順に並べ替えます。
+fn parse_news(json: &str, sort: SortOrder) -> Result<Vec<SearchData>, String> {
+    let mut response: NewsResponse = serde_json::from_str(json)
+        .map_err(|e| format!("Failed to parse DuckDuckGo News response: {}", e))?;
+    if sort == SortOrder::Recency {
+        response
+            .results
+            .sort_by_key(|r| std::cmp::Reverse(r.date.unwrap_or(0)));
+    }
+    let results = response
+        .results
+        .into_iter()
+        .map(|r| {
+            let mut metadata = BTreeMap::new();
+            if let Some(source) = r.source.filter(|s| !s.is_empty()) {
+                metadata.insert("source".to_string(), source);
             }
-        } else {
-            href.to_string()
-        };
-        let mut description = String::new();
-        if let Some(parent_td) = a.parent().and_then(scraper::ElementRef::wrap) {
-            if let Some(parent_tr) = parent_td.parent().and_then(scraper::ElementRef::wrap) {
-                let mut next_tr = parent_tr.next_sibling();
-                while let Some(node) = next_tr {
-                    if let Some(tr_elem) = scraper::ElementRef::wrap(node) {
-                        let td_selector = Selector::parse("td.result-snippet").unwrap();
-                        if let Some(snippet_td) = tr_elem.select(&td_selector).next() {
-                            description = snippet_td
-                                .text()
-                                .collect::<Vec<_>>()
-                                .join("")
-                                .trim()
-                                .to_string();
-                            break;
-                        }
-                    }
-                    next_tr = node.next_sibling();
+            if let Some(date) = r.date {
+                metadata.insert("published_date".to_string(), search::format_unix_time(date));
+            }
+            if let Some(image) = r.image.filter(|i| i.starts_with("http")) {
+                metadata.insert("thumbnail".to_string(), image);
+            }
+            SearchData {
+                title: search::html_to_text(&r.title),
+                url: r.url,
+                description: search::html_to_text(&r.excerpt),
+                metadata,
+            }
+        })
+        .collect();
+    Ok(results)
+}
+
+#[derive(Debug, Deserialize)]
+struct ImageResponse {
+    #[serde(default)]
+    results: Vec<ImageResult>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ImageResult {
+    image: String,
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    thumbnail: Option<String>,
+    #[serde(default)]
+    url: Option<String>,
+    #[serde(default)]
+    width: Option<u64>,
+    #[serde(default)]
+    height: Option<u64>,
+    #[serde(default)]
+    source: Option<String>,
+}
+
+/// `i.js` のJSONを `SearchData` のベクターに変換します。`url` には画像本体のURLを格納します。
+fn parse_images(json: &str) -> Result<Vec<SearchData>, String> {
+    let response: ImageResponse = serde_json::from_str(json)
+        .map_err(|e| format!("Failed to parse DuckDuckGo Images response: {}", e))?;
+    let results = response
+        .results
+        .into_iter()
+        .map(|r| {
+            let mut metadata = BTreeMap::new();
+            if let Some(thumbnail) = r.thumbnail.filter(|t| !t.is_empty()) {
+                metadata.insert("thumbnail".to_string(), thumbnail);
+            }
+            if let (Some(width), Some(height)) = (r.width, r.height) {
+                metadata.insert("width".to_string(), width.to_string());
+                metadata.insert("height".to_string(), height.to_string());
+            }
+            if let Some(page) = r.url.filter(|u| !u.is_empty()) {
+                if let Some(host) = search::url_host(&page) {
+                    metadata.insert("source".to_string(), host);
                 }
+                metadata.insert("source_page".to_string(), page);
+            } else if let Some(source) = r.source.filter(|s| !s.is_empty()) {
+                metadata.insert("source".to_string(), source);
             }
-        }
-        if !title.is_empty() && !url.is_empty() {
-            results.push(SearchData {
-                title,
-                url,
-                description,
+            SearchData {
+                title: search::html_to_text(&r.title),
+                url: r.image,
+                description: String::new(),
+                metadata,
+            }
+        })
+        .collect();
+    Ok(results)
+}
+
+#[derive(Debug, Deserialize)]
+struct VideoResponse {
+    #[serde(default)]
+    results: Vec<VideoResult>,
+}
+
+#[derive(Debug, Deserialize)]
+struct VideoResult {
+    /// 動画ページのURL
+    content: String,
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    duration: Option<String>,
+    #[serde(default)]
+    publisher: Option<String>,
+    #[serde(default)]
+    uploader: Option<String>,
+    #[serde(default)]
+    published: Option<String>,
+    #[serde(default)]
+    images: Option<VideoImages>,
+}
+
+#[derive(Debug, Deserialize)]
+struct VideoImages {
+    #[serde(default)]
+    large: Option<String>,
+    #[serde(default)]
+    medium: Option<String>,
+    #[serde(default)]
+    small: Option<String>,
+}
+
+/// `v.js` のJSONを `SearchData` のベクターに変換し、必要なら新しい順に並べ替えます。
+fn parse_videos(json: &str, sort: SortOrder) -> Result<Vec<SearchData>, String> {
+    let mut response: VideoResponse = serde_json::from_str(json)
+        .map_err(|e| format!("Failed to parse DuckDuckGo Videos response: {}", e))?;
+    if sort == SortOrder::Recency {
+        response.results.sort_by_cached_key(|r| {
+            std::cmp::Reverse(r.published.as_deref().and_then(search::parse_unix_time))
+        });
+    }
+    let results = response
+        .results
+        .into_iter()
+        .map(|r| {
+            let mut metadata = BTreeMap::new();
+            for (key, value) in [
+                ("duration", r.duration),
+                ("publisher", r.publisher),
+                ("channel", r.uploader),
+                ("published_date", r.published),
+            ] {
+                if let Some(value) = value.filter(|v| !v.is_empty()) {
+                    metadata.insert(key.to_string(), value);
+                }
+            }
+            if let Some(thumbnail) = r
+                .images
+                .and_then(|i| i.medium.or(i.large).or(i.small))
+                .filter(|t| t.starts_with("http"))
+            {
+                metadata.insert("thumbnail".to_string(), thumbnail);
+            }
+            SearchData {
+                title: search::html_to_text(&r.title),
+                url: r.content,
+                description: search::html_to_text(&r.description),
+                metadata,
+            }
+        })
+        .collect();
+    Ok(results)
+}
+
+/// Instant Answer APIのレスポンス
+///
+/// 値がない項目は空文字列、`Infobox` は空文字列またはオブジェクトで返されます。
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct InstantAnswerResponse {
+    #[serde(default)]
+    heading: String,
+    #[serde(default)]
+    abstract_text: String,
+    #[serde(default)]
+    abstract_source: String,
+    #[serde(rename = "AbstractURL", default)]
+    abstract_url: String,
+    #[serde(default)]
+    answer: serde_json::Value,
+    #[serde(default)]
+    definition: String,
+    #[serde(default)]
+    definition_source: String,
+    #[serde(rename = "DefinitionURL", default)]
+    definition_url: String,
+    #[serde(default)]
+    image: String,
+    #[serde(default)]
+    infobox: serde_json::Value,
+    #[serde(default)]
+    related_topics: Vec<Topic>,
+}
+
+/// 関連トピック。カテゴリ分けされている場合は `Topics` に子のトピックを持つ
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct Topic {
+    #[serde(default)]
+    text: String,
+    #[serde(rename = "FirstURL", default)]
+    first_url: String,
+    #[serde(default)]
+    topics: Vec<Topic>,
+}
+
+/// Instant Answer APIのJSONを `Answer` に変換します。要約・回答・定義・インフォボックスのいずれもなければ `None` を返します。
+fn parse_instant_answer(json: &str) -> Result<Option<Answer>, String> {
+    let response: InstantAnswerResponse = serde_json::from_str(json)
+        .map_err(|e| format!("Failed to parse DuckDuckGo Instant Answer response: {}", e))?;
+    // 計算や単位変換などの回答 (`Answer`) は文字列で返される
+    let direct_answer = response.answer.as_str().unwrap_or("").to_string();
+    let (abstract_text, source, url) = if !response.abstract_text.is_empty() {
+        (
+            response.abstract_text,
+            response.abstract_source,
+            response.abstract_url,
+        )
+    } else if !direct_answer.is_empty() {
+        (direct_answer, "DuckDuckGo".to_string(), String::new())
+    } else {
+        (
+            response.definition,
+            response.definition_source,
+            response.definition_url,
+        )
+    };
+    let infobox: Vec<(String, String)> = response
+        .infobox
+        .get("content")
+        .and_then(serde_json::Value::as_array)
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| {
+                    let label = item.get("label")?.as_str()?.to_string();
+                    // 値は文字列のほか、数値やオブジェクト (`{"id": ..., "numeric-id": ...}`) の場合がある
+                    let value = match item.get("value")? {
+                        serde_json::Value::String(s) => s.clone(),
+                        serde_json::Value::Number(n) => n.to_string(),
+                        _ => return None,
+                    };
+                    (!value.is_empty()).then_some((label, value))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    if abstract_text.is_empty() && infobox.is_empty() {
+        return Ok(None);
+    }
+    let mut related_topics = Vec::new();
+    let mut stack: Vec<Topic> = response.related_topics.into_iter().rev().collect();
+    while let Some(topic) = stack.pop() {
+        if !topic.first_url.is_empty() && !topic.text.is_empty() {
+            related_topics.push(RelatedTopic {
+                text: topic.text,
+                url: topic.first_url,
             });
         }
+        stack.extend(topic.topics.into_iter().rev());
     }
-    Ok(results)
+    let image = match response.image.as_str() {
+        "" => None,
+        path if path.starts_with('/') => Some(format!("https://duckduckgo.com{}", path)),
+        url => Some(url.to_string()),
+    };
+    Ok(Some(Answer {
+        title: response.heading,
+        abstract_text,
+        source,
+        url,
+        image,
+        infobox,
+        related_topics,
+    }))
 }
@@ -0,0 +1,308 @@
+// src/search/github.rs
+
+//! GitHub検索API (`api.github.com/search/*`) のモジュール
+//!
+//! 検索対象は `SearchOptions::categories` の先頭の値で選択します。
+//! - `repositories` (デフォルト、`repos` も可): リポジトリ
+//! - `code`: コード (GitHubの仕様上、トークンが必要)
+//! - `issues` (`pulls` も可): Issue / Pull Request
+//!
+//! 設定 (`[engines.github] api_key`) または環境変数 `GITHUB_TOKEN` でトークンを指定すると、
+//! 認証付きでリクエストします (レート制限が緩和されます)。
+//!
+//! `SearchData::metadata` には次の値を格納します。
+//! - リポジトリ: `stars`, `forks`, `language`, `updated_at`, `archived`
+//! - コード: `repository`, `path`
+//! - Issue: `repository`, `state`, `comments`, `author`, `kind` (`issue` / `pull_request`), `updated_at`
+
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+
+use crate::search;
+use crate::{EngineType, SearchData, SearchOptions, config};
+
+const API_URL: &str = "https://api.github.com/search";
+
+/// 1ページあたりの取得件数
+const PAGE_SIZE: usize = 20;
+
+/// 説明として使用するIssue本文の最大文字数
+const BODY_EXCERPT_CHARS: usize = 300;
+
+/// 検索対象
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Repositories,
+    Code,
+    Issues,
+}
+
+impl Kind {
+    fn from_options(options: &SearchOptions) -> Result<Self, String> {
+        match options.categories.first().map(String::as_str) {
+            None | Some("repositories") | Some("repos") => Ok(Kind::Repositories),
+            Some("code") => Ok(Kind::Code),
+            Some("issues") | Some("pulls") => Ok(Kind::Issues),
+            Some(other) => Err(format!(
+                "Unknown GitHub search category: {} (expected repositories, code or issues)",
+                other
+            )),
+        }
+    }
+
+    fn path(self) -> &'static str {
+        match self {
+            Kind::Repositories => "repositories",
+            Kind::Code => "code",
+            Kind::Issues => "issues",
+        }
+    }
+}
+
+/// GitHubの検索APIで検索を実行します。
+///
+/// # 引数
+/// - `query`: 検索クエリ文字列 (GitHubの検索修飾子 `language:rust` などをそのまま使用可能)。
+/// - `options`: 検索オプション (ページ番号、検索対象のカテゴリ)。
+///
+/// # 戻り値
+/// `Result<Vec<SearchData>, String>`:
+/// - 成功した場合: 検索結果のリスト (`Vec<SearchData>`)。
+/// - 失敗した場合: エラーメッセージ文字列。
+pub async fn search_github(
+    query: String,
+    options: &SearchOptions,
+) -> Result<Vec<SearchData>, String> {
+    let kind = Kind::from_options(options)?;
+    let client = search::client(Some(EngineType::Github), Some(search::API_USER_AGENT))?;
+    let mut request = client
+        .get(format!("{}/{}", API_URL, kind.path()))
+        .query(&params(&query, options))
+        .header("Accept", accept(kind))
+        .header("X-GitHub-Api-Version", "2022-11-28");
+    if let Some(token) = token() {
+        request = request.bearer_auth(token);
+    }
+    parse_data(&search::fetch_text(request, "GitHub").await?, kind)
+}
+
+/// GitHubの検索APIで検索を同期で実行します。
+pub fn search_github_sync(
+    query: String,
+    options: &SearchOptions,
+) -> Result<Vec<SearchData>, String> {
+    let kind = Kind::from_options(options)?;
+    let client = search::blocking_client(Some(EngineType::Github), Some(search::API_USER_AGENT))?;
+    let mut request = client
+        .get(format!("{}/{}", API_URL, kind.path()))
+        .query(&params(&query, options))
+        .header("Accept", accept(kind))
+        .header("X-GitHub-Api-Version", "2022-11-28");
+    if let Some(token) = token() {
+        request = request.bearer_auth(token);
+    }
+    parse_data(&search::fetch_text_sync(request, "GitHub")?, kind)
+}
+
+fn token() -> Option<String> {
+    config::current()
+        .engines
+        .github
+        .api_key
+        .clone()
+        .filter(|k| !k.is_empty())
+}
+
+/// コード検索では一致箇所 (`text_matches`) を含めるメディアタイプを指定する
+fn accept(kind: Kind) -> &'static str {
+    match kind {
+        Kind::Code => "application/vnd.github.text-match+json",
+        _ => "application/vnd.github+json",
+    }
+}
+
+fn params(query: &str, options: &SearchOptions) -> Vec<(&'static str, String)> {
+    vec![
+        ("q", query.to_string()),
+        ("per_page", PAGE_SIZE.to_string()),
+        ("page", options.page.max(1).to_string()),
+    ]
+}
+
+#[derive(Debug, Deserialize)]
+struct Response {
+    #[serde(default)]
+    items: Vec<Item>,
+}
+
+/// リポジトリ・コード・Issueの各項目 (種類ごとに存在するフィールドが異なる)
+#[derive(Debug, Deserialize)]
+struct Item {
+    html_url: String,
+    // リポジトリ
+    #[serde(default)]
+    full_name: Option<String>,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    stargazers_count: Option<u64>,
+    #[serde(default)]
+    forks_count: Option<u64>,
+    #[serde(default)]
+    language: Option<String>,
+    #[serde(default)]
+    archived: Option<bool>,
+    #[serde(default)]
+    updated_at: Option<String>,
+    // コード
+    #[serde(default)]
+    path: Option<String>,
+    #[serde(default)]
+    repository: Option<Repository>,
+    #[serde(default)]
+    text_matches: Vec<TextMatch>,
+    // Issue
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    body: Option<String>,
+    #[serde(default)]
+    state: Option<String>,
+    #[serde(default)]
+    comments: Option<u64>,
+    #[serde(default)]
+    user: Option<User>,
+    #[serde(default)]
+    pull_request: Option<serde_json::Value>,
+    #[serde(default)]
+    repository_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Repository {
+    full_name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TextMatch {
+    #[serde(default)]
+    fragment: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct User {
+    login: String,
+}
+
+/// GitHub検索APIのJSONレスポンスを `SearchData` のベクターに変換します。
+fn parse_data(json: &str, kind: Kind) -> Result<Vec<SearchData>, String> {
+    let response: Response = serde_json::from_str(json)
+        .map_err(|e| format!("Failed to parse GitHub response: {}", e))?;
+    let results = response
+        .items
+        .into_iter()
+        .map(|item| match kind {
+            Kind::Repositories => repository_data(item),
+            Kind::Code => code_data(item),
+            Kind::Issues => issue_data(item),
+        })
+        .collect();
+    Ok(results)
+}
+
+fn repository_data(item: Item) -> SearchData {
+    let mut metadata = BTreeMap::new();
+    if let Some(stars) = item.stargazers_count {
+        metadata.insert("stars".to_string(), stars.to_string());
+    }
+    if let Some(forks) = item.forks_count {
+        metadata.insert("forks".to_string(), forks.to_string());
+    }
+    if let Some(language) = item.language {
+        metadata.insert("language".to_string(), language);
+    }
+    if let Some(updated_at) = item.updated_at {
+        metadata.insert("updated_at".to_string(), updated_at);
+    }
+    if item.archived == Some(true) {
+        metadata.insert("archived".to_string(), "true".to_string());
+    }
+    SearchData {
+        title: item.full_name.unwrap_or_else(|| item.html_url.clone()),
+        url: item.html_url,
+        description: item.description.unwrap_or_default(),
+        metadata,
+    }
+}
+
+fn code_data(item: Item) -> SearchData {
+    let mut metadata = BTreeMap::new();
+    let repository = item.repository.map(|r| r.full_name);
+    if let Some(repository) = &repository {
+        metadata.insert("repository".to_string(), repository.clone());
+    }
+    if let Some(path) = &item.path {
+        metadata.insert("path".to_string(), path.clone());
+    }
+    let title = match (repository, item.path) {
+        (Some(repository), Some(path)) => format!("{}/{}", repository, path),
+        (_, Some(path)) => path,
+        _ => item.html_url.clone(),
+    };
+    let description = item
+        .text_matches
+        .iter()
+        .map(|m| m.fragment.split_whitespace().collect::<Vec<_>>().join(" "))
+        .collect::<Vec<_>>()
+        .join(" … ");
+    SearchData {
+        title,
+        url: item.html_url,
+        description,
+        metadata,
+    }
+}
+
+fn issue_data(item: Item) -> SearchData {
+    let mut metadata = BTreeMap::new();
+    // repository_url は `https://api.github.com/repos/<owner>/<repo>` の形式
+    if let Some(repository) = item
+        .repository_url
+        .as_deref()
+        .and_then(|u| u.split_once("/repos/"))
+        .map(|(_, name)| name.to_string())
+    {
+        metadata.insert("repository".to_string(), repository);
+    }
+    if let Some(state) = item.state {
+        metadata.insert("state".to_string(), state);
+    }
+    if let Some(comments) = item.comments {
+        metadata.insert("comments".to_string(), comments.to_string());
+    }
+    if let Some(user) = item.user {
+        metadata.insert("author".to_string(), user.login);
+    }
+    let kind = if item.pull_request.is_some() {
+        "pull_request"
+    } else {
+        "issue"
+    };
+    metadata.insert("kind".to_string(), kind.to_string());
+    if let Some(updated_at) = item.updated_at {
+        metadata.insert("updated_at".to_string(), updated_at);
+    }
+    let body = item.body.unwrap_or_default();
+    let body = body.split_whitespace().collect::<Vec<_>>().join(" ");
+    let description = match body.char_indices().nth(BODY_EXCERPT_CHARS) {
+        Some((end, _)) => format!("{}…", &body[..end]),
+        None => body,
+    };
+    SearchData {
+        title: item.title.unwrap_or_else(|| item.html_url.clone()),
+        url: item.html_url,
+        description,
+        metadata,
+    }
+}
@@ -0,0 +1,103 @@
+// src/search/goo.rs
+
+//! goo検索 (search.goo.ne.jp) のモジュール
+//!
+//! 検索結果ページのHTMLをパースします。結果のリンクがgoo内のクリック計測用URL
+//! (`https://search.goo.ne.jp/...?...&url=<URL>`) になっている場合は、
+//! クエリパラメータに含まれる本来のURLを [`crate::canonical`] で復元します。
+
+use scraper::{ElementRef, Html, Selector};
+
+use crate::search::{self, element_text};
+use crate::{EngineType, SearchData, SearchOptions, canonical};
+
+const SEARCH_URL: &str = "https://search.goo.ne.jp/web.jsp";
+
+/// 1ページあたりの件数
+const PAGE_SIZE: usize = 10;
+
+/// goo検索を実行します。
+///
+/// # 引数
+/// - `query`: 検索クエリ文字列。
+/// - `options`: 検索オプション (ページ番号)。
+///
+/// # 戻り値
+/// `Result<Vec<SearchData>, String>`:
+/// - 成功した場合: 検索結果のリスト (`Vec<SearchData>`)。
+/// - 失敗した場合: エラーメッセージ文字列。
+pub async fn search_goo(query: String, options: &SearchOptions) -> Result<Vec<SearchData>, String> {
+    let client = search::client(Some(EngineType::Goo), Some(search::DEFAULT_USER_AGENT))?;
+    let request = client.get(SEARCH_URL).query(&params(&query, options));
+    Ok(parse_data(&search::fetch_text(request, "goo").await?))
+}
+
+/// goo検索を同期で実行します。
+pub fn search_goo_sync(query: String, options: &SearchOptions) -> Result<Vec<SearchData>, String> {
+    let client = search::blocking_client(Some(EngineType::Goo), Some(search::DEFAULT_USER_AGENT))?;
+    let request = client.get(SEARCH_URL).query(&params(&query, options));
+    Ok(parse_data(&search::fetch_text_sync(request, "goo")?))
+}
+
+/// クエリパラメータ。`FR` は結果の開始位置 (0始まり) です。
+fn params(query: &str, options: &SearchOptions) -> Vec<(&'static str, String)> {
+    vec![
+        ("MT", query.to_string()),
+        ("IE", "UTF-8".to_string()),
+        ("OE", "UTF-8".to_string()),
+        ("mode", "0".to_string()),
+        ("FR", ((options.page.max(1) - 1) * PAGE_SIZE).to_string()),
+    ]
+}
+
+/// gooの検索結果HTMLを `SearchData` のベクターにパースします。
+///
+/// 各結果は `div.result` で、以下の要素から値を抽出します。
+/// - **URL**: `.title a[href]` (見つからなければ結果内の最初の `a[href]`)。計測用URLは [`canonical::resolve`] で復元
+/// - **タイトル**: リンクのテキスト
+/// - **説明**: `.txt` (見つからなければ `.result_txt`)
+///
+/// # 重要な注意点:
+/// gooのHTML構造は変更される可能性があるため、複数のセレクタを順に試します。
+fn parse_data(html: &str) -> Vec<SearchData> {
+    let document = Html::parse_document(html);
+    let result_selector = Selector::parse("div.result").unwrap();
+    let link_selectors = [
+        Selector::parse(".title a[href]").unwrap(),
+        Selector::parse("a[href]").unwrap(),
+    ];
+    let description_selectors = [
+        Selector::parse(".txt").unwrap(),
+        Selector::parse(".result_txt").unwrap(),
+    ];
+
+    let mut results = Vec::new();
+    for result in document.select(&result_selector) {
+        let Some(link) = first_match(result, &link_selectors) else {
+            continue;
+        };
+        let url = canonical::resolve(SEARCH_URL, link.value().attr("href").unwrap_or(""))
+            .unwrap_or_default();
+        let title = element_text(link);
+        if title.is_empty() || !url.starts_with("http") {
+            continue;
+        }
+        let description = first_match(result, &description_selectors)
+            .map(element_text)
+            .unwrap_or_default();
+        results.push(SearchData {
+            title,
+            url,
+            description,
+            ..Default::default()
+        });
+    }
+    results
+}
+
+/// セレクタを順に試し、最初に見つかった要素を返す
+fn first_match<'a>(element: ElementRef<'a>, selectors: &[Selector]) -> Option<ElementRef<'a>> {
+    selectors
+        .iter()
+        .find_map(|selector| element.select(selector).next())
+}
@@ -1,7 +1,12 @@
 // src/search/google.rs
 
-use crate::SearchData; // lib.rsからSearchData構造体をインポート
-use reqwest; // HTTPクライアントのreqwestをインポート
+use std::collections::BTreeMap;
+
+use crate::search; // 設定を反映したHTTPクライアントの構築に使用
+use crate::{
+    Answer, EngineType, RelatedTopic, SearchData, SearchOptions, SearchResponse, SortOrder,
+    TimeRange, Vertical, canonical,
+}; // lib.rsからSearchData構造体をインポート
 use scraper::{ElementRef, Html, Selector}; // HTMLパース用のscraperクレートをインポート
 // URL解析のためにurlクレートをインポート
 use urlencoding; // URLエンコーディングのためにurlencodingをインポート
@@ -18,21 +23,30 @@ use urlencoding; // URLエンコーディングのためにurlencodingをイン
 /// - 成功した場合: 検索結果のリスト (`Vec<SearchData>`)。
 /// - 失敗した場合: エラーメッセージ文字列。
 pub async fn search_google(query: String) -> Result<Vec<SearchData>, String> {
+    search_google_with_options(query, &SearchOptions::default()).await
+}
+
+/// 検索オプション (ページ番号など) を指定してGoogle検索を実行します。
+pub async fn search_google_with_options(
+    query: String,
+    options: &SearchOptions,
+) -> Result<Vec<SearchData>, String> {
+    search_google_response(query, options)
+        .await
+        .map(|response| response.results)
+}
+
+/// 検索オプションを指定してGoogle検索を実行し、検索結果とインスタントアンサー
+/// (強調スニペット・ナレッジパネル) を返します。
+pub async fn search_google_response(
+    query: String,
+    options: &SearchOptions,
+) -> Result<SearchResponse, String> {
     // Google検索のURLを構築
-    let url = format!(
-        "https://www.google.com/search?q={}",
-        urlencoding::encode(&query)
-    );
+    let url = build_url(&query, options);
 
-    // reqwest::Clientを使用し、クッキーとリダイレクトを有効化
-    let client = match reqwest::ClientBuilder::new()
-        .cookie_store(true)
-        .user_agent("w3m (w3m/0.5.3+git20230121)")
-        .build()
-    {
-        Ok(c) => c,
-        Err(e) => return Err(format!("Failed to build reqwest client: {}", e)),
-    };
+    // 設定を反映したクライアントを使用し、クッキーとリダイレクトを有効化
+    let client = search::client(Some(EngineType::Google), Some(search::DEFAULT_USER_AGENT))?;
 
     // クライアントでGETリクエストを送信
     match client.get(&url).send().await {
@@ -41,8 +55,17 @@ pub async fn search_google(query: String) -> Result<Vec<SearchData>, String> {
                 match response.text().await {
                     Ok(html) => {
                         // 取得したHTMLをパースし、検索データを抽出
-                        let results = parse_data(html);
-                        Ok(results)
+                        let mut response = match options.vertical {
+                            Vertical::Web => parse_page_info(&html, &query),
+                            _ => SearchResponse::default(),
+                        };
+                        response.results = match options.vertical {
+                            Vertical::News => parse_news(&html),
+                            Vertical::Images => parse_images(&html),
+                            Vertical::Videos => parse_videos(&html),
+                            Vertical::Web => parse_data(html),
+                        };
+                        Ok(response)
                     }
                     Err(e) => Err(format!("Failed to get text from Google response: {}", e)),
                 }
@@ -69,21 +92,28 @@ pub async fn search_google(query: String) -> Result<Vec<SearchData>, String> {
 /// - 成功した場合: 検索結果のリスト (`Vec<SearchData>`)。
 /// - 失敗した場合: エラーメッセージ文字列。
 pub fn search_google_sync(query: String) -> Result<Vec<SearchData>, String> {
+    search_google_sync_with_options(query, &SearchOptions::default())
+}
+
+/// 検索オプション (ページ番号など) を指定してGoogle検索を同期で実行します。
+pub fn search_google_sync_with_options(
+    query: String,
+    options: &SearchOptions,
+) -> Result<Vec<SearchData>, String> {
+    search_google_response_sync(query, options).map(|response| response.results)
+}
+
+/// 検索オプションを指定してGoogle検索を同期で実行し、検索結果とインスタントアンサーを返します。
+pub fn search_google_response_sync(
+    query: String,
+    options: &SearchOptions,
+) -> Result<SearchResponse, String> {
     // Google検索のURLを構築
-    let url = format!(
-        "https://www.google.com/search?q={}",
-        urlencoding::encode(&query)
-    );
+    let url = build_url(&query, options);
 
-    // reqwest::Clientを使用し、クッキーとリダイレクトを有効化
-    let client = match reqwest::blocking::ClientBuilder::new()
-        .cookie_store(true)
-        .user_agent("w3m (w3m/0.5.3+git20230121)")
-        .build()
-    {
-        Ok(c) => c,
-        Err(e) => return Err(format!("Failed to build reqwest client: {}", e)),
-    };
+    // 設定を反映したクライアントを使用し、クッキーとリダイレクトを有効化
+    let client =
+        search::blocking_client(Some(EngineType::Google), Some(search::DEFAULT_USER_AGENT))?;
 
     // クライアントでGETリクエストを送信
     match client.get(&url).send() {
@@ -92,8 +122,17 @@ pub fn search_google_sync(query: String) -> Result<Vec<SearchData>, String> {
                 match response.text() {
                     Ok(html) => {
                         // 取得したHTMLをパースし、検索データを抽出
-                        let results = parse_data(html);
-                        Ok(results)
+                        let mut response = match options.vertical {
+                            Vertical::Web => parse_page_info(&html, &query),
+                            _ => SearchResponse::default(),
+                        };
+                        response.results = match options.vertical {
+                            Vertical::News => parse_news(&html),
+                            Vertical::Images => parse_images(&html),
+                            Vertical::Videos => parse_videos(&html),
+                            Vertical::Web => parse_data(html),
+                        };
+                        Ok(response)
                     }
                     Err(e) => Err(format!("Failed to get text from Google response: {}", e)),
                 }
@@ -108,6 +147,96 @@ pub fn search_google_sync(query: String) -> Result<Vec<SearchData>, String> {
     }
 }
 
+/// Googleの検索候補 (`complete/search?client=firefox`) を取得します。
+///
+/// # 戻り値
+/// 候補の文字列のリスト (Googleが返した順)。
+pub async fn suggest_google(prefix: &str) -> Result<Vec<String>, String> {
+    let client = search::client(Some(EngineType::Google), Some(search::BROWSER_USER_AGENT))?;
+    let request = client.get(SUGGEST_URL).query(&suggest_params(prefix));
+    parse_suggestions(&search::fetch_text(request, "Google Suggest").await?)
+}
+
+/// Googleの検索候補を同期で取得します。
+pub fn suggest_google_sync(prefix: &str) -> Result<Vec<String>, String> {
+    let client =
+        search::blocking_client(Some(EngineType::Google), Some(search::BROWSER_USER_AGENT))?;
+    let request = client.get(SUGGEST_URL).query(&suggest_params(prefix));
+    parse_suggestions(&search::fetch_text_sync(request, "Google Suggest")?)
+}
+
+const SUGGEST_URL: &str = "https://www.google.com/complete/search";
+
+fn suggest_params(prefix: &str) -> Vec<(&'static str, String)> {
+    vec![
+        ("client", "firefox".to_string()),
+        ("q", prefix.to_string()),
+        // 応答の文字コードをUTF-8に固定する
+        ("ie", "utf-8".to_string()),
+        ("oe", "utf-8".to_string()),
+    ]
+}
+
+/// OpenSearch Suggestions形式 (`["クエリ", ["候補1", "候補2", ...]]`) の応答から候補を取り出す
+fn parse_suggestions(json: &str) -> Result<Vec<String>, String> {
+    let value: serde_json::Value = serde_json::from_str(json)
+        .map_err(|e| format!("Failed to parse Google Suggest response: {}", e))?;
+    Ok(value
+        .get(1)
+        .and_then(serde_json::Value::as_array)
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(serde_json::Value::as_str)
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+/// Google検索のURLを構築します。2ページ目以降は `start` パラメータで開始位置を指定し、
+/// 言語は `hl`/`lr`、期間は `tbs=qdr:*` で指定します。
+/// ニュース検索では `tbm=nws`、画像検索では `tbm=isch`、動画検索では `tbm=vid` を付け、
+/// 新しい順の並び替えは `tbs` に `sbd:1` を加えて指定します。
+fn build_url(query: &str, options: &SearchOptions) -> String {
+    let mut url = format!(
+        "https://www.google.com/search?q={}",
+        urlencoding::encode(query)
+    );
+    match options.vertical {
+        Vertical::News => url.push_str("&tbm=nws"),
+        Vertical::Images => url.push_str("&tbm=isch"),
+        Vertical::Videos => url.push_str("&tbm=vid"),
+        Vertical::Web => {}
+    }
+    let page = options.page.max(1);
+    if page > 1 {
+        url.push_str(&format!("&start={}", (page - 1) * 10));
+    }
+    if let Some(language) = &options.language {
+        let language = urlencoding::encode(language);
+        url.push_str(&format!("&hl={}&lr=lang_{}", language, language));
+    }
+    let mut tbs = Vec::new();
+    if let Some(time_range) = options.time_range {
+        tbs.push(match time_range {
+            TimeRange::Day => "qdr:d",
+            TimeRange::Week => "qdr:w",
+            TimeRange::Month => "qdr:m",
+            TimeRange::Year => "qdr:y",
+        });
+    }
+    if matches!(options.vertical, Vertical::News | Vertical::Videos)
+        && options.sort == SortOrder::Recency
+    {
+        tbs.push("sbd:1");
+    }
+    if !tbs.is_empty() {
+        url.push_str(&format!("&tbs={}", tbs.join(",")));
+    }
+    url
+}
+
 /// Google検索結果の生のHTML文字列を `SearchData` のベクターにパースします。
 ///
 /// この関数は、以下の指定された基準に基づいてタイトル、URL、説明を抽出します。
@@ -148,17 +277,8 @@ fn parse_data(html_str: String) -> Vec<SearchData> {
             Some(a) => a,
             None => continue,
         };
-        // URL: href から /url?q=... のqパラメータを正確に抽出
-        let mut url: Option<String> = None;
-        if let Some(href) = a_element.value().attr("href") {
-            if let Some(q_start) = href.find("q=") {
-                let q_and_rest = &href[q_start + 2..];
-                let q_value = q_and_rest.split('&').next().unwrap_or("");
-                if let Ok(decoded) = urlencoding::decode(q_value) {
-                    url = Some(decoded.into_owned());
-                }
-            }
-        }
+        // URL: href (/url?q=...) のリダイレクト先
+        let url = redirect_target(a_element.value().attr("href").unwrap_or(""));
         // タイトル: a要素の最初のspan子要素のテキスト
         let span_selector = Selector::parse("span").unwrap();
         let title = a_element
@@ -200,8 +320,523 @@ fn parse_data(html_str: String) -> Vec<SearchData> {
                 title: t,
                 url: u,
                 description,
+                ..Default::default()
             });
         }
     }
     search_results
 }
+
+/// Googleニュース検索 (`tbm=nws`) の結果HTMLを `SearchData` のベクターにパースします。
+///
+/// 各結果は `/url?q=...` へのリンクで、以下の要素から値を抽出します。
+/// - **URL**: リンクの `q` パラメータ
+/// - **タイトル**: リンク内の `h3` (なければ `.vvjwJb`)
+/// - **source**: リンク内の `.UPmit` (配信元)
+/// - **説明**: 結果ブロック ([`result_block`]) 内の `.s3v9rd` のうち、最も長いテキスト
+/// - **age**: 結果ブロック内の `.r0bn4c` または `.tAd8D` (`3 hours ago` などの表記)
+/// - **published_date**: `age` をUTCのISO 8601形式に変換したもの (変換できた場合のみ)
+/// - **thumbnail**: 結果ブロック内の `http(s)` の `img`
+///
+/// # 重要な注意点:
+/// `parse_data` と同様に、GoogleのHTML構造の変更で使えなくなる可能性があります。
+fn parse_news(html: &str) -> Vec<SearchData> {
+    let document = Html::parse_document(html);
+    let link_selector = Selector::parse(r#"a[href^="/url?q="]"#).unwrap();
+    let title_selector = Selector::parse("h3, .vvjwJb").unwrap();
+    let source_selector = Selector::parse(".UPmit").unwrap();
+    let snippet_selector = Selector::parse(".s3v9rd").unwrap();
+    let time_selector = Selector::parse(".r0bn4c, .tAd8D").unwrap();
+    let image_selector = Selector::parse(r#"img[src^="http"]"#).unwrap();
+
+    let mut results: Vec<SearchData> = Vec::new();
+    for link in document.select(&link_selector) {
+        let Some(title) = link
+            .select(&title_selector)
+            .next()
+            .map(search::element_text)
+            .filter(|t| !t.is_empty())
+        else {
+            continue;
+        };
+        let Some(url) = redirect_target(link.value().attr("href").unwrap_or("")) else {
+            continue;
+        };
+        if results.iter().any(|r| r.url == url) {
+            continue;
+        }
+        let block = result_block(link, &link_selector);
+
+        let mut metadata = BTreeMap::new();
+        if let Some(source) = link
+            .select(&source_selector)
+            .next()
+            .map(search::element_text)
+            .filter(|s| !s.is_empty())
+        {
+            metadata.insert("source".to_string(), source);
+        }
+        let mut description = String::new();
+        if let Some(block) = block {
+            description = block
+                .select(&snippet_selector)
+                .map(search::element_text)
+                .max_by_key(|t| t.len())
+                .unwrap_or_default();
+            if let Some(time) = block
+                .select(&time_selector)
+                .next()
+                .map(search::element_text)
+                .filter(|t| !t.is_empty())
+            {
+                // 説明の末尾に付く日時の表記は取り除く
+                if let Some(rest) = description.strip_suffix(&time) {
+                    description = rest.trim_end_matches([' ', '·']).to_string();
+                }
+                if let Some(date) = search::parse_date_text(&time, search::unix_now()) {
+                    metadata.insert("published_date".to_string(), search::format_unix_time(date));
+                }
+                metadata.insert("age".to_string(), time);
+            }
+            if let Some(src) = block
+                .select(&image_selector)
+                .next()
+                .and_then(|img| img.value().attr("src"))
+            {
+                metadata.insert("thumbnail".to_string(), src.to_string());
+            }
+        }
+        results.push(SearchData {
+            title,
+            url,
+            description,
+            metadata,
+        });
+    }
+    results
+}
+
+/// Google画像検索 (`tbm=isch`) の結果HTMLを `SearchData` のベクターにパースします。
+///
+/// 各結果はサムネイル画像 (`img[src^="http"]`) とそれを囲むリンクで、以下の値を抽出します。
+/// - **URL**: リンクが `/imgres?imgurl=...` の場合は `imgurl` (画像本体)、なければサムネイルのURL
+/// - **source_page**: `imgres` の `imgrefurl`、または `/url?q=...` の `q`
+/// - **width** / **height**: `imgres` の `w` / `h`
+/// - **タイトル**: 結果ブロック内のテキストのうち、ホスト名以外の最初のもの (なければ `alt`)
+///
+/// # 重要な注意点:
+/// 簡易HTML版では画像本体のURLが含まれない場合があり、その場合はサムネイルのURLを返します。
+fn parse_images(html: &str) -> Vec<SearchData> {
+    let document = Html::parse_document(html);
+    let image_selector = Selector::parse(r#"a[href] img[src^="http"]"#).unwrap();
+
+    let mut results: Vec<SearchData> = Vec::new();
+    for image in document.select(&image_selector) {
+        let thumbnail = image.value().attr("src").unwrap_or("").to_string();
+        let Some(link) = image
+            .ancestors()
+            .filter_map(ElementRef::wrap)
+            .find(|e| e.value().name() == "a")
+        else {
+            continue;
+        };
+        let Ok(href) = url::Url::parse("https://www.google.com")
+            .and_then(|base| base.join(link.value().attr("href").unwrap_or("")))
+        else {
+            continue;
+        };
+        let param = |name: &str| {
+            href.query_pairs()
+                .find(|(k, _)| k == name)
+                .map(|(_, v)| v.into_owned())
+                .filter(|v| !v.is_empty())
+        };
+        let (url, source_page) = match href.path() {
+            "/imgres" => (param("imgurl"), param("imgrefurl")),
+            "/url" => (None, param("q")),
+            _ => continue,
+        };
+
+        let mut metadata = BTreeMap::new();
+        metadata.insert("thumbnail".to_string(), thumbnail.clone());
+        if let (Some(width), Some(height)) = (param("w"), param("h")) {
+            metadata.insert("width".to_string(), width);
+            metadata.insert("height".to_string(), height);
+        }
+        let source = source_page.as_deref().and_then(search::url_host);
+        if let Some(source) = &source {
+            metadata.insert("source".to_string(), source.clone());
+        }
+        if let Some(source_page) = source_page {
+            metadata.insert("source_page".to_string(), source_page);
+        }
+        // 結果ブロック (リンクの親) 内のテキストからタイトルを探す
+        let title = link
+            .parent()
+            .and_then(ElementRef::wrap)
+            .map(|block| {
+                block
+                    .text()
+                    .map(str::trim)
+                    .find(|t| !t.is_empty() && Some(*t) != source.as_deref())
+                    .unwrap_or("")
+                    .to_string()
+            })
+            .filter(|t| !t.is_empty())
+            .or_else(|| image.value().attr("alt").map(str::to_string))
+            .unwrap_or_default();
+        let url = url.unwrap_or(thumbnail);
+        if results.iter().any(|r| r.url == url) {
+            continue;
+        }
+        results.push(SearchData {
+            title,
+            url,
+            description: String::new(),
+            metadata,
+        });
+    }
+    results
+}
+
+/// Google動画検索 (`tbm=vid`) の結果HTMLを `SearchData` のベクターにパースします。
+///
+/// 各結果は `/url?q=...` へのリンクで、以下の要素から値を抽出します。
+/// - **URL**: リンクの `q` パラメータ (動画ページ)
+/// - **タイトル**: リンク内の `h3` (なければ `.vvjwJb`)
+/// - **説明**: 結果ブロック ([`result_block`]) 内の `.s3v9rd` のうち、最も長いテキスト
+/// - **duration**: 結果ブロック内の `12:34` 形式のテキスト
+/// - **publisher** / **channel** / **age**: `YouTube · チャンネル名 · 2024/06/05` 形式の行
+///   (日付として解釈できる部分を `age`、それ以外をチャンネルとする)
+/// - **published_date**: `age` をUTCのISO 8601形式に変換したもの
+/// - **thumbnail**: 結果ブロック内の `http(s)` の `img`
+///
+/// # 重要な注意点:
+/// `parse_data` と同様に、GoogleのHTML構造の変更で使えなくなる可能性があります。
+fn parse_videos(html: &str) -> Vec<SearchData> {
+    let document = Html::parse_document(html);
+    let link_selector = Selector::parse(r#"a[href^="/url?q="]"#).unwrap();
+    let title_selector = Selector::parse("h3, .vvjwJb").unwrap();
+    let snippet_selector = Selector::parse(".s3v9rd").unwrap();
+    let image_selector = Selector::parse(r#"img[src^="http"]"#).unwrap();
+
+    let mut results: Vec<SearchData> = Vec::new();
+    for link in document.select(&link_selector) {
+        let Some(title) = link
+            .select(&title_selector)
+            .next()
+            .map(search::element_text)
+            .filter(|t| !t.is_empty())
+        else {
+            continue;
+        };
+        let Some(url) = redirect_target(link.value().attr("href").unwrap_or("")) else {
+            continue;
+        };
+        if results.iter().any(|r| r.url == url) {
+            continue;
+        }
+        let block = result_block(link, &link_selector);
+
+        let mut metadata = BTreeMap::new();
+        let mut description = String::new();
+        if let Some(block) = block {
+            description = block
+                .select(&snippet_selector)
+                .map(search::element_text)
+                .max_by_key(|t| t.len())
+                .unwrap_or_default();
+            let texts: Vec<&str> = block
+                .text()
+                .map(str::trim)
+                .filter(|t| !t.is_empty())
+                .collect();
+            if let Some(duration) = texts.iter().find(|t| is_duration(t)) {
+                metadata.insert("duration".to_string(), duration.to_string());
+            }
+            // `配信サイト · チャンネル · 投稿日` (チャンネルまたは投稿日は省略されることがある)
+            if let Some(line) = texts.iter().find(|t| t.contains('·') && **t != title) {
+                let parts: Vec<&str> = line
+                    .split('·')
+                    .map(str::trim)
+                    .filter(|p| !p.is_empty())
+                    .collect();
+                let mut parts = parts.into_iter();
+                if let Some(publisher) = parts.next() {
+                    metadata.insert("publisher".to_string(), publisher.to_string());
+                }
+                for part in parts {
+                    match search::parse_date_text(part, search::unix_now()) {
+                        Some(date) if !metadata.contains_key("age") => {
+                            metadata.insert("age".to_string(), part.to_string());
+                            metadata.insert(
+                                "published_date".to_string(),
+                                search::format_unix_time(date),
+                            );
+                        }
+                        Some(_) => {}
+                        None => {
+                            metadata
+                                .entry("channel".to_string())
+                                .or_insert_with(|| part.to_string());
+                        }
+                    }
+                }
+            }
+            if let Some(src) = block
+                .select(&image_selector)
+                .next()
+                .and_then(|img| img.value().attr("src"))
+            {
+                metadata.insert("thumbnail".to_string(), src.to_string());
+            }
+            for key in ["duration", "age"] {
+                if let Some(value) = metadata.get(key)
+                    && let Some(rest) = description.strip_suffix(value.as_str())
+                {
+                    description = rest.trim_end_matches([' ', '·']).to_string();
+                }
+            }
+        }
+        results.push(SearchData {
+            title,
+            url,
+            description,
+            metadata,
+        });
+    }
+    results
+}
+
+/// 結果のリンクを含むブロック (結果カード) を返す
+///
+/// リンクの祖先を、結果カード (`.xpd`) に達するか、別の結果へのリンク (`link_selector` に一致し、
+/// 異なるURLを指すもの) を含む祖先の手前までさかのぼります。
+fn result_block<'a>(link: ElementRef<'a>, link_selector: &Selector) -> Option<ElementRef<'a>> {
+    let target = redirect_target(link.value().attr("href").unwrap_or(""));
+    let mut block = None;
+    for ancestor in link.ancestors().filter_map(ElementRef::wrap) {
+        if ancestor
+            .select(link_selector)
+            .any(|other| redirect_target(other.value().attr("href").unwrap_or("")) != target)
+        {
+            break;
+        }
+        block = Some(ancestor);
+        if ancestor.value().classes().any(|c| c == "xpd") {
+            break;
+        }
+    }
+    block
+}
+
+/// Googleのリダイレクトリンク (`/url?q=...`) から本来のURLを取り出し、正規化して返す
+fn redirect_target(href: &str) -> Option<String> {
+    let url = url::Url::parse("https://www.google.com")
+        .and_then(|base| base.join(href))
+        .ok()?;
+    canonical::unwrap_redirect(&url).map(|target| canonical::canonicalize(&target))
+}
+
+/// `4:05` や `1:02:03` 形式の再生時間かどうか
+fn is_duration(text: &str) -> bool {
+    let parts: Vec<&str> = text.split(':').collect();
+    (2..=3).contains(&parts.len())
+        && parts
+            .iter()
+            .all(|p| !p.is_empty() && p.len() <= 2 && p.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// 「他の人はこちらも質問」のブロックの見出し
+const PEOPLE_ALSO_ASK_HEADINGS: &[&str] = &["People also ask", "他の人はこちらも質問"];
+
+/// Google検索結果のHTMLから、検索結果以外の情報 (インスタントアンサー、スペル修正、関連検索、
+/// 「他の人はこちらも質問」) を取り出します。`results` は空のまま返します。
+///
+/// - **corrected_query**: `spell=1` を含む `/search?q=...` リンクのクエリ
+/// - **auto_corrected**: 元のクエリで検索し直すリンク (`nfpr=1`) があれば、修正後のクエリで検索済み
+/// - **related_searches**: 結果ページ内の `/search?q=...` リンクのうち、タブ・ページ送り・検索ツール・
+///   インスタントアンサー内のリンクを除いたもののクエリ
+/// - **people_also_ask**: 「他の人はこちらも質問」の質問。質問の要素 (`.related-question-pair[data-q]`) の
+///   `data-q`、または見出しが「People also ask」などのブロック (`.xpd`) 内の `?` で終わるテキスト
+fn parse_page_info(html: &str, query: &str) -> SearchResponse {
+    let document = Html::parse_document(html);
+    let search_link_selector = Selector::parse(r#"a[href^="/search?"]"#).unwrap();
+    let block_selector = Selector::parse("div.xpd").unwrap();
+    let result_title_selector = Selector::parse(r#"a[href^="/url?q="] h3"#).unwrap();
+    let answer_selector = Selector::parse(".iBp4i, .deIvCb").unwrap();
+
+    let mut corrected_query = None;
+    let mut auto_corrected = false;
+    let mut related_searches: Vec<String> = Vec::new();
+    for link in document.select(&search_link_selector) {
+        let Ok(href) = url::Url::parse("https://www.google.com")
+            .and_then(|base| base.join(link.value().attr("href").unwrap_or("")))
+        else {
+            continue;
+        };
+        let params: Vec<(String, String)> = href.query_pairs().into_owned().collect();
+        let has = |name: &str| params.iter().any(|(k, _)| k == name);
+        let Some(q) = params
+            .iter()
+            .find(|(k, _)| k == "q")
+            .map(|(_, v)| v.trim().to_string())
+            .filter(|q| !q.is_empty())
+        else {
+            continue;
+        };
+        if has("spell") {
+            corrected_query.get_or_insert(q);
+        } else if has("nfpr") {
+            auto_corrected = true;
+        } else if !["tbm", "start", "tbs", "lr", "safe"].iter().any(|p| has(p))
+            && !q.eq_ignore_ascii_case(query.trim())
+            && !related_searches.contains(&q)
+            // インスタントアンサーの関連トピックは除く
+            && !link
+                .ancestors()
+                .filter_map(ElementRef::wrap)
+                .any(|e| {
+                    e.value().classes().any(|c| c == "xpd")
+                        && e.select(&answer_selector).next().is_some()
+                })
+        {
+            related_searches.push(q);
+        }
+    }
+
+    // 修正後のクエリへのリンク (タブなど) は関連検索に含めない
+    if let Some(corrected) = &corrected_query {
+        related_searches.retain(|q| !q.eq_ignore_ascii_case(corrected));
+    }
+
+    let mut people_also_ask: Vec<String> = Vec::new();
+    let question_selector = Selector::parse(".related-question-pair[data-q]").unwrap();
+    let questions = document
+        .select(&question_selector)
+        .filter_map(|q| q.value().attr("data-q"))
+        .map(str::trim);
+    // 見出しが「他の人はこちらも質問」のブロックだけを対象にする
+    let paa_blocks = document.select(&block_selector).filter(|b| {
+        b.select(&result_title_selector).next().is_none()
+            && b.text()
+                .map(str::trim)
+                .find(|t| !t.is_empty())
+                .is_some_and(|heading| {
+                    PEOPLE_ALSO_ASK_HEADINGS
+                        .iter()
+                        .any(|h| heading.eq_ignore_ascii_case(h))
+                })
+    });
+    let block_questions = paa_blocks
+        .flat_map(|b| b.text().map(str::trim))
+        .filter(|t| t.ends_with('?') || t.ends_with('？'));
+    for text in questions.chain(block_questions) {
+        if !text.is_empty() && !people_also_ask.iter().any(|q| q == text) {
+            people_also_ask.push(text.to_string());
+        }
+    }
+
+    SearchResponse {
+        answer: parse_answer(&document),
+        auto_corrected: auto_corrected && corrected_query.is_some(),
+        corrected_query,
+        related_searches,
+        people_also_ask,
+        ..Default::default()
+    }
+}
+
+/// Google検索結果のHTMLから、強調スニペットまたはナレッジパネルを `Answer` として取り出します。
+///
+/// 簡易HTML版では、これらは短い回答 (`.iBp4i`) または見出し (`.deIvCb`) を含むブロック (`.xpd`) として
+/// 表示されます。そのブロックから以下の値を抽出します。
+/// - **abstract_text**: 短い回答 `.iBp4i` (例: `8,849 m`)、なければ `.s3v9rd` のうち最も長いテキスト
+/// - **title**: 見出し `.deIvCb` (なければ出典リンクのタイトル)
+/// - **url** / **source**: ブロック内の最初の `/url?q=...` リンクと、そのホスト名
+/// - **infobox**: `.s3v9rd` のうち `ラベル: 値` 形式のもの
+/// - **related_topics**: ブロック内の `/search?q=...` リンク
+///
+/// # 重要な注意点:
+/// `parse_data` と同様に、GoogleのHTML構造の変更で使えなくなる可能性があります。
+fn parse_answer(document: &Html) -> Option<Answer> {
+    let block_selector = Selector::parse("div.xpd").unwrap();
+    let short_answer_selector = Selector::parse(".iBp4i").unwrap();
+    let heading_selector = Selector::parse(".deIvCb").unwrap();
+    let text_selector = Selector::parse(".s3v9rd").unwrap();
+    let link_selector = Selector::parse(r#"a[href^="/url?q="]"#).unwrap();
+    let link_title_selector = Selector::parse("h3, .vvjwJb").unwrap();
+    let topic_selector = Selector::parse(r#"a[href^="/search?"]"#).unwrap();
+    let image_selector = Selector::parse(r#"img[src^="http"]"#).unwrap();
+
+    let block = document.select(&block_selector).find(|b| {
+        b.select(&short_answer_selector).next().is_some()
+            || b.select(&heading_selector).next().is_some()
+    })?;
+
+    let mut abstract_text = block
+        .select(&short_answer_selector)
+        .map(search::element_text)
+        .find(|t| !t.is_empty())
+        .unwrap_or_default();
+    let mut infobox = Vec::new();
+    let mut longest = String::new();
+    for text in block.select(&text_selector).map(search::element_text) {
+        // `生年月日: 1970年1月1日` のような短いラベルの行はインフォボックスの項目とする
+        match text.split_once(':') {
+            Some((label, value))
+                if !label.trim().is_empty()
+                    && label.chars().count() <= 30
+                    && !label.contains(['.', '。'])
+                    && !value.trim().is_empty()
+                    && !value.starts_with("//") =>
+            {
+                infobox.push((label.trim().to_string(), value.trim().to_string()));
+            }
+            _ if text.len() > longest.len() => longest = text,
+            _ => {}
+        }
+    }
+    if abstract_text.is_empty() {
+        abstract_text = longest;
+    }
+    if abstract_text.is_empty() && infobox.is_empty() {
+        return None;
+    }
+
+    let link = block.select(&link_selector).next();
+    let url = link
+        .and_then(|a| redirect_target(a.value().attr("href").unwrap_or("")))
+        .unwrap_or_default();
+    let source = search::url_host(&url).unwrap_or_default();
+    let title = block
+        .select(&heading_selector)
+        .next()
+        .or_else(|| link.and_then(|a| a.select(&link_title_selector).next()))
+        .map(search::element_text)
+        .unwrap_or_default();
+    let related_topics = block
+        .select(&topic_selector)
+        .filter_map(|a| {
+            let text = search::element_text(a);
+            let href = a.value().attr("href")?;
+            (!text.is_empty()).then(|| RelatedTopic {
+                text,
+                url: format!("https://www.google.com{}", href),
+            })
+        })
+        .collect();
+    Some(Answer {
+        title,
+        abstract_text,
+        source,
+        url,
+        image: block
+            .select(&image_selector)
+            .next()
+            .and_then(|img| img.value().attr("src"))
+            .map(str::to_string),
+        infobox,
+        related_topics,
+    })
+}
@@ -0,0 +1,68 @@
+// src/search/local.rs
+
+//! 閲覧したページのローカル全文検索インデックス ([`crate::index`]) を検索するモジュール
+//!
+//! ネットワークに接続せずに、以前に閲覧したページを検索できます。
+//! `SearchData::description` には本文の抜粋を、`SearchData::metadata` の `fetched_at` には
+//! ページを取得した日時を格納します。
+//!
+//! cargoの `index` フィーチャーを無効にしてビルドした場合は、常にエラーを返します。
+
+#[cfg(feature = "index")]
+use crate::index;
+use crate::{SearchData, SearchOptions};
+
+/// 1ページあたりの取得件数
+#[cfg(feature = "index")]
+const PAGE_SIZE: usize = 20;
+
+/// ローカルのインデックスを検索します。
+///
+/// # 引数
+/// - `query`: 検索クエリ文字列。
+/// - `options`: 検索オプション (ページ番号)。
+///
+/// # 戻り値
+/// `Result<Vec<SearchData>, String>`:
+/// - 成功した場合: 検索結果のリスト (`Vec<SearchData>`)。
+/// - 失敗した場合: エラーメッセージ文字列。
+#[cfg(feature = "index")]
+pub async fn search_local(
+    query: String,
+    options: &SearchOptions,
+) -> Result<Vec<SearchData>, String> {
+    let offset = offset(options);
+    tokio::task::spawn_blocking(move || index::search(&query, offset, PAGE_SIZE))
+        .await
+        .map_err(|e| format!("Local index search failed: {}", e))?
+}
+
+/// ローカルのインデックスを同期で検索します。
+#[cfg(feature = "index")]
+pub fn search_local_sync(
+    query: String,
+    options: &SearchOptions,
+) -> Result<Vec<SearchData>, String> {
+    index::search(&query, offset(options), PAGE_SIZE)
+}
+
+#[cfg(not(feature = "index"))]
+pub async fn search_local(
+    query: String,
+    options: &SearchOptions,
+) -> Result<Vec<SearchData>, String> {
+    search_local_sync(query, options)
+}
+
+#[cfg(not(feature = "index"))]
+pub fn search_local_sync(
+    _query: String,
+    _options: &SearchOptions,
+) -> Result<Vec<SearchData>, String> {
+    Err("The local engine requires the `index` feature".to_string())
+}
+
+#[cfg(feature = "index")]
+fn offset(options: &SearchOptions) -> usize {
+    options.page.saturating_sub(1) * PAGE_SIZE
+}
@@ -0,0 +1,726 @@
+// src/search/query.rs
+
+//! 検索演算子 (`site:`、`-除外`、`"フレーズ"`、`filetype:`、`intitle:`、`before:`/`after:`、`OR`) の解析と
+//! エンジンごとの変換を行うモジュール
+//!
+//! [`Query::parse`] でクエリ文字列を型付きの構文木に変換し、[`Query::translate`] でエンジンが対応している
+//! 演算子だけをエンジンのクエリに残します。対応していない演算子は検索結果の絞り込み
+//! ([`Translation::apply`]) で代替します。
+//!
+//! 絞り込みに使える情報は検索結果のタイトル・URL・概要・`metadata` だけのため、語句やフレーズ、日付の
+//! 条件は正確に判定できない場合があります。その場合は [`Translation::warnings`] に警告を格納します。
+//! `site:` と `filetype:` はURL、`intitle:` はタイトルで判定できるため、警告は出しません。
+//!
+//! ```
+//! use www_search::EngineType;
+//! use www_search::search::query::Query;
+//!
+//! let query = Query::parse(r#""borrow checker" site:doc.rust-lang.org -nightly"#);
+//! let translation = query.translate(EngineType::Crates);
+//! assert_eq!(translation.query, "borrow checker");
+//! assert_eq!(translation.filters.len(), 3);
+//! ```
+
+use std::cmp::Ordering;
+use std::fmt;
+
+use crate::{EngineType, SearchData};
+
+/// 解析したクエリ。`clauses` はすべてを満たす (AND) 条件です。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Query {
+    pub clauses: Vec<Clause>,
+}
+
+/// クエリの条件
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Clause {
+    /// 単独の条件
+    Term(Term),
+    /// `a OR b` のように、いずれかを満たす条件
+    Or(Vec<Term>),
+}
+
+/// 演算子と、除外 (`-`) の指定
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Term {
+    pub negated: bool,
+    pub operator: Operator,
+}
+
+/// 検索演算子
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Operator {
+    /// 通常の語句
+    Word(String),
+    /// `"exact phrase"`
+    Phrase(String),
+    /// `site:example.com` (パスを含めることも可能: `site:example.com/docs`)
+    Site(String),
+    /// `filetype:pdf`
+    FileType(String),
+    /// `intitle:rust`
+    InTitle(String),
+    /// `before:2024-01-01`
+    Before(Date),
+    /// `after:2024-01-01`
+    After(Date),
+}
+
+/// `before:`/`after:` の日付。年のみ、年月のみの指定も可能です。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Date {
+    pub year: u16,
+    pub month: Option<u8>,
+    pub day: Option<u8>,
+}
+
+/// エンジンが対応しているかを判定する演算子の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OperatorKind {
+    Phrase,
+    Exclude,
+    Site,
+    FileType,
+    InTitle,
+    Before,
+    After,
+    Or,
+}
+
+impl OperatorKind {
+    /// 警告メッセージで使用する名前
+    pub fn name(self) -> &'static str {
+        match self {
+            OperatorKind::Phrase => "\"phrase\"",
+            OperatorKind::Exclude => "-exclude",
+            OperatorKind::Site => "site:",
+            OperatorKind::FileType => "filetype:",
+            OperatorKind::InTitle => "intitle:",
+            OperatorKind::Before => "before:",
+            OperatorKind::After => "after:",
+            OperatorKind::Or => "OR",
+        }
+    }
+}
+
+/// エンジンが検索演算子に対応しているかを返します。
+///
+/// 通常の語句はすべてのエンジンが対応しています。
+pub fn supports(engine: EngineType, kind: OperatorKind) -> bool {
+    use OperatorKind::*;
+    match engine {
+        EngineType::Google => true,
+        EngineType::DuckDuckGo | EngineType::Brave | EngineType::Goo => {
+            matches!(kind, Phrase | Exclude | Site | FileType | InTitle | Or)
+        }
+        EngineType::Searxng | EngineType::YahooJapan => {
+            matches!(kind, Phrase | Exclude | Site | Or)
+        }
+        EngineType::Wikipedia => matches!(kind, Phrase | Exclude | InTitle | Or),
+        EngineType::Github => matches!(kind, Phrase),
+        EngineType::Local => matches!(kind, Phrase | Exclude | Or),
+        EngineType::StackExchange
+        | EngineType::Crates
+        | EngineType::DocsRs
+        | EngineType::Arxiv
+        | EngineType::Crossref => false,
+    }
+}
+
+/// エンジン向けに変換したクエリ
+///
+/// - `query`: エンジンに送るクエリ文字列
+/// - `filters`: エンジンが対応していないため、検索結果の絞り込みで代替する条件
+/// - `warnings`: 絞り込みが正確でない可能性がある条件についての警告
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Translation {
+    pub query: String,
+    pub filters: Vec<Clause>,
+    pub warnings: Vec<String>,
+}
+
+impl Translation {
+    /// `filters` の条件で検索結果を絞り込みます。
+    ///
+    /// 結果から判定できない条件 (日付のない結果に対する `before:` など) は満たしているものとして扱います。
+    pub fn apply(&self, results: Vec<SearchData>) -> Vec<SearchData> {
+        if self.filters.is_empty() {
+            return results;
+        }
+        results
+            .into_iter()
+            .filter(|r| self.filters.iter().all(|c| c.matches(r) != Some(false)))
+            .collect()
+    }
+}
+
+impl Query {
+    /// クエリ文字列を解析します。
+    ///
+    /// 演算子として解釈できない語 (`lang:ja` や不正な日付の `before:` など) は通常の語句として扱います。
+    pub fn parse(input: &str) -> Query {
+        let mut clauses: Vec<Clause> = Vec::new();
+        let mut pending_or = false;
+        for token in tokenize(input) {
+            let Token::Term(term) = token else {
+                if clauses.is_empty() {
+                    // 先頭の `OR` は語句として扱う
+                    clauses.push(Clause::Term(Term::word("OR")));
+                } else {
+                    pending_or = true;
+                }
+                continue;
+            };
+            if std::mem::take(&mut pending_or) {
+                match clauses.pop() {
+                    Some(Clause::Term(prev)) => clauses.push(Clause::Or(vec![prev, term])),
+                    Some(Clause::Or(mut terms)) => {
+                        terms.push(term);
+                        clauses.push(Clause::Or(terms));
+                    }
+                    None => clauses.push(Clause::Term(term)),
+                }
+            } else {
+                clauses.push(Clause::Term(term));
+            }
+        }
+        if pending_or {
+            clauses.push(Clause::Term(Term::word("OR")));
+        }
+        Query { clauses }
+    }
+
+    /// エンジンが対応している演算子だけを残したクエリと、絞り込みで代替する条件を返します。
+    ///
+    /// 対応していないフレーズや `intitle:` は、検索の精度を保つため語句としてエンジンのクエリに残します。
+    /// 絞り込みの結果、検索語がなくなる場合は絞り込みで代替する条件の語句を検索語にします。
+    pub fn translate(&self, engine: EngineType) -> Translation {
+        let mut translation = Translation::default();
+        let mut parts: Vec<String> = Vec::new();
+        for clause in &self.clauses {
+            if clause.kinds().iter().all(|&kind| supports(engine, kind)) {
+                parts.push(clause.to_string());
+                continue;
+            }
+            if let Clause::Term(Term {
+                negated: false,
+                operator: Operator::Phrase(text) | Operator::InTitle(text),
+            }) = clause
+            {
+                parts.push(text.clone());
+            }
+            if clause.is_lossy() {
+                let unsupported: Vec<&str> = clause
+                    .kinds()
+                    .into_iter()
+                    .filter(|&kind| !supports(engine, kind))
+                    .map(OperatorKind::name)
+                    .collect();
+                translation.warnings.push(format!(
+                    "{} does not support {}; filtering results by `{}` may be inaccurate",
+                    engine,
+                    unsupported.join(", "),
+                    clause
+                ));
+            }
+            translation.filters.push(clause.clone());
+        }
+        if parts.is_empty() {
+            // 検索語がなくなる場合 (`OR` に対応していないエンジンでの `a OR b` など) は、
+            // 絞り込みで代替する条件の語句で検索する
+            parts = translation
+                .filters
+                .iter()
+                .flat_map(|clause| match clause {
+                    Clause::Term(term) => std::slice::from_ref(term),
+                    Clause::Or(terms) => terms.as_slice(),
+                })
+                .filter_map(|term| match &term.operator {
+                    Operator::Word(text) | Operator::Phrase(text) if !term.negated => {
+                        Some(text.clone())
+                    }
+                    _ => None,
+                })
+                .collect();
+        }
+        translation.query = parts.join(" ");
+        translation
+    }
+}
+
+impl fmt::Display for Query {
+    /// Google形式の構文でクエリを表示します。
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parts: Vec<String> = self.clauses.iter().map(Clause::to_string).collect();
+        f.write_str(&parts.join(" "))
+    }
+}
+
+impl Clause {
+    /// 条件で使われている演算子の種類
+    fn kinds(&self) -> Vec<OperatorKind> {
+        match self {
+            Clause::Term(term) => term.kinds(),
+            Clause::Or(terms) => {
+                let mut kinds = vec![OperatorKind::Or];
+                for kind in terms.iter().flat_map(Term::kinds) {
+                    if !kinds.contains(&kind) {
+                        kinds.push(kind);
+                    }
+                }
+                kinds
+            }
+        }
+    }
+
+    /// 絞り込みで代替した場合に、正確に判定できない可能性があるか
+    fn is_lossy(&self) -> bool {
+        match self {
+            Clause::Term(term) => term.is_lossy(),
+            Clause::Or(terms) => terms.iter().any(Term::is_lossy),
+        }
+    }
+
+    /// 検索結果が条件を満たすかを返します。判定できない場合は `None` を返します。
+    pub fn matches(&self, result: &SearchData) -> Option<bool> {
+        match self {
+            Clause::Term(term) => term.matches(result),
+            Clause::Or(terms) => {
+                let mut unknown = false;
+                for term in terms {
+                    match term.matches(result) {
+                        Some(true) => return Some(true),
+                        Some(false) => {}
+                        None => unknown = true,
+                    }
+                }
+                if unknown { None } else { Some(false) }
+            }
+        }
+    }
+}
+
+impl fmt::Display for Clause {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Clause::Term(term) => write!(f, "{}", term),
+            Clause::Or(terms) => {
+                let parts: Vec<String> = terms.iter().map(Term::to_string).collect();
+                f.write_str(&parts.join(" OR "))
+            }
+        }
+    }
+}
+
+impl Term {
+    fn word(text: &str) -> Term {
+        Term {
+            negated: false,
+            operator: Operator::Word(text.to_string()),
+        }
+    }
+
+    fn kinds(&self) -> Vec<OperatorKind> {
+        let mut kinds = Vec::new();
+        if self.negated {
+            kinds.push(OperatorKind::Exclude);
+        }
+        kinds.extend(match self.operator {
+            Operator::Word(_) => None,
+            Operator::Phrase(_) => Some(OperatorKind::Phrase),
+            Operator::Site(_) => Some(OperatorKind::Site),
+            Operator::FileType(_) => Some(OperatorKind::FileType),
+            Operator::InTitle(_) => Some(OperatorKind::InTitle),
+            Operator::Before(_) => Some(OperatorKind::Before),
+            Operator::After(_) => Some(OperatorKind::After),
+        });
+        kinds
+    }
+
+    /// 語句・フレーズは概要に含まれない場合があり、日付は結果に含まれない場合がある
+    fn is_lossy(&self) -> bool {
+        matches!(
+            self.operator,
+            Operator::Word(_) | Operator::Phrase(_) | Operator::Before(_) | Operator::After(_)
+        )
+    }
+
+    fn matches(&self, result: &SearchData) -> Option<bool> {
+        let matched = match &self.operator {
+            Operator::Word(text) | Operator::Phrase(text) => {
+                let text = text.to_lowercase();
+                [&result.title, &result.description, &result.url]
+                    .iter()
+                    .any(|field| field.to_lowercase().contains(&text))
+            }
+            Operator::InTitle(text) => result.title.to_lowercase().contains(&text.to_lowercase()),
+            Operator::Site(site) => matches_site(page_url(result), site),
+            Operator::FileType(ext) => url::Url::parse(&result.url).is_ok_and(|u| {
+                u.path()
+                    .to_lowercase()
+                    .ends_with(&format!(".{}", ext.to_lowercase()))
+            }),
+            Operator::Before(date) => compare_date(result, *date)? == Ordering::Less,
+            Operator::After(date) => compare_date(result, *date)? != Ordering::Less,
+        };
+        Some(matched != self.negated)
+    }
+}
+
+impl fmt::Display for Term {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.negated {
+            f.write_str("-")?;
+        }
+        match &self.operator {
+            Operator::Word(text) => f.write_str(text),
+            Operator::Phrase(text) => write!(f, "\"{}\"", text),
+            Operator::Site(site) => write!(f, "site:{}", site),
+            Operator::FileType(ext) => write!(f, "filetype:{}", ext),
+            Operator::InTitle(text) if text.contains(char::is_whitespace) => {
+                write!(f, "intitle:\"{}\"", text)
+            }
+            Operator::InTitle(text) => write!(f, "intitle:{}", text),
+            Operator::Before(date) => write!(f, "before:{}", date),
+            Operator::After(date) => write!(f, "after:{}", date),
+        }
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}", self.year)?;
+        if let Some(month) = self.month {
+            write!(f, "-{:02}", month)?;
+            if let Some(day) = self.day {
+                write!(f, "-{:02}", day)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::str::FromStr for Date {
+    type Err = String;
+
+    /// `2024`、`2024-01`、`2024-01-02` (区切りは `/` も可) の形式の日付を解析します。
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid date: {}", s);
+        let mut parts = s.split(['-', '/']);
+        let year = parts
+            .next()
+            .filter(|y| y.len() == 4)
+            .and_then(|y| y.parse().ok())
+            .ok_or_else(invalid)?;
+        let mut field = |max: u8| -> Result<Option<u8>, String> {
+            match parts.next() {
+                None => Ok(None),
+                Some(p) => p
+                    .parse()
+                    .ok()
+                    .filter(|n| (1..=max).contains(n))
+                    .map(Some)
+                    .ok_or_else(invalid),
+            }
+        };
+        let month = field(12)?;
+        let day = field(31)?;
+        if parts.next().is_some() {
+            return Err(invalid());
+        }
+        Ok(Date { year, month, day })
+    }
+}
+
+/// クエリの字句
+#[derive(Debug, PartialEq, Eq)]
+enum Token {
+    Term(Term),
+    Or,
+}
+
+/// クエリを字句に分割する。引用符で囲んだ部分は空白を含めて1つの値として扱う
+fn tokenize(input: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        // 引用符の外の空白までを1語として読む
+        let mut raw = String::new();
+        let mut quoted = String::new();
+        let mut in_quote = false;
+        let mut has_quote = false;
+        while let Some(&c) = chars.peek() {
+            if !in_quote && c.is_whitespace() {
+                break;
+            }
+            chars.next();
+            if c == '"' {
+                in_quote = !in_quote;
+                has_quote = true;
+            } else if in_quote {
+                quoted.push(c);
+            } else {
+                raw.push(c);
+            }
+        }
+        if !has_quote && (raw == "OR" || raw == "|") {
+            tokens.push(Token::Or);
+            continue;
+        }
+        let (negated, raw) = match raw.strip_prefix('-') {
+            Some(rest) if !rest.is_empty() || has_quote => (true, rest.to_string()),
+            _ => (false, raw),
+        };
+        if let Some(operator) = operator(&raw, &quoted, has_quote) {
+            tokens.push(Token::Term(Term { negated, operator }));
+        }
+    }
+    tokens
+}
+
+/// 1語を演算子に変換する。`raw` は引用符の外、`quoted` は引用符の中の文字列
+fn operator(raw: &str, quoted: &str, has_quote: bool) -> Option<Operator> {
+    if raw.is_empty() {
+        let quoted = quoted.split_whitespace().collect::<Vec<_>>().join(" ");
+        return (!quoted.is_empty()).then_some(Operator::Phrase(quoted));
+    }
+    if let Some((key, value)) = raw.split_once(':') {
+        let value = if has_quote {
+            format!("{}{}", value, quoted)
+        } else {
+            value.to_string()
+        };
+        let value = value.split_whitespace().collect::<Vec<_>>().join(" ");
+        if !value.is_empty() {
+            let operator = match key.to_lowercase().as_str() {
+                "site" => Some(Operator::Site(value.to_lowercase())),
+                "filetype" | "ext" => Some(Operator::FileType(
+                    value.trim_start_matches('.').to_lowercase(),
+                )),
+                "intitle" => Some(Operator::InTitle(value)),
+                "before" => value.parse().ok().map(Operator::Before),
+                "after" => value.parse().ok().map(Operator::After),
+                _ => None,
+            };
+            if operator.is_some() {
+                return operator;
+            }
+        }
+    }
+    Some(Operator::Word(if has_quote {
+        format!("{}\"{}\"", raw, quoted)
+    } else {
+        raw.to_string()
+    }))
+}
+
+/// 掲載ページのURL (画像検索では `source_page`)
+fn page_url(result: &SearchData) -> &str {
+    result.metadata.get("source_page").unwrap_or(&result.url)
+}
+
+/// URLが `site:` の値 (ドメイン、またはドメインとパス) に一致するか。サブドメインも一致とする
+fn matches_site(url: &str, site: &str) -> bool {
+    let Ok(url) = url::Url::parse(url) else {
+        return false;
+    };
+    let Some(host) = url.host_str() else {
+        return false;
+    };
+    let site = site
+        .trim_start_matches("https://")
+        .trim_start_matches("http://");
+    let (domain, path) = match site.find('/') {
+        Some(i) => site.split_at(i),
+        None => (site, ""),
+    };
+    let host = host.to_lowercase();
+    (host == domain || host.ends_with(&format!(".{}", domain)))
+        && url.path().to_lowercase().starts_with(path)
+}
+
+/// 検索結果の日付 (`published_date` または `year`) と指定した日付を比較する
+///
+/// 指定した日付の精度 (年・年月・年月日) で比較し、結果の日付の精度が足りない場合は `None` を返す
+fn compare_date(result: &SearchData, date: Date) -> Option<Ordering> {
+    let found = result
+        .metadata
+        .get("published_date")
+        .or_else(|| result.metadata.get("year"))?;
+    let target = date.to_string();
+    if !found.is_ascii() {
+        return None;
+    }
+    let found = &found[..found.len().min(target.len())];
+    if found.len() < 4 || !found[..4].bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    match found.cmp(&target[..found.len()]) {
+        Ordering::Equal if found.len() < target.len() => None,
+        ordering => Some(ordering),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn term(negated: bool, operator: Operator) -> Clause {
+        Clause::Term(Term { negated, operator })
+    }
+
+    fn word(text: &str) -> Term {
+        Term::word(text)
+    }
+
+    fn result_with(key: &str, value: &str) -> SearchData {
+        let mut result = SearchData::default();
+        result.metadata.insert(key.to_string(), value.to_string());
+        result
+    }
+
+    #[test]
+    fn parses_phrases_and_negated_phrases() {
+        let query = Query::parse(r#""exact  phrase" -"not this" rust"#);
+        assert_eq!(
+            query.clauses,
+            vec![
+                term(false, Operator::Phrase("exact phrase".to_string())),
+                term(true, Operator::Phrase("not this".to_string())),
+                Clause::Term(word("rust")),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_quoted_intitle() {
+        let query = Query::parse(r#"intitle:"borrow checker" site:Docs.rs"#);
+        assert_eq!(
+            query.clauses,
+            vec![
+                term(false, Operator::InTitle("borrow checker".to_string())),
+                term(false, Operator::Site("docs.rs".to_string())),
+            ]
+        );
+        assert_eq!(
+            query.to_string(),
+            r#"intitle:"borrow checker" site:docs.rs"#
+        );
+    }
+
+    #[test]
+    fn treats_leading_and_trailing_or_as_words() {
+        let query = Query::parse("OR rust OR");
+        assert_eq!(
+            query.clauses,
+            vec![
+                Clause::Term(word("OR")),
+                Clause::Term(word("rust")),
+                Clause::Term(word("OR")),
+            ]
+        );
+    }
+
+    #[test]
+    fn groups_or_terms() {
+        let query = Query::parse("rust OR go | zig tokio");
+        assert_eq!(
+            query.clauses,
+            vec![
+                Clause::Or(vec![word("rust"), word("go"), word("zig")]),
+                Clause::Term(word("tokio")),
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_invalid_operators_as_words() {
+        let query = Query::parse("lang:ja before:2024-13");
+        assert_eq!(
+            query.clauses,
+            vec![
+                Clause::Term(word("lang:ja")),
+                Clause::Term(word("before:2024-13")),
+            ]
+        );
+    }
+
+    #[test]
+    fn translates_or_for_engine_without_or() {
+        let translation = Query::parse("rust OR go").translate(EngineType::Github);
+        assert_eq!(translation.query, "rust go");
+        assert_eq!(
+            translation.filters,
+            vec![Clause::Or(vec![word("rust"), word("go")])]
+        );
+        assert_eq!(translation.warnings.len(), 1);
+    }
+
+    #[test]
+    fn keeps_supported_operators_in_query() {
+        let translation = Query::parse(r#""a b" OR c -d"#).translate(EngineType::DuckDuckGo);
+        assert_eq!(translation.query, r#""a b" OR c -d"#);
+        assert!(translation.filters.is_empty());
+        assert!(translation.warnings.is_empty());
+    }
+
+    #[test]
+    fn filters_unsupported_site_without_warning() {
+        let translation = Query::parse("serde site:github.com").translate(EngineType::Crates);
+        assert_eq!(translation.query, "serde");
+        assert!(translation.warnings.is_empty());
+        let results = vec![
+            SearchData {
+                url: "https://github.com/serde-rs/serde".to_string(),
+                ..Default::default()
+            },
+            SearchData {
+                url: "https://crates.io/crates/serde".to_string(),
+                ..Default::default()
+            },
+        ];
+        let filtered = translation.apply(results);
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered[0].url, "https://github.com/serde-rs/serde");
+    }
+
+    #[test]
+    fn compares_dates_at_the_requested_precision() {
+        let month: Date = "2024-03".parse().unwrap();
+        let day: Date = "2024-03-01".parse().unwrap();
+        let result = result_with("published_date", "2024-03-15T10:00:00Z");
+        assert_eq!(compare_date(&result, month), Some(Ordering::Equal));
+        assert_eq!(compare_date(&result, day), Some(Ordering::Greater));
+        assert_eq!(
+            compare_date(&result_with("year", "2023"), month),
+            Some(Ordering::Less)
+        );
+        // 結果の日付が年だけの場合、同じ年の年月日とは比較できない
+        assert_eq!(compare_date(&result_with("year", "2024"), day), None);
+        assert_eq!(
+            compare_date(&result_with("published_date", "3 hours ago"), day),
+            None
+        );
+        assert_eq!(compare_date(&SearchData::default(), day), None);
+    }
+
+    #[test]
+    fn parses_dates() {
+        assert_eq!(
+            "2024/1/2".parse::<Date>(),
+            Ok(Date {
+                year: 2024,
+                month: Some(1),
+                day: Some(2),
+            })
+        );
+        assert!("24-01-02".parse::<Date>().is_err());
+        assert!("2024-01-32".parse::<Date>().is_err());
+        assert!("2024-01-02-03".parse::<Date>().is_err());
+    }
+}
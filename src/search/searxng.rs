@@ -0,0 +1,255 @@
+// src/search/searxng.rs
+
+//! SearXNGインスタンスを検索エンジンとして使用するモジュール
+//!
+//! 設定 (`[engines.searxng] base_url`) または環境変数 `SEARXNG_URL` で指定したインスタンスの
+//! `/search?format=json` を呼び出します。インスタンス側でJSON形式の出力が有効
+//! (`search.formats` に `json` を含む) である必要があります。
+//!
+//! `SearchData::metadata` には次の値を格納します。
+//! - `engines`: 結果を返した上流エンジン (カンマ区切り)
+//! - `score`: SearXNGのスコア
+//! - `published_date`: 公開日時
+//! - `category`: カテゴリ
+//! - `source`, `thumbnail`: ニュース検索 (`categories=news`) での配信元のホスト名とサムネイル画像のURL
+//! - 画像検索 (`categories=images`) では `url` に画像本体のURLを格納し、`thumbnail`, `width`, `height`,
+//!   `source_page`, `source` を格納します。
+//! - `duration`, `publisher`, `channel`, `thumbnail`: 動画検索 (`categories=videos`) での再生時間、
+//!   配信サイトのホスト名、投稿者、サムネイル画像のURL
+
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+
+use crate::search;
+use crate::{EngineType, SearchData, SearchOptions, SortOrder, Vertical, config};
+
+/// SearXNGインスタンスで検索を実行します。
+///
+/// # 引数
+/// - `query`: 検索クエリ文字列。
+/// - `options`: 検索オプション。ページ番号・言語・期間・カテゴリをSearXNGに転送します。
+///
+/// # 戻り値
+/// `Result<Vec<SearchData>, String>`:
+/// - 成功した場合: 検索結果のリスト (`Vec<SearchData>`)。
+/// - 失敗した場合: エラーメッセージ文字列。
+pub async fn search_searxng(
+    query: String,
+    options: &SearchOptions,
+) -> Result<Vec<SearchData>, String> {
+    let client = search::client(Some(EngineType::Searxng), None)?;
+    let request = client
+        .get(search_url()?)
+        .query(&params(&query, options))
+        .header("Accept", "application/json");
+    parse_data(&search::fetch_text(request, "SearXNG").await?, options)
+}
+
+/// SearXNGインスタンスで検索を同期で実行します。
+pub fn search_searxng_sync(
+    query: String,
+    options: &SearchOptions,
+) -> Result<Vec<SearchData>, String> {
+    let client = search::blocking_client(Some(EngineType::Searxng), None)?;
+    let request = client
+        .get(search_url()?)
+        .query(&params(&query, options))
+        .header("Accept", "application/json");
+    parse_data(&search::fetch_text_sync(request, "SearXNG")?, options)
+}
+
+/// 設定されたインスタンスの検索エンドポイントのURL
+fn search_url() -> Result<String, String> {
+    let base_url = config::current()
+        .engines
+        .searxng
+        .base_url
+        .clone()
+        .filter(|u| !u.is_empty())
+        .ok_or(
+            "SearXNG instance URL is not configured ([engines.searxng] base_url or SEARXNG_URL)",
+        )?;
+    Ok(format!("{}/search", base_url.trim_end_matches('/')))
+}
+
+/// SearXNGのクエリパラメータ。カテゴリの指定がなければ設定のデフォルトカテゴリを使用し、
+/// ニュース検索では `news`、画像検索では `images` カテゴリを使用します。
+fn params(query: &str, options: &SearchOptions) -> Vec<(&'static str, String)> {
+    let mut params = vec![
+        ("q", query.to_string()),
+        ("format", "json".to_string()),
+        ("pageno", options.page.max(1).to_string()),
+    ];
+    if let Some(language) = &options.language {
+        params.push(("language", language.clone()));
+    }
+    if let Some(time_range) = options.time_range {
+        params.push(("time_range", time_range.name().to_string()));
+    }
+    let categories = if options.vertical != Vertical::Web {
+        vec![options.vertical.name().to_string()]
+    } else if options.categories.is_empty() {
+        config::current().engines.searxng.categories.clone()
+    } else {
+        options.categories.clone()
+    };
+    if !categories.is_empty() {
+        params.push(("categories", categories.join(",")));
+    }
+    params
+}
+
+#[derive(Debug, Deserialize)]
+struct Response {
+    #[serde(default)]
+    results: Vec<ResponseResult>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ResponseResult {
+    url: String,
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    content: Option<String>,
+    #[serde(default)]
+    engine: Option<String>,
+    #[serde(default)]
+    engines: Vec<String>,
+    #[serde(default)]
+    score: Option<f64>,
+    #[serde(default)]
+    category: Option<String>,
+    #[serde(default, rename = "publishedDate")]
+    published_date: Option<String>,
+    #[serde(default)]
+    thumbnail: Option<String>,
+    #[serde(default)]
+    img_src: Option<String>,
+    #[serde(default)]
+    thumbnail_src: Option<String>,
+    #[serde(default)]
+    resolution: Option<String>,
+    /// 動画の再生時間 (エンジンにより `12:34` 形式の文字列または秒数)
+    #[serde(default)]
+    length: Option<serde_json::Value>,
+    #[serde(default)]
+    author: Option<String>,
+}
+
+/// SearXNGの `format=json` のレスポンスを `SearchData` のベクターに変換します。
+///
+/// ニュース・動画検索で新しい順が指定された場合は `publishedDate` の降順に並べ替えます
+/// (日時を解釈できない結果は末尾に置きます)。画像検索では画像本体のURL (`img_src`) がない結果を除きます。
+fn parse_data(json: &str, options: &SearchOptions) -> Result<Vec<SearchData>, String> {
+    let mut response: Response = serde_json::from_str(json)
+        .map_err(|e| format!("Failed to parse SearXNG response: {}", e))?;
+    let news = options.vertical == Vertical::News;
+    let images = options.vertical == Vertical::Images;
+    let videos = options.vertical == Vertical::Videos;
+    if (news || videos) && options.sort == SortOrder::Recency {
+        response.results.sort_by_cached_key(|r| {
+            std::cmp::Reverse(
+                r.published_date
+                    .as_deref()
+                    .and_then(search::parse_unix_time),
+            )
+        });
+    }
+    let results = response
+        .results
+        .into_iter()
+        .filter(|r| !r.url.is_empty())
+        .filter_map(|r| {
+            let host = search::url_host(&r.url);
+            let mut metadata = BTreeMap::new();
+            let engines = if r.engines.is_empty() {
+                r.engine.into_iter().collect()
+            } else {
+                r.engines
+            };
+            if !engines.is_empty() {
+                metadata.insert("engines".to_string(), engines.join(", "));
+            }
+            if let Some(score) = r.score {
+                metadata.insert("score".to_string(), format!("{:.3}", score));
+            }
+            if let Some(date) = r.published_date.filter(|d| !d.is_empty()) {
+                metadata.insert("published_date".to_string(), date);
+            }
+            if let Some(category) = r.category.filter(|c| !c.is_empty()) {
+                metadata.insert("category".to_string(), category);
+            }
+            if images {
+                let img_src = r.img_src.filter(|i| i.starts_with("http"))?;
+                if let Some(thumbnail) = r.thumbnail_src.clone().filter(|t| t.starts_with("http")) {
+                    metadata.insert("thumbnail".to_string(), thumbnail);
+                }
+                // `resolution` は `1920 x 1080` や `1920x1080` の形式
+                if let Some((width, height)) =
+                    r.resolution.as_deref().and_then(|res| res.split_once('x'))
+                {
+                    metadata.insert("width".to_string(), width.trim().to_string());
+                    metadata.insert("height".to_string(), height.trim().to_string());
+                }
+                if let Some(host) = host {
+                    metadata.insert("source".to_string(), host);
+                }
+                metadata.insert("source_page".to_string(), r.url);
+                return Some(SearchData {
+                    title: r.title.filter(|t| !t.is_empty()).unwrap_or_default(),
+                    url: img_src,
+                    description: r.content.unwrap_or_default(),
+                    metadata,
+                });
+            }
+            if videos {
+                if let Some(duration) = r.length.as_ref().and_then(format_length) {
+                    metadata.insert("duration".to_string(), duration);
+                }
+                if let Some(author) = r.author.filter(|a| !a.is_empty()) {
+                    metadata.insert("channel".to_string(), author);
+                }
+                if let Some(host) = &host {
+                    metadata.insert("publisher".to_string(), host.clone());
+                }
+            }
+            if news || videos {
+                if news && let Some(host) = host {
+                    metadata.insert("source".to_string(), host);
+                }
+                if let Some(thumbnail) = r.thumbnail.or(r.img_src).filter(|t| t.starts_with("http"))
+                {
+                    metadata.insert("thumbnail".to_string(), thumbnail);
+                }
+            }
+            Some(SearchData {
+                title: r
+                    .title
+                    .filter(|t| !t.is_empty())
+                    .unwrap_or_else(|| r.url.clone()),
+                url: r.url,
+                description: r.content.unwrap_or_default(),
+                metadata,
+            })
+        })
+        .collect();
+    Ok(results)
+}
+
+/// 動画の再生時間を `12:34` (1時間以上は `1:02:03`) 形式の文字列にする
+fn format_length(length: &serde_json::Value) -> Option<String> {
+    match length {
+        serde_json::Value::String(s) if !s.trim().is_empty() => Some(s.trim().to_string()),
+        serde_json::Value::Number(n) => {
+            let secs = n.as_f64()?.round() as u64;
+            Some(if secs >= 3600 {
+                format!("{}:{:02}:{:02}", secs / 3600, secs % 3600 / 60, secs % 60)
+            } else {
+                format!("{}:{:02}", secs / 60, secs % 60)
+            })
+        }
+        _ => None,
+    }
+}
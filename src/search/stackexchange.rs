@@ -0,0 +1,180 @@
+// src/search/stackexchange.rs
+
+//! Stack Exchange API (`api.stackexchange.com/2.3/search/advanced`) のモジュール
+//!
+//! 検索するサイトは `SearchOptions::categories` の先頭の値 (なければ `[engines.stackexchange]
+//! categories` の先頭、それもなければ `stackoverflow`) で指定します (例: `superuser`, `unix`)。
+//! 設定 (`[engines.stackexchange] api_key`) でアプリケーションキーを指定すると、
+//! 1日あたりのリクエスト上限が緩和されます。
+//!
+//! `SearchData::metadata` には次の値を格納します。
+//! - `score`: 質問のスコア (投票数)
+//! - `answers`: 回答数
+//! - `answered`: 受け入れられた (または高評価の) 回答があるか (`true` / `false`)
+//! - `accepted`: 受け入れられた回答があるか (`true` / `false`)
+//! - `views`: 閲覧数
+//! - `tags`: タグ (カンマ区切り)
+//! - `created_at`: 投稿日時 (UNIX時間)
+
+use std::collections::BTreeMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Deserialize;
+
+use crate::search;
+use crate::{EngineType, SearchData, SearchOptions, TimeRange, config};
+
+const API_URL: &str = "https://api.stackexchange.com/2.3/search/advanced";
+
+/// 1ページあたりの取得件数
+const PAGE_SIZE: usize = 20;
+
+/// 説明として使用する質問本文の最大文字数
+const BODY_EXCERPT_CHARS: usize = 300;
+
+/// Stack Exchangeで質問を検索します。
+///
+/// # 引数
+/// - `query`: 検索クエリ文字列。
+/// - `options`: 検索オプション (ページ番号、期間、サイト名のカテゴリ)。
+///
+/// # 戻り値
+/// `Result<Vec<SearchData>, String>`:
+/// - 成功した場合: 検索結果のリスト (`Vec<SearchData>`)。
+/// - 失敗した場合: エラーメッセージ文字列。
+pub async fn search_stackexchange(
+    query: String,
+    options: &SearchOptions,
+) -> Result<Vec<SearchData>, String> {
+    let client = search::client(
+        Some(EngineType::StackExchange),
+        Some(search::API_USER_AGENT),
+    )?;
+    let request = client.get(API_URL).query(&params(&query, options));
+    parse_data(&search::fetch_text(request, "Stack Exchange").await?)
+}
+
+/// Stack Exchangeで質問を同期で検索します。
+pub fn search_stackexchange_sync(
+    query: String,
+    options: &SearchOptions,
+) -> Result<Vec<SearchData>, String> {
+    let client = search::blocking_client(
+        Some(EngineType::StackExchange),
+        Some(search::API_USER_AGENT),
+    )?;
+    let request = client.get(API_URL).query(&params(&query, options));
+    parse_data(&search::fetch_text_sync(request, "Stack Exchange")?)
+}
+
+/// クエリパラメータ。期間は `fromdate` (UNIX時間) で指定し、本文を説明に使うため `withbody` フィルタを使用します。
+fn params(query: &str, options: &SearchOptions) -> Vec<(&'static str, String)> {
+    let config = config::current();
+    let engine_config = &config.engines.stackexchange;
+    let site = options
+        .categories
+        .first()
+        .or(engine_config.categories.first())
+        .cloned()
+        .unwrap_or_else(|| "stackoverflow".to_string());
+    let mut params = vec![
+        ("q", query.to_string()),
+        ("site", site),
+        ("order", "desc".to_string()),
+        ("sort", "relevance".to_string()),
+        ("filter", "withbody".to_string()),
+        ("page", options.page.max(1).to_string()),
+        ("pagesize", PAGE_SIZE.to_string()),
+    ];
+    if let Some(time_range) = options.time_range {
+        let days = match time_range {
+            TimeRange::Day => 1,
+            TimeRange::Week => 7,
+            TimeRange::Month => 30,
+            TimeRange::Year => 365,
+        };
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        params.push(("fromdate", now.saturating_sub(days * 86400).to_string()));
+    }
+    if let Some(key) = engine_config.api_key.clone().filter(|k| !k.is_empty()) {
+        params.push(("key", key));
+    }
+    params
+}
+
+#[derive(Debug, Deserialize)]
+struct Response {
+    #[serde(default)]
+    items: Vec<Question>,
+    #[serde(default)]
+    error_message: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Question {
+    title: String,
+    link: String,
+    #[serde(default)]
+    body: String,
+    #[serde(default)]
+    score: i64,
+    #[serde(default)]
+    answer_count: u64,
+    #[serde(default)]
+    is_answered: bool,
+    #[serde(default)]
+    accepted_answer_id: Option<u64>,
+    #[serde(default)]
+    view_count: u64,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    creation_date: Option<u64>,
+}
+
+/// Stack Exchange APIのJSONレスポンスを `SearchData` のベクターに変換します。
+///
+/// タイトルの文字参照は展開し、説明には本文の先頭部分をテキスト化して使用します。
+fn parse_data(json: &str) -> Result<Vec<SearchData>, String> {
+    let response: Response = serde_json::from_str(json)
+        .map_err(|e| format!("Failed to parse Stack Exchange response: {}", e))?;
+    if let Some(message) = response.error_message {
+        return Err(format!("Stack Exchange API error: {}", message));
+    }
+    let results = response
+        .items
+        .into_iter()
+        .map(|q| {
+            let mut metadata = BTreeMap::new();
+            metadata.insert("score".to_string(), q.score.to_string());
+            metadata.insert("answers".to_string(), q.answer_count.to_string());
+            metadata.insert("answered".to_string(), q.is_answered.to_string());
+            metadata.insert(
+                "accepted".to_string(),
+                q.accepted_answer_id.is_some().to_string(),
+            );
+            metadata.insert("views".to_string(), q.view_count.to_string());
+            if !q.tags.is_empty() {
+                metadata.insert("tags".to_string(), q.tags.join(", "));
+            }
+            if let Some(created) = q.creation_date {
+                metadata.insert("created_at".to_string(), created.to_string());
+            }
+            let body = search::html_to_text(&q.body);
+            let description = match body.char_indices().nth(BODY_EXCERPT_CHARS) {
+                Some((end, _)) => format!("{}…", &body[..end]),
+                None => body,
+            };
+            SearchData {
+                title: search::html_to_text(&q.title),
+                url: q.link,
+                description,
+                metadata,
+            }
+        })
+        .collect();
+    Ok(results)
+}
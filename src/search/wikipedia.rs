@@ -0,0 +1,360 @@
+// src/search/wikipedia.rs
+
+//! Wikipedia / MediaWiki の検索モジュール
+//!
+//! MediaWiki Action API (`api.php`) の `list=search` で検索し、`generator=search` で
+//! 同時に取得した正規のURL (`fullurl`) と組み合わせて結果を返します。
+//!
+//! 接続先は `[engines.wikipedia] base_url` (`api.php` のあるスクリプトパス、例:
+//! `https://wiki.example.com/w`) で変更できます。未設定の場合は `SearchOptions::language`
+//! (デフォルト `en`) の言語版Wikipedia (`https://<lang>.wikipedia.org/w`) を使用します。
+//!
+//! 記事ページは [`fetch_article`] でparse APIから取得し、Markdownに変換できます
+//! (`browse::fetch_and_markdown` はWikipediaの記事URLに対して自動的にこちらを使用します)。
+//!
+//! `SearchData::metadata` には次の値を格納します。
+//! - `wordcount`: 記事の単語数
+//! - `timestamp`: 最終更新日時
+
+use std::collections::{BTreeMap, HashMap};
+
+use scraper::{Html, Selector};
+use serde::Deserialize;
+
+use crate::search;
+use crate::{EngineType, SearchData, SearchOptions, browse, config};
+
+/// 1ページあたりの取得件数
+const PAGE_SIZE: usize = 20;
+
+/// MediaWikiの検索APIで検索を実行します。
+///
+/// # 引数
+/// - `query`: 検索クエリ文字列。
+/// - `options`: 検索オプション (ページ番号、言語版の選択)。
+///
+/// # 戻り値
+/// `Result<Vec<SearchData>, String>`:
+/// - 成功した場合: 検索結果のリスト (`Vec<SearchData>`)。
+/// - 失敗した場合: エラーメッセージ文字列。
+pub async fn search_wikipedia(
+    query: String,
+    options: &SearchOptions,
+) -> Result<Vec<SearchData>, String> {
+    let client = search::client(Some(EngineType::Wikipedia), Some(search::API_USER_AGENT))?;
+    let request = client
+        .get(api_url(options.language.as_deref())?)
+        .query(&search_params(&query, options));
+    parse_search(&search::fetch_text(request, "MediaWiki").await?)
+}
+
+/// MediaWikiの検索APIで検索を同期で実行します。
+pub fn search_wikipedia_sync(
+    query: String,
+    options: &SearchOptions,
+) -> Result<Vec<SearchData>, String> {
+    let client =
+        search::blocking_client(Some(EngineType::Wikipedia), Some(search::API_USER_AGENT))?;
+    let request = client
+        .get(api_url(options.language.as_deref())?)
+        .query(&search_params(&query, options));
+    parse_search(&search::fetch_text_sync(request, "MediaWiki")?)
+}
+
+/// 記事のURLであれば、parse APIで本文を取得してMarkdownで返します。
+///
+/// 記事のURLでない場合は `None` を返します。
+pub async fn fetch_article(url: &str) -> Option<Result<String, String>> {
+    let (api, title) = article_location(url)?;
+    let result = async {
+        let client = search::client(Some(EngineType::Wikipedia), Some(search::API_USER_AGENT))?;
+        let request = client.get(&api).query(&parse_params(&title));
+        article_markdown(&search::fetch_text(request, "MediaWiki").await?, url)
+    };
+    Some(result.await)
+}
+
+/// 記事のURLであれば、parse APIで本文を同期で取得してMarkdownで返します。
+pub fn fetch_article_sync(url: &str) -> Option<Result<String, String>> {
+    let (api, title) = article_location(url)?;
+    let result = (|| {
+        let client =
+            search::blocking_client(Some(EngineType::Wikipedia), Some(search::API_USER_AGENT))?;
+        let request = client.get(&api).query(&parse_params(&title));
+        article_markdown(&search::fetch_text_sync(request, "MediaWiki")?, url)
+    })();
+    Some(result)
+}
+
+/// 設定されたスクリプトパス (または言語版Wikipedia) の `api.php` のURL
+///
+/// 言語コードはホスト名の一部になるため、[`is_language_code`] を満たさない場合はエラーを返します。
+fn api_url(language: Option<&str>) -> Result<String, String> {
+    match config::current()
+        .engines
+        .wikipedia
+        .base_url
+        .as_deref()
+        .filter(|u| !u.is_empty())
+    {
+        Some(base_url) => Ok(format!("{}/api.php", base_url.trim_end_matches('/'))),
+        None => {
+            let language = language.filter(|l| !l.is_empty()).unwrap_or("en");
+            if !is_language_code(language) {
+                return Err(format!("Invalid Wikipedia language code: {}", language));
+            }
+            Ok(format!("https://{}.wikipedia.org/w/api.php", language))
+        }
+    }
+}
+
+/// 言語版Wikipediaのサブドメインとして使える言語コード (`^[a-z][a-z0-9-]{1,15}$`) かどうか
+fn is_language_code(language: &str) -> bool {
+    let mut chars = language.chars();
+    chars.next().is_some_and(|c| c.is_ascii_lowercase())
+        && (2..=16).contains(&language.len())
+        && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+}
+
+fn search_params(query: &str, options: &SearchOptions) -> Vec<(&'static str, String)> {
+    let offset = ((options.page.max(1) - 1) * PAGE_SIZE).to_string();
+    vec![
+        ("action", "query".to_string()),
+        ("format", "json".to_string()),
+        ("formatversion", "2".to_string()),
+        ("list", "search".to_string()),
+        ("srsearch", query.to_string()),
+        ("srlimit", PAGE_SIZE.to_string()),
+        ("sroffset", offset.clone()),
+        ("srprop", "snippet|wordcount|timestamp".to_string()),
+        // 同じ検索をgeneratorとして実行し、各ページの正規のURLを取得する
+        ("generator", "search".to_string()),
+        ("gsrsearch", query.to_string()),
+        ("gsrlimit", PAGE_SIZE.to_string()),
+        ("gsroffset", offset),
+        ("prop", "info".to_string()),
+        ("inprop", "url".to_string()),
+    ]
+}
+
+fn parse_params(title: &str) -> Vec<(&'static str, String)> {
+    vec![
+        ("action", "parse".to_string()),
+        ("format", "json".to_string()),
+        ("formatversion", "2".to_string()),
+        ("page", title.to_string()),
+        ("prop", "text|displaytitle".to_string()),
+        ("redirects", "1".to_string()),
+        ("disableeditsection", "1".to_string()),
+        ("disabletoc", "1".to_string()),
+    ]
+}
+
+/// 記事URLから (`api.php` のURL, 記事名) を取り出す
+///
+/// `<言語>.wikipedia.org` (モバイル版の `<言語>.m.wikipedia.org` を含む) の `/wiki/<記事名>` と、
+/// 設定された `base_url` と同じホストの `/wiki/<記事名>` または `?title=<記事名>` を
+/// 記事のURLとみなします。言語版Wikipediaの記事は常に `https://<言語>.wikipedia.org` のAPIから取得します。
+fn article_location(url: &str) -> Option<(String, String)> {
+    let parsed = url::Url::parse(url).ok()?;
+    let host = parsed.host_str()?;
+    let api = if let Some(subdomain) = host.strip_suffix(".wikipedia.org") {
+        let language = subdomain.strip_suffix(".m").unwrap_or(subdomain);
+        if !is_language_code(language) || parsed.port().is_some() {
+            return None;
+        }
+        format!("https://{}.wikipedia.org/w/api.php", language)
+    } else {
+        let base_url = config::current().engines.wikipedia.base_url.clone()?;
+        let base = url::Url::parse(&base_url).ok()?;
+        if base.host_str() != Some(host) {
+            return None;
+        }
+        format!("{}/api.php", base_url.trim_end_matches('/'))
+    };
+    let title = match parsed.path().strip_prefix("/wiki/") {
+        Some(title) if !title.is_empty() => urlencoding::decode(title).ok()?.into_owned(),
+        _ => parsed
+            .query_pairs()
+            .find(|(k, _)| k == "title")
+            .map(|(_, v)| v.into_owned())?,
+    };
+    Some((api, title.replace('_', " ")))
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchResponse {
+    #[serde(default)]
+    query: Option<SearchQuery>,
+    #[serde(default)]
+    error: Option<ApiError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchQuery {
+    #[serde(default)]
+    search: Vec<SearchHit>,
+    #[serde(default)]
+    pages: Vec<PageInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchHit {
+    pageid: u64,
+    title: String,
+    #[serde(default)]
+    snippet: String,
+    #[serde(default)]
+    wordcount: Option<u64>,
+    #[serde(default)]
+    timestamp: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PageInfo {
+    pageid: u64,
+    #[serde(default)]
+    fullurl: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiError {
+    #[serde(default)]
+    info: String,
+}
+
+/// `list=search` と `generator=search` の結果を `SearchData` のベクターに変換します。
+///
+/// スニペット中の `<span class="searchmatch">` (検索語の一致箇所) は `*テキスト*` 形式に変換します。
+fn parse_search(json: &str) -> Result<Vec<SearchData>, String> {
+    let response: SearchResponse = serde_json::from_str(json)
+        .map_err(|e| format!("Failed to parse MediaWiki response: {}", e))?;
+    if let Some(error) = response.error {
+        return Err(format!("MediaWiki API error: {}", error.info));
+    }
+    let Some(query) = response.query else {
+        return Ok(Vec::new());
+    };
+    let urls: HashMap<u64, String> = query
+        .pages
+        .into_iter()
+        .filter_map(|p| Some((p.pageid, p.fullurl?)))
+        .collect();
+    let results = query
+        .search
+        .into_iter()
+        .filter_map(|hit| {
+            let url = urls.get(&hit.pageid)?.clone();
+            let mut metadata = BTreeMap::new();
+            if let Some(wordcount) = hit.wordcount {
+                metadata.insert("wordcount".to_string(), wordcount.to_string());
+            }
+            if let Some(timestamp) = hit.timestamp {
+                metadata.insert("timestamp".to_string(), timestamp);
+            }
+            let snippet = hit
+                .snippet
+                .replace(r#"<span class="searchmatch">"#, "*")
+                .replace("</span>", "*");
+            Some(SearchData {
+                title: hit.title,
+                url,
+                description: search::html_to_text(&snippet),
+                metadata,
+            })
+        })
+        .collect();
+    Ok(results)
+}
+
+#[derive(Debug, Deserialize)]
+struct ParseResponse {
+    #[serde(default)]
+    parse: Option<ParsedPage>,
+    #[serde(default)]
+    error: Option<ApiError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ParsedPage {
+    title: String,
+    #[serde(default)]
+    text: String,
+}
+
+/// parse APIの記事HTMLから、脚注・編集リンク・表・ナビゲーションを除いてMarkdownに変換します。
+fn article_markdown(json: &str, url: &str) -> Result<String, String> {
+    let response: ParseResponse = serde_json::from_str(json)
+        .map_err(|e| format!("Failed to parse MediaWiki response: {}", e))?;
+    if let Some(error) = response.error {
+        return Err(format!("MediaWiki API error: {}", error.info));
+    }
+    let page = response.parse.ok_or("MediaWiki API returned no page")?;
+
+    let mut document = Html::parse_document(&page.text);
+    let noise = Selector::parse(
+        "sup.reference, .mw-editsection, .reflist, .mw-references-wrap, .navbox, \
+         .metadata, .hatnote, table, style, figure, .thumb",
+    )
+    .unwrap();
+    let ids: Vec<_> = document.select(&noise).map(|e| e.id()).collect();
+    for id in ids {
+        if let Some(mut node) = document.tree.get_mut(id) {
+            node.detach();
+        }
+    }
+    let body = browse::html_to_markdown(&document.html(), url)?;
+    Ok(format!("# {}\n\n{}", page.title, body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn api_url_accepts_only_language_codes() {
+        assert_eq!(
+            api_url(Some("ja")).unwrap(),
+            "https://ja.wikipedia.org/w/api.php"
+        );
+        assert_eq!(api_url(None).unwrap(), "https://en.wikipedia.org/w/api.php");
+        assert!(api_url(Some("zh-classical")).is_ok());
+        for language in [
+            "evil.com/",
+            "evil.com#",
+            "user@evil.com",
+            "localhost:8080",
+            "en?x=",
+            "EN",
+            "e",
+            "1a",
+            "a-very-long-language",
+        ] {
+            assert!(api_url(Some(language)).is_err(), "{}", language);
+        }
+    }
+
+    #[test]
+    fn article_location_rebuilds_the_api_host() {
+        assert_eq!(
+            article_location("https://ja.m.wikipedia.org/wiki/Rust_(%E8%A8%80%E8%AA%9E)"),
+            Some((
+                "https://ja.wikipedia.org/w/api.php".to_string(),
+                "Rust (言語)".to_string()
+            ))
+        );
+        assert_eq!(
+            article_location("http://en.wikipedia.org/wiki/Rust").map(|(api, _)| api),
+            Some("https://en.wikipedia.org/w/api.php".to_string())
+        );
+        assert_eq!(
+            article_location("https://en.wikipedia.org:8443/wiki/Rust"),
+            None
+        );
+        assert_eq!(
+            article_location("https://a.b.wikipedia.org/wiki/Rust"),
+            None
+        );
+        assert_eq!(article_location("https://example.com/wiki/Rust"), None);
+    }
+}
@@ -0,0 +1,118 @@
+// src/search/yahoo_japan.rs
+
+//! Yahoo! JAPAN検索 (search.yahoo.co.jp) のモジュール
+//!
+//! 検索結果ページのHTMLをパースします。結果のリンクはクリック計測用のリダイレクトURL
+//! (`https://rdsig.yahoo.co.jp/.../RU=<Base64>/RS=...`) になっている場合があるため、
+//! `RU=` に埋め込まれた本来のURLを [`crate::canonical`] で復元します。
+
+use scraper::{Html, Selector};
+
+use crate::search::{self, element_text};
+use crate::{EngineType, SearchData, SearchOptions, canonical};
+
+const SEARCH_URL: &str = "https://search.yahoo.co.jp/search";
+
+/// 1ページあたりの件数
+const PAGE_SIZE: usize = 10;
+
+/// Yahoo! JAPANで検索を実行します。
+///
+/// # 引数
+/// - `query`: 検索クエリ文字列。
+/// - `options`: 検索オプション (ページ番号)。
+///
+/// # 戻り値
+/// `Result<Vec<SearchData>, String>`:
+/// - 成功した場合: 検索結果のリスト (`Vec<SearchData>`)。
+/// - 失敗した場合: エラーメッセージ文字列。
+pub async fn search_yahoo_japan(
+    query: String,
+    options: &SearchOptions,
+) -> Result<Vec<SearchData>, String> {
+    let client = search::client(
+        Some(EngineType::YahooJapan),
+        Some(search::DEFAULT_USER_AGENT),
+    )?;
+    let request = client.get(SEARCH_URL).query(&params(&query, options));
+    Ok(parse_data(
+        &search::fetch_text(request, "Yahoo! JAPAN").await?,
+    ))
+}
+
+/// Yahoo! JAPANで検索を同期で実行します。
+pub fn search_yahoo_japan_sync(
+    query: String,
+    options: &SearchOptions,
+) -> Result<Vec<SearchData>, String> {
+    let client = search::blocking_client(
+        Some(EngineType::YahooJapan),
+        Some(search::DEFAULT_USER_AGENT),
+    )?;
+    let request = client.get(SEARCH_URL).query(&params(&query, options));
+    Ok(parse_data(&search::fetch_text_sync(
+        request,
+        "Yahoo! JAPAN",
+    )?))
+}
+
+/// クエリパラメータ。`b` は結果の開始位置 (1始まり) です。
+fn params(query: &str, options: &SearchOptions) -> Vec<(&'static str, String)> {
+    vec![
+        ("p", query.to_string()),
+        ("ei", "UTF-8".to_string()),
+        ("b", ((options.page.max(1) - 1) * PAGE_SIZE + 1).to_string()),
+    ]
+}
+
+/// Yahoo! JAPANの検索結果HTMLを `SearchData` のベクターにパースします。
+///
+/// 各結果は `div.Algo` (旧構造では `div.w`) で、以下の要素から値を抽出します。
+/// - **URL**: `h3` を含むリンク (なければ最初のリンク)。リダイレクトURLは [`canonical::resolve`] で復元
+/// - **タイトル**: `h3` (なければリンクのテキスト)
+/// - **説明**: `.sw-Card__summary` または `.a` 内の段落
+///
+/// # 重要な注意点:
+/// Yahoo! JAPANのHTML構造は変更される可能性があるため、複数のセレクタを順に試します。
+fn parse_data(html: &str) -> Vec<SearchData> {
+    let document = Html::parse_document(html);
+    let result_selector = Selector::parse("div.Algo, div#web li > div.w").unwrap();
+    let title_link_selector = Selector::parse("a[href]:has(h3)").unwrap();
+    let link_selector = Selector::parse("a[href]").unwrap();
+    let title_selector = Selector::parse("h3").unwrap();
+    let description_selector =
+        Selector::parse(".sw-Card__summary, .Algo__summary, div.a, p").unwrap();
+
+    let mut results = Vec::new();
+    for result in document.select(&result_selector) {
+        let Some(link) = result
+            .select(&title_link_selector)
+            .next()
+            .or_else(|| result.select(&link_selector).next())
+        else {
+            continue;
+        };
+        let href = link.value().attr("href").unwrap_or("");
+        let url = canonical::resolve(SEARCH_URL, href).unwrap_or_else(|| href.to_string());
+        let title = result
+            .select(&title_selector)
+            .next()
+            .map(element_text)
+            .unwrap_or_else(|| element_text(link));
+        if title.is_empty() || !url.starts_with("http") {
+            continue;
+        }
+        let description = result
+            .select(&description_selector)
+            .map(element_text)
+            .find(|d| !d.is_empty())
+            .unwrap_or_default();
+        results.push(SearchData {
+            title,
+            url,
+            description,
+            ..Default::default()
+        });
+    }
+    results
+}
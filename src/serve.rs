@@ -0,0 +1,460 @@
+// src/serve.rs
+
+//! `www-search serve` で起動するローカルHTTP JSON APIサーバー
+//!
+//! エンドポイント:
+//! - `GET /search?q=&engine=&page=`: 検索結果をSearXNGの `format=json` 互換の形式で返す
+//!   (`engines=google,duckduckgo` で複数エンジンの結果を統合、`pageno` も `page` と同様に扱う。
+//!   `language`, `time_range`, `categories` も受け付け、`categories=news` ではニュースを検索する)
+//! - `GET /browse?url=`: ページ本文をMarkdownで返す (`[server] enable_browse = true` の場合のみ)
+//!
+//! `[server]` の `api_keys` が設定されている場合は、`X-API-Key` ヘッダーまたは
+//! `Authorization: Bearer <key>` ヘッダーでの認証が必要です。
+//! `/browse` はサーバーから任意のURL (内部ネットワークを含む) を取得できるため、
+//! `api_keys` を設定していない場合は有効にできません。閲覧したページを保存したローカルの
+//! 全文検索インデックス (`engine=local`、`!local`) も、`/browse` を有効にした場合のみ検索できます。
+//!
+//! レート制限は認証より先に適用するため、誤ったAPIキーでのリクエストも上限に数えます。
+
+use std::collections::{BTreeMap, HashMap};
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use axum::extract::{ConnectInfo, Query, Request, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use tokio::sync::Semaphore;
+use www_search::config::{self, ServerConfig};
+use www_search::{
+    Answer, EngineType, MAX_PAGE, SearchOptions, TimeRange, Vertical, bang, browse, canonical,
+    www_search_response,
+};
+
+struct AppState {
+    server: ServerConfig,
+    /// クライアントIPごとの (ウィンドウ開始時刻, リクエスト数)
+    windows: Mutex<HashMap<IpAddr, (Instant, u32)>>,
+    permits: Semaphore,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchParams {
+    q: Option<String>,
+    engine: Option<String>,
+    engines: Option<String>,
+    page: Option<usize>,
+    pageno: Option<usize>,
+    language: Option<String>,
+    time_range: Option<String>,
+    categories: Option<String>,
+    format: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BrowseParams {
+    url: Option<String>,
+}
+
+/// SearXNGの `format=json` 互換のレスポンス
+#[derive(Debug, Serialize)]
+struct SearchResponse {
+    query: String,
+    number_of_results: usize,
+    results: Vec<SearchResult>,
+    answers: Vec<String>,
+    corrections: Vec<String>,
+    infoboxes: Vec<serde_json::Value>,
+    suggestions: Vec<String>,
+    /// 「他の人はこちらも質問」の質問 (SearXNGにはない拡張フィールド)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    people_also_ask: Vec<String>,
+    /// 検索演算子を絞り込みで代替した際の警告 (SearXNGにはない拡張フィールド)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    warnings: Vec<String>,
+    unresponsive_engines: Vec<(String, String)>,
+}
+
+#[derive(Debug, Serialize)]
+struct SearchResult {
+    url: String,
+    title: String,
+    content: String,
+    engine: String,
+    engines: Vec<String>,
+    positions: Vec<usize>,
+    score: f64,
+    category: String,
+    parsed_url: Vec<String>,
+    /// 画像検索での画像本体とサムネイルのURL (`url` は掲載ページ)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    img_src: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    thumbnail_src: Option<String>,
+    /// エンジン固有の付加情報 (SearXNGにはない拡張フィールド)
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    metadata: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Serialize)]
+struct BrowseResponse {
+    url: String,
+    markdown: String,
+}
+
+/// 設定 (`config::current().server`) に従ってサーバーを起動します。
+pub async fn run() -> Result<(), String> {
+    let server = config::current().server.clone();
+    if server.enable_browse && server.api_keys.is_empty() {
+        return Err(
+            "[server] enable_browse requires api_keys (set api_keys or use --api-key)".to_string(),
+        );
+    }
+    let listen = server.listen.clone();
+    let mut app = Router::new().route("/search", get(search));
+    if server.enable_browse {
+        app = app.route("/browse", get(browse_page));
+    }
+    let state = Arc::new(AppState {
+        permits: Semaphore::new(server.max_concurrent.max(1)),
+        windows: Mutex::new(HashMap::new()),
+        server,
+    });
+    let app = app
+        .layer(middleware::from_fn_with_state(state.clone(), guard))
+        .with_state(state);
+    let listener = tokio::net::TcpListener::bind(&listen)
+        .await
+        .map_err(|e| format!("Failed to bind {}: {}", listen, e))?;
+    eprintln!("Listening on http://{}", listen);
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await
+    .map_err(|e| format!("Server error: {}", e))
+}
+
+fn error(status: StatusCode, message: impl Into<String>) -> Response {
+    let body = serde_json::json!({ "error": message.into() });
+    (status, Json(body)).into_response()
+}
+
+/// レート制限・APIキー認証・同時実行数の制限を行うミドルウェア
+async fn guard(
+    State(state): State<Arc<AppState>>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    request: Request,
+    next: Next,
+) -> Response {
+    // 認証に失敗したリクエストも数えて、APIキーの総当たりを制限する
+    if state.server.rate_limit_per_minute > 0 {
+        let mut windows = state.windows.lock().unwrap();
+        let now = Instant::now();
+        let window = Duration::from_secs(60);
+        // 期限切れのエントリを掃除してメモリの増加を防ぐ
+        if windows.len() > 1024 {
+            windows.retain(|_, (start, _)| now.duration_since(*start) < window);
+        }
+        let entry = windows.entry(addr.ip()).or_insert((now, 0));
+        if now.duration_since(entry.0) >= window {
+            *entry = (now, 0);
+        }
+        if entry.1 >= state.server.rate_limit_per_minute {
+            return error(StatusCode::TOO_MANY_REQUESTS, "Rate limit exceeded");
+        }
+        entry.1 += 1;
+    }
+    if !state.server.api_keys.is_empty() {
+        let key = headers
+            .get("x-api-key")
+            .and_then(|v| v.to_str().ok())
+            .or_else(|| {
+                headers
+                    .get("authorization")
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|v| v.strip_prefix("Bearer "))
+            });
+        // キーの比較にかかる時間から一致した長さを推測されないように、すべてのキーと比較する
+        let valid = key.is_some_and(|key| {
+            state.server.api_keys.iter().fold(false, |found, k| {
+                found | constant_time_eq(k.as_bytes(), key.as_bytes())
+            })
+        });
+        if !valid {
+            return error(StatusCode::UNAUTHORIZED, "Invalid or missing API key");
+        }
+    }
+    let Ok(_permit) = state.permits.try_acquire() else {
+        return error(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Too many concurrent requests",
+        );
+    };
+    next.run(request).await
+}
+
+async fn search(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<SearchParams>,
+) -> Response {
+    if params.format.as_deref().is_some_and(|f| f != "json") {
+        return error(StatusCode::BAD_REQUEST, "Only format=json is supported");
+    }
+    let query = params.q.unwrap_or_default().trim().to_string();
+    if query.is_empty() {
+        return error(StatusCode::BAD_REQUEST, "Missing query parameter: q");
+    }
+    if query.chars().count() > state.server.max_query_length {
+        return error(StatusCode::BAD_REQUEST, "Query is too long");
+    }
+    let names = params
+        .engines
+        .or(params.engine)
+        .unwrap_or_else(|| config::current().search.engine.name().to_string());
+    let mut engines = Vec::new();
+    for name in names.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+        match name.parse::<EngineType>() {
+            Ok(engine) if !engines.contains(&engine) => engines.push(engine),
+            Ok(_) => {}
+            Err(e) => return error(StatusCode::BAD_REQUEST, e),
+        }
+    }
+    // 閲覧したページの全文検索インデックスは、`/browse` を有効にした場合のみ公開する
+    let bang_engine = match bang::route(&query) {
+        Some(bang::Route::Engine { engine, .. }) => Some(engine),
+        _ => None,
+    };
+    if !state.server.enable_browse
+        && (engines.contains(&EngineType::Local) || bang_engine == Some(EngineType::Local))
+    {
+        return error(
+            StatusCode::FORBIDDEN,
+            "engine=local requires [server] enable_browse",
+        );
+    }
+    let time_range = match params.time_range.as_deref().filter(|t| !t.is_empty()) {
+        Some(time_range) => match time_range.parse::<TimeRange>() {
+            Ok(time_range) => Some(time_range),
+            Err(e) => return error(StatusCode::BAD_REQUEST, e),
+        },
+        None => None,
+    };
+    let categories: Vec<String> = params
+        .categories
+        .map(|c| {
+            c.split(',')
+                .map(|c| c.trim().to_string())
+                .filter(|c| !c.is_empty())
+                .collect()
+        })
+        .unwrap_or_default();
+    // SearXNGと同様に `categories=news` / `images` / `videos` でニュース・画像・動画検索を行う
+    let vertical = categories
+        .iter()
+        .find_map(|c| c.parse().ok().filter(|v| *v != Vertical::Web))
+        .unwrap_or(Vertical::Web);
+    let options = SearchOptions {
+        page: params
+            .page
+            .or(params.pageno)
+            .unwrap_or(1)
+            .clamp(1, MAX_PAGE),
+        // SearXNGの `language=all` は言語指定なしとして扱う
+        language: params.language.filter(|l| !l.is_empty() && l != "all"),
+        time_range,
+        categories,
+        vertical,
+        bangs: true,
+        ..Default::default()
+    };
+
+    // 各エンジンを並行して検索し、エンジンの指定順に結果を統合する
+    let handles: Vec<_> = engines
+        .iter()
+        .map(|&engine| {
+            let query = query.clone();
+            let options = options.clone();
+            tokio::spawn(async move { www_search_response(engine, query, &options).await })
+        })
+        .collect();
+    let mut results: Vec<SearchResult> = Vec::new();
+    let mut unresponsive_engines = Vec::new();
+    let mut answers = Vec::new();
+    let mut infoboxes = Vec::new();
+    let mut corrections: Vec<String> = Vec::new();
+    let mut suggestions: Vec<String> = Vec::new();
+    let mut people_also_ask: Vec<String> = Vec::new();
+    let mut warnings: Vec<String> = Vec::new();
+    for (engine, handle) in engines.iter().zip(handles) {
+        let data = match handle.await {
+            Ok(Ok(response)) => {
+                if let Some(corrected) = response.corrected_query
+                    && !corrections.contains(&corrected)
+                {
+                    corrections.push(corrected);
+                }
+                for related in response.related_searches {
+                    if !suggestions.contains(&related) {
+                        suggestions.push(related);
+                    }
+                }
+                warnings.extend(response.warnings);
+                for question in response.people_also_ask {
+                    if !people_also_ask.contains(&question) {
+                        people_also_ask.push(question);
+                    }
+                }
+                if let Some(answer) = response.answer {
+                    if !answer.abstract_text.is_empty() {
+                        answers.push(answer.abstract_text.clone());
+                    }
+                    infoboxes.push(infobox(&answer, *engine));
+                }
+                response.results
+            }
+            Ok(Err(e)) => {
+                unresponsive_engines.push((engine.name().to_string(), e));
+                continue;
+            }
+            Err(e) => {
+                unresponsive_engines.push((engine.name().to_string(), e.to_string()));
+                continue;
+            }
+        };
+        for (index, mut data) in data.into_iter().enumerate() {
+            let position = index + 1;
+            // SearXNGの画像結果と同様に、`url` を掲載ページ、`img_src` を画像本体にする
+            let (img_src, thumbnail_src) = if vertical == Vertical::Images {
+                let img_src = match data.metadata.get("source_page") {
+                    Some(page) => Some(std::mem::replace(&mut data.url, page.clone())),
+                    None => Some(data.url.clone()),
+                };
+                (img_src, data.metadata.get("thumbnail").cloned())
+            } else {
+                (None, None)
+            };
+            let key = canonical::dedup_key(&data.url);
+            match results
+                .iter_mut()
+                .find(|r| canonical::dedup_key(&r.url) == key && r.img_src == img_src)
+            {
+                Some(existing) => {
+                    existing.engines.push(engine.name().to_string());
+                    existing.positions.push(position);
+                    existing.score += 1.0 / position as f64;
+                }
+                None => results.push(SearchResult {
+                    parsed_url: parse_url(&data.url),
+                    url: data.url,
+                    title: data.title,
+                    content: data.description,
+                    engine: engine.name().to_string(),
+                    engines: vec![engine.name().to_string()],
+                    positions: vec![position],
+                    score: 1.0 / position as f64,
+                    category: match vertical {
+                        Vertical::Web => "general".to_string(),
+                        _ => vertical.name().to_string(),
+                    },
+                    img_src,
+                    thumbnail_src,
+                    metadata: data.metadata,
+                }),
+            }
+        }
+    }
+    if engines.len() > 1 {
+        results.sort_by(|a, b| b.score.total_cmp(&a.score));
+    }
+    Json(SearchResponse {
+        query,
+        number_of_results: results.len(),
+        results,
+        answers,
+        corrections,
+        infoboxes,
+        suggestions,
+        people_also_ask,
+        warnings,
+        unresponsive_engines,
+    })
+    .into_response()
+}
+
+async fn browse_page(Query(params): Query<BrowseParams>) -> Response {
+    let Some(url) = params.url.filter(|u| !u.is_empty()) else {
+        return error(StatusCode::BAD_REQUEST, "Missing query parameter: url");
+    };
+    if !(url.starts_with("http://") || url.starts_with("https://")) {
+        return error(StatusCode::BAD_REQUEST, "Only http(s) URLs are supported");
+    }
+    match browse::fetch_and_markdown(&url).await {
+        Ok(markdown) => Json(BrowseResponse { url, markdown }).into_response(),
+        Err(e) => error(StatusCode::BAD_GATEWAY, e),
+    }
+}
+
+/// 一致しない位置によらず同じ時間で2つのバイト列を比較する
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    let mut diff = a.len() ^ b.len();
+    for i in 0..a.len().max(b.len()) {
+        let x = a.get(i).copied().unwrap_or(0);
+        let y = b.get(i).copied().unwrap_or(0);
+        diff |= usize::from(x ^ y);
+    }
+    diff == 0
+}
+
+/// インスタントアンサーをSearXNGのインフォボックス形式に変換する
+fn infobox(answer: &Answer, engine: EngineType) -> serde_json::Value {
+    let mut urls: Vec<serde_json::Value> = Vec::new();
+    if !answer.url.is_empty() {
+        urls.push(serde_json::json!({ "title": answer.source, "url": answer.url }));
+    }
+    urls.extend(
+        answer
+            .related_topics
+            .iter()
+            .map(|t| serde_json::json!({ "title": t.text, "url": t.url })),
+    );
+    serde_json::json!({
+        "infobox": answer.title,
+        "id": answer.url,
+        "content": answer.abstract_text,
+        "img_src": answer.image,
+        "urls": urls,
+        "attributes": answer
+            .infobox
+            .iter()
+            .map(|(label, value)| serde_json::json!({ "label": label, "value": value }))
+            .collect::<Vec<_>>(),
+        "engine": engine.name(),
+    })
+}
+
+/// SearXNGの `parsed_url` (Pythonの `urlparse` の結果) に相当する配列を作る
+fn parse_url(url: &str) -> Vec<String> {
+    match url::Url::parse(url) {
+        Ok(u) => {
+            let netloc = match u.port() {
+                Some(port) => format!("{}:{}", u.host_str().unwrap_or(""), port),
+                None => u.host_str().unwrap_or("").to_string(),
+            };
+            vec![
+                u.scheme().to_string(),
+                netloc,
+                u.path().to_string(),
+                String::new(),
+                u.query().unwrap_or("").to_string(),
+                u.fragment().unwrap_or("").to_string(),
+            ]
+        }
+        Err(_) => Vec::new(),
+    }
+}
@@ -0,0 +1,154 @@
+// src/stream.rs
+
+//! 検索結果をストリームとして順次返すAPI
+//!
+//! 複数のエンジンを並行して検索し、各エンジンの各ページの取得が完了した時点で結果を流します。
+//! 指定件数が集まるまで自動的に次のページを取得します。
+
+use std::collections::HashSet;
+
+use futures::stream::{self, BoxStream, StreamExt};
+
+use crate::{EngineType, SearchData, SearchOptions, canonical, config, search_results};
+
+/// ストリーム検索のオプション
+///
+/// - `engines`: 並行して検索するエンジン (空なら設定のデフォルトエンジン)
+/// - `limit`: 返却する結果の最大件数 (`None` なら設定の `max_results`、それもなければ `max_pages` まで取得)
+/// - `max_pages`: エンジンごとに取得する最大ページ数
+/// - `options`: 各ページの検索オプション (`page` は開始ページとして扱います)
+#[derive(Debug, Clone)]
+pub struct StreamOptions {
+    pub engines: Vec<EngineType>,
+    pub limit: Option<usize>,
+    pub max_pages: usize,
+    pub options: SearchOptions,
+}
+
+impl Default for StreamOptions {
+    fn default() -> Self {
+        StreamOptions {
+            engines: Vec::new(),
+            limit: None,
+            max_pages: 5,
+            options: SearchOptions::default(),
+        }
+    }
+}
+
+/// 検索結果を1件ずつ返すストリームを作成します。
+///
+/// 同じURLの結果は最初に届いたもののみを返します。設定の `[domains]` は各ページの結果に適用するため、
+/// 固定・ブーストなどの並べ替えはページ内でのみ行います。エンジンでエラーが発生した場合は
+/// `Err` を1件流し、そのエンジンの検索を終了します (他のエンジンの検索は継続します)。
+/// ストリームは遅延評価のため、必要な件数を受け取った後のページは取得しません。
+///
+/// # 例
+/// ```ignore
+/// use futures::StreamExt;
+/// use www_search::stream::{StreamOptions, search_stream};
+/// use www_search::EngineType;
+///
+/// async fn run() {
+///     let options = StreamOptions {
+///         engines: vec![EngineType::Google, EngineType::DuckDuckGo],
+///         limit: Some(30),
+///         ..Default::default()
+///     };
+///     let mut results = search_stream("Rust".to_string(), options);
+///     while let Some(result) = results.next().await {
+///         match result {
+///             Ok(data) => println!("{} - {}", data.title, data.url),
+///             Err(e) => eprintln!("{}", e),
+///         }
+///     }
+/// }
+/// ```
+pub fn search_stream(
+    query: String,
+    options: StreamOptions,
+) -> BoxStream<'static, Result<SearchData, String>> {
+    let engines = if options.engines.is_empty() {
+        vec![config::current().search.engine]
+    } else {
+        options.engines.clone()
+    };
+    let pages = engines.into_iter().map(|engine| {
+        engine_pages(
+            engine,
+            query.clone(),
+            options.options.clone(),
+            options.max_pages.max(1),
+        )
+    });
+    let limit = options.limit.or(config::current().search.max_results);
+    let results = stream::select_all(pages)
+        .flat_map(|page| {
+            let items: Vec<Result<SearchData, String>> = match page {
+                Ok(results) => config::current()
+                    .domains
+                    .apply(results)
+                    .into_iter()
+                    .map(Ok)
+                    .collect(),
+                Err(e) => vec![Err(e)],
+            };
+            stream::iter(items)
+        })
+        // 同じURLの結果を除く
+        .scan(HashSet::new(), |seen, item| {
+            let item = match item {
+                Ok(data) if !seen.insert(canonical::dedup_key(&data.url)) => None,
+                item => Some(item),
+            };
+            futures::future::ready(Some(item))
+        })
+        .filter_map(futures::future::ready)
+        .boxed();
+    // 件数の上限に達したら、次の結果を待たずにストリームを終了する
+    stream::unfold((results, 0usize), move |(mut results, count)| async move {
+        if limit.is_some_and(|limit| count >= limit) {
+            return None;
+        }
+        let item = results.next().await?;
+        let count = count + usize::from(item.is_ok());
+        Some((item, (results, count)))
+    })
+    .boxed()
+}
+
+/// 1つのエンジンについて、ページごとの検索結果を順に返すストリーム
+///
+/// 結果が空のページ、または新しいURLを含まないページに達した時点で終了します。
+fn engine_pages(
+    engine: EngineType,
+    query: String,
+    options: SearchOptions,
+    max_pages: usize,
+) -> BoxStream<'static, Result<Vec<SearchData>, String>> {
+    let first_page = options.page.max(1);
+    let last_page = first_page.saturating_add(max_pages - 1);
+    stream::unfold(
+        (Some(first_page), HashSet::new()),
+        move |(page, mut seen): (Option<usize>, HashSet<String>)| {
+            let query = query.clone();
+            let mut options = options.clone();
+            async move {
+                let page = page?;
+                options.page = page;
+                match search_results(engine, query, &options).await {
+                    Ok(results) => {
+                        let new_urls = results
+                            .iter()
+                            .filter(|r| seen.insert(canonical::dedup_key(&r.url)))
+                            .count();
+                        let next = (new_urls > 0 && page < last_page).then_some(page + 1);
+                        Some((Ok(results), (next, seen)))
+                    }
+                    Err(e) => Some((Err(format!("{}: {}", engine, e)), (None, seen))),
+                }
+            }
+        },
+    )
+    .boxed()
+}
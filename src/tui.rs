@@ -0,0 +1,659 @@
+// src/tui.rs
+
+//! 検索とWebページ閲覧をフルスクリーンで行うターミナルUI
+//!
+//! 画面構成:
+//! - 上部: 検索クエリ入力欄 (タイトルに現在のエンジンを表示)
+//! - 左側: 検索結果リスト
+//! - 右側: 閲覧中ページのMarkdownを整形表示するリーダー
+//! - 下部: ステータス・キー操作の案内
+
+use std::process::{Command, Stdio};
+
+use ratatui::DefaultTerminal;
+use ratatui::Frame;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap};
+use www_search::{
+    EngineType, SearchData, SearchOptions, Vertical, browse, history, www_search_with_options,
+};
+
+/// 入力フォーカスの位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Focus {
+    Query,
+    Results,
+    Reader,
+}
+
+/// リーダーで表示中のページ
+struct Page {
+    url: String,
+    markdown: String,
+    /// ページ内リンク (出現順)
+    links: Vec<String>,
+    /// 選択中のリンク番号
+    selected_link: Option<usize>,
+    scroll: u16,
+    /// 前回描画したときのリーダーの幅 (枠を除く)
+    width: u16,
+}
+
+/// キー入力の結果として実行する非同期処理
+enum Action {
+    Search,
+    Open(String),
+    Quit,
+}
+
+struct App {
+    engine: EngineType,
+    query: String,
+    focus: Focus,
+    results: Vec<SearchData>,
+    list_state: ListState,
+    page: Option<Page>,
+    back: Vec<Page>,
+    forward: Vec<Page>,
+    status: String,
+}
+
+/// ターミナルUIを起動します。
+///
+/// `query` が空でなければ、起動直後にその検索を実行します。
+pub async fn run(engine: EngineType, query: String) -> Result<(), String> {
+    let mut terminal = ratatui::init();
+    let result = App::new(engine, query).event_loop(&mut terminal).await;
+    ratatui::restore();
+    result
+}
+
+impl App {
+    fn new(engine: EngineType, query: String) -> Self {
+        App {
+            engine,
+            focus: if query.is_empty() {
+                Focus::Query
+            } else {
+                Focus::Results
+            },
+            query,
+            results: Vec::new(),
+            list_state: ListState::default(),
+            page: None,
+            back: Vec::new(),
+            forward: Vec::new(),
+            status: String::new(),
+        }
+    }
+
+    async fn event_loop(mut self, terminal: &mut DefaultTerminal) -> Result<(), String> {
+        if !self.query.is_empty() {
+            self.search(terminal).await?;
+        }
+        loop {
+            terminal
+                .draw(|frame| self.draw(frame))
+                .map_err(|e| format!("Failed to draw terminal: {}", e))?;
+            let event = event::read().map_err(|e| format!("Failed to read event: {}", e))?;
+            let Event::Key(key) = event else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match self.handle_key(key) {
+                Some(Action::Quit) => return Ok(()),
+                Some(Action::Search) => self.search(terminal).await?,
+                Some(Action::Open(url)) => self.open(terminal, url).await?,
+                None => {}
+            }
+        }
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> Option<Action> {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            return Some(Action::Quit);
+        }
+        match self.focus {
+            Focus::Query => match key.code {
+                KeyCode::Enter if !self.query.trim().is_empty() => {
+                    self.focus = Focus::Results;
+                    return Some(Action::Search);
+                }
+                KeyCode::Tab => self.next_engine(),
+                KeyCode::Esc => self.focus = Focus::Results,
+                KeyCode::Backspace => {
+                    self.query.pop();
+                }
+                KeyCode::Char(c) => self.query.push(c),
+                _ => {}
+            },
+            Focus::Results => match key.code {
+                KeyCode::Char('q') => return Some(Action::Quit),
+                KeyCode::Char('/') | KeyCode::Char('i') => self.focus = Focus::Query,
+                KeyCode::Char('e') => {
+                    self.next_engine();
+                    if !self.query.trim().is_empty() {
+                        return Some(Action::Search);
+                    }
+                }
+                KeyCode::Down | KeyCode::Char('j') => self.list_state.select_next(),
+                KeyCode::Up | KeyCode::Char('k') => self.list_state.select_previous(),
+                KeyCode::Right | KeyCode::Char('l') | KeyCode::Tab if self.page.is_some() => {
+                    self.focus = Focus::Reader
+                }
+                KeyCode::Enter => {
+                    if let Some(result) = self.selected_result() {
+                        return Some(Action::Open(result.url.clone()));
+                    }
+                }
+                KeyCode::Char('o') => {
+                    if let Some(result) = self.selected_result() {
+                        let url = result.url.clone();
+                        self.open_in_browser(&url);
+                    }
+                }
+                _ => {}
+            },
+            Focus::Reader => {
+                let page = self.page.as_mut()?;
+                match key.code {
+                    KeyCode::Char('q') => return Some(Action::Quit),
+                    KeyCode::Char('/') | KeyCode::Char('i') => self.focus = Focus::Query,
+                    KeyCode::Esc | KeyCode::Left | KeyCode::Char('h') => {
+                        self.focus = Focus::Results
+                    }
+                    KeyCode::Down | KeyCode::Char('j') => {
+                        page.scroll = page.scroll.saturating_add(1)
+                    }
+                    KeyCode::Up | KeyCode::Char('k') => page.scroll = page.scroll.saturating_sub(1),
+                    KeyCode::PageDown | KeyCode::Char(' ') => {
+                        page.scroll = page.scroll.saturating_add(20)
+                    }
+                    KeyCode::PageUp => page.scroll = page.scroll.saturating_sub(20),
+                    KeyCode::Home | KeyCode::Char('g') => page.scroll = 0,
+                    KeyCode::Tab => page.select_link(1),
+                    KeyCode::BackTab => page.select_link(-1),
+                    KeyCode::Enter => {
+                        if let Some(url) = page.selected_link.map(|i| page.links[i].clone()) {
+                            return Some(Action::Open(url));
+                        }
+                    }
+                    KeyCode::Char('b') | KeyCode::Backspace => self.go_back(),
+                    KeyCode::Char('f') => self.go_forward(),
+                    KeyCode::Char('o') => {
+                        let url = match page.selected_link {
+                            Some(i) => page.links[i].clone(),
+                            None => page.url.clone(),
+                        };
+                        self.open_in_browser(&url);
+                    }
+                    _ => {}
+                }
+            }
+        }
+        None
+    }
+
+    fn selected_result(&self) -> Option<&SearchData> {
+        self.list_state.selected().and_then(|i| self.results.get(i))
+    }
+
+    fn next_engine(&mut self) {
+        let index = EngineType::ALL
+            .iter()
+            .position(|e| *e == self.engine)
+            .unwrap_or(0);
+        self.engine = EngineType::ALL[(index + 1) % EngineType::ALL.len()];
+    }
+
+    /// 処理中の表示を行ってから長時間の処理を始めるために、ステータスを即座に描画する
+    fn show_status(
+        &mut self,
+        terminal: &mut DefaultTerminal,
+        status: String,
+    ) -> Result<(), String> {
+        self.status = status;
+        terminal
+            .draw(|frame| self.draw(frame))
+            .map_err(|e| format!("Failed to draw terminal: {}", e))?;
+        Ok(())
+    }
+
+    async fn search(&mut self, terminal: &mut DefaultTerminal) -> Result<(), String> {
+        let query = self.query.trim().to_string();
+        self.show_status(terminal, format!("Searching with {:?}...", self.engine))?;
+        let options = SearchOptions {
+            bangs: true,
+            ..Default::default()
+        };
+        match www_search_with_options(self.engine, query.clone(), &options).await {
+            Ok(results) => {
+                self.status = format!("{} results ({:?})", results.len(), self.engine);
+                let entry = history::Entry::search(&query, &[self.engine], Vertical::Web, &results);
+                if let Err(e) = history::record(&entry) {
+                    self.status = format!("Failed to record history: {}", e);
+                }
+                self.list_state
+                    .select(if results.is_empty() { None } else { Some(0) });
+                self.results = results;
+            }
+            Err(e) => self.status = format!("Error during search: {}", e),
+        }
+        Ok(())
+    }
+
+    async fn open(&mut self, terminal: &mut DefaultTerminal, url: String) -> Result<(), String> {
+        self.show_status(terminal, format!("Loading {}...", url))?;
+        match browse::fetch_page(&url).await {
+            Ok(page) => {
+                if let Some(current) = self.page.take() {
+                    self.back.push(current);
+                }
+                self.forward.clear();
+                self.status =
+                    match history::record(&history::Entry::browse(&url, None, &self.query)) {
+                        Ok(()) => url.clone(),
+                        Err(e) => format!("Failed to record history: {}", e),
+                    };
+                #[cfg(feature = "index")]
+                if let Err(e) = www_search::index::add(&url, &page.title, &page.markdown).await {
+                    self.status = format!("Failed to index {}: {}", url, e);
+                }
+                self.page = Some(Page::new(url, page.markdown));
+                self.focus = Focus::Reader;
+            }
+            Err(e) => self.status = format!("Failed to browse: {}", e),
+        }
+        Ok(())
+    }
+
+    fn go_back(&mut self) {
+        match self.back.pop() {
+            Some(previous) => {
+                if let Some(current) = self.page.replace(previous) {
+                    self.forward.push(current);
+                }
+            }
+            // 履歴の先頭からさらに戻ると検索結果リストへ
+            None => self.focus = Focus::Results,
+        }
+    }
+
+    fn go_forward(&mut self) {
+        if let Some(next) = self.forward.pop()
+            && let Some(current) = self.page.replace(next)
+        {
+            self.back.push(current);
+        }
+    }
+
+    fn open_in_browser(&mut self, url: &str) {
+        self.status = match open_in_browser(url) {
+            Ok(()) => format!("Opened in browser: {}", url),
+            Err(e) => format!("Failed to open browser: {}", e),
+        };
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [query_area, main_area, status_area] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Min(1),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+        let [results_area, reader_area] =
+            Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)])
+                .areas(main_area);
+
+        let query = Paragraph::new(self.query.as_str()).block(
+            self.block(Focus::Query)
+                .title(format!(" Query ({:?}) ", self.engine)),
+        );
+        frame.render_widget(query, query_area);
+        if self.focus == Focus::Query {
+            let x = query_area.x + 1 + Line::raw(self.query.as_str()).width() as u16;
+            frame.set_cursor_position((x.min(query_area.right() - 2), query_area.y + 1));
+        }
+
+        let items: Vec<ListItem> = self
+            .results
+            .iter()
+            .map(|result| {
+                ListItem::new(vec![
+                    Line::styled(
+                        result.title.clone(),
+                        Style::new().add_modifier(Modifier::BOLD),
+                    ),
+                    Line::styled(result.url.clone(), Style::new().fg(Color::Green)),
+                ])
+            })
+            .collect();
+        let list = List::new(items)
+            .block(self.block(Focus::Results).title(" Results "))
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, results_area, &mut self.list_state);
+
+        if let Some(page) = &mut self.page {
+            page.width = reader_area.width.saturating_sub(2);
+        }
+        let reader = match &self.page {
+            Some(page) => Paragraph::new(page.render().lines)
+                .block(self.block(Focus::Reader).title(format!(" {} ", page.url)))
+                .wrap(Wrap { trim: false })
+                .scroll((page.scroll, 0)),
+            None => Paragraph::new("").block(self.block(Focus::Reader).title(" Reader ")),
+        };
+        frame.render_widget(reader, reader_area);
+
+        let help = match self.focus {
+            Focus::Query => "Enter: search  Tab: engine  Esc: results",
+            Focus::Results => "Enter: read  e: engine  /: query  o: open browser  q: quit",
+            Focus::Reader => {
+                "Tab/S-Tab: link  Enter: follow  b/f: back/forward  o: open browser  Esc: results"
+            }
+        };
+        let status = if self.status.is_empty() {
+            Line::raw(help)
+        } else {
+            Line::from(vec![
+                Span::styled(self.status.clone(), Style::new().fg(Color::Yellow)),
+                Span::raw(" | "),
+                Span::raw(help),
+            ])
+        };
+        frame.render_widget(Paragraph::new(status), status_area);
+    }
+
+    fn block(&self, focus: Focus) -> Block<'static> {
+        let style = if self.focus == focus {
+            Style::new().fg(Color::Cyan)
+        } else {
+            Style::new()
+        };
+        Block::new().borders(Borders::ALL).border_style(style)
+    }
+}
+
+impl Page {
+    fn new(url: String, markdown: String) -> Self {
+        // 番号が表示と一致するように、描画と同じ走査でリンクを集める
+        let links = render(&markdown, None)
+            .links
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+        Page {
+            url,
+            markdown,
+            links,
+            selected_link: None,
+            scroll: 0,
+            width: 0,
+        }
+    }
+
+    fn select_link(&mut self, step: isize) {
+        if self.links.is_empty() {
+            return;
+        }
+        let len = self.links.len() as isize;
+        let next = match self.selected_link {
+            Some(i) => (i as isize + step).rem_euclid(len),
+            None if step < 0 => len - 1,
+            None => 0,
+        } as usize;
+        self.selected_link = Some(next);
+        // 選択したリンクを含む行が見えるように、折り返し後の行数でスクロールする
+        let rendered = self.render();
+        if let Some(&line) = rendered.link_lines.get(next) {
+            let above = Paragraph::new(rendered.lines[..line].to_vec())
+                .wrap(Wrap { trim: false })
+                .line_count(self.width.max(1));
+            self.scroll = above.saturating_sub(3).min(u16::MAX as usize) as u16;
+        }
+    }
+
+    fn render(&self) -> Rendered<'_> {
+        render(&self.markdown, self.selected_link)
+    }
+}
+
+/// 描画用に変換したMarkdown
+struct Rendered<'a> {
+    lines: Vec<Line<'a>>,
+    /// 各リンクを含む行の番号 (リンクの出現順)
+    link_lines: Vec<usize>,
+    /// 各リンクのURL (リンクの出現順、表示する番号 - 1 の位置)
+    links: Vec<&'a str>,
+}
+
+/// Markdownを見出し・リンク・コードブロックを装飾した行に変換する
+///
+/// 見出し・リスト・段落のリンクには出現順に `[番号]` を付けます。コードブロック内はリンクとして扱いません。
+fn render(markdown: &str, selected_link: Option<usize>) -> Rendered<'_> {
+    let mut rendered = Rendered {
+        lines: Vec::new(),
+        link_lines: Vec::new(),
+        links: Vec::new(),
+    };
+    let mut in_code = false;
+    for line in markdown.lines() {
+        if line.starts_with("```") {
+            in_code = !in_code;
+            continue;
+        }
+        if in_code {
+            rendered
+                .lines
+                .push(Line::styled(line, Style::new().fg(Color::Green)));
+            continue;
+        }
+        let mut spans = Vec::new();
+        let mut text_style = Style::new();
+        let level = line.chars().take_while(|c| *c == '#').count();
+        let body = if (1..=6).contains(&level) && line[level..].starts_with(' ') {
+            text_style = text_style.fg(Color::Yellow).add_modifier(Modifier::BOLD);
+            if level == 1 {
+                text_style = text_style.add_modifier(Modifier::UNDERLINED);
+            }
+            &line[level + 1..]
+        } else {
+            match line.strip_prefix("- ") {
+                Some(rest) => {
+                    spans.push(Span::raw("  • "));
+                    rest
+                }
+                None => line,
+            }
+        };
+        for segment in split_links(body) {
+            match segment {
+                Segment::Text(text) => spans.push(Span::styled(text, text_style)),
+                Segment::Link(text, url) => {
+                    let mut style = text_style
+                        .fg(Color::Cyan)
+                        .add_modifier(Modifier::UNDERLINED);
+                    if selected_link == Some(rendered.links.len()) {
+                        style = style.add_modifier(Modifier::REVERSED);
+                    }
+                    spans.push(Span::styled(text, style));
+                    rendered.links.push(url);
+                    spans.push(Span::styled(
+                        format!("[{}]", rendered.links.len()),
+                        Style::new().fg(Color::DarkGray),
+                    ));
+                    rendered.link_lines.push(rendered.lines.len());
+                }
+            }
+        }
+        rendered.lines.push(Line::from(spans));
+    }
+    rendered
+}
+
+/// Markdownの1行をテキストとリンク (`[テキスト](<URL>)` または `[テキスト](URL)`) に分割したもの
+enum Segment<'a> {
+    Text(&'a str),
+    Link(&'a str, &'a str),
+}
+
+/// Markdownの1行をテキストとリンクに分割する
+///
+/// リンクのテキストの `[]` とURLの `()` は対応の取れた組を含められます (`[Foo](https://en.wikipedia.org/wiki/Foo_(bar))`)。
+/// リンクにならない `[` はテキストとして残します。
+fn split_links(line: &str) -> Vec<Segment<'_>> {
+    let mut segments = Vec::new();
+    let mut text_start = 0;
+    let mut pos = 0;
+    while let Some(open) = line[pos..].find('[').map(|i| pos + i) {
+        let Some((text, url, len)) = parse_link(&line[open..]) else {
+            pos = open + 1;
+            continue;
+        };
+        if open > text_start {
+            segments.push(Segment::Text(&line[text_start..open]));
+        }
+        segments.push(Segment::Link(text, url));
+        pos = open + len;
+        text_start = pos;
+    }
+    if text_start < line.len() {
+        segments.push(Segment::Text(&line[text_start..]));
+    }
+    segments
+}
+
+/// `[` で始まる文字列の先頭のリンクを解析し、(テキスト, URL, リンク全体のバイト数) を返す
+fn parse_link(s: &str) -> Option<(&str, &str, usize)> {
+    let text_end = closing(s, '[', ']')?;
+    let destination = &s[text_end + 1..];
+    if !destination.starts_with('(') {
+        return None;
+    }
+    let (url, len) = match destination[1..].strip_prefix('<') {
+        Some(inner) => {
+            let end = inner.find('>')?;
+            if !inner[end + 1..].starts_with(')') {
+                return None;
+            }
+            (&inner[..end], end + 4)
+        }
+        None => {
+            let end = closing(destination, '(', ')')?;
+            (&destination[1..end], end + 1)
+        }
+    };
+    Some((&s[1..text_end], url, text_end + 1 + len))
+}
+
+/// 先頭の開き括弧に対応する閉じ括弧の位置を返す
+fn closing(s: &str, open: char, close: char) -> Option<usize> {
+    let mut depth = 0usize;
+    for (i, c) in s.char_indices() {
+        if c == open {
+            depth += 1;
+        } else if c == close {
+            depth = depth.checked_sub(1)?;
+            if depth == 0 {
+                return Some(i);
+            }
+        }
+    }
+    None
+}
+
+/// URLをシステムの既定ブラウザで開く
+fn open_in_browser(url: &str) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    let mut command = Command::new("open");
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        let mut c = Command::new("cmd");
+        c.args(["/C", "start", ""]);
+        c
+    };
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let mut command = Command::new("xdg-open");
+    command
+        .arg(url)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn links(line: &str) -> Vec<(&str, &str)> {
+        split_links(line)
+            .into_iter()
+            .filter_map(|segment| match segment {
+                Segment::Link(text, url) => Some((text, url)),
+                Segment::Text(_) => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn splits_angle_bracket_links() {
+        assert_eq!(
+            links(
+                "see [Foo](<https://en.wikipedia.org/wiki/Foo_(bar)>) and [x](<https://x.test/>)"
+            ),
+            vec![
+                ("Foo", "https://en.wikipedia.org/wiki/Foo_(bar)"),
+                ("x", "https://x.test/")
+            ]
+        );
+    }
+
+    #[test]
+    fn balances_parentheses_and_brackets() {
+        assert_eq!(
+            links("[Foo](https://en.wikipedia.org/wiki/Foo_(bar)) [a [b] c](https://x.test/)"),
+            vec![
+                ("Foo", "https://en.wikipedia.org/wiki/Foo_(bar)"),
+                ("a [b] c", "https://x.test/")
+            ]
+        );
+    }
+
+    #[test]
+    fn numbers_links_in_headings_and_skips_code_blocks() {
+        let markdown = "## [Heading](<https://h.test/>)\n\n```\n[code](https://c.test/)\n```\n\nsee [para](<https://p.test/>)";
+        let page = Page::new("https://x.test/".to_string(), markdown.to_string());
+        assert_eq!(page.links, vec!["https://h.test/", "https://p.test/"]);
+        let rendered = page.render();
+        assert_eq!(rendered.link_lines, vec![0, 4]);
+        let text = |line: &Line| {
+            line.spans
+                .iter()
+                .map(|s| s.content.as_ref())
+                .collect::<String>()
+        };
+        assert_eq!(text(&rendered.lines[0]), "Heading[1]");
+        assert_eq!(text(&rendered.lines[2]), "[code](https://c.test/)");
+        assert_eq!(text(&rendered.lines[4]), "see para[2]");
+    }
+
+    #[test]
+    fn keeps_stray_brackets_as_text() {
+        let segments = split_links("a [ b [1] [x](<https://x.test/>) c");
+        assert!(matches!(
+            segments.as_slice(),
+            [
+                Segment::Text("a [ b [1] "),
+                Segment::Link("x", "https://x.test/"),
+                Segment::Text(" c")
+            ]
+        ));
+    }
+}